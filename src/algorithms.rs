@@ -0,0 +1,339 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+//! This module contains some graph algorithms built on the efficient
+//! decrease-key operation of [`PriorityQueue`]:
+//!  * [`dijkstra`], for single source shortest paths;
+//!  * [`astar`], for the shortest path to a goal guided by an heuristic;
+//!  * [`prim`], for the minimum spanning tree.
+//!
+//! The graph is never stored: it is described by a closure returning,
+//! for each node, an iterator over its neighbours and the weights of the
+//! edges leading to them. So the nodes may be computed on the fly, like
+//! the cells of a grid or the states of a puzzle.
+//!
+//! The module is available only when compiling with the `algorithms`
+//! feature.
+//!
+//! # Example
+//! ```rust
+//! use priority_queue::algorithms::dijkstra;
+//!
+//! // a line of 5 nodes, with a shortcut from 0 to 3
+//! let paths = dijkstra(0, |&n: &u32| {
+//!     let mut next = vec![];
+//!     if n < 4 {
+//!         next.push((n + 1, 2));
+//!     }
+//!     if n == 0 {
+//!         next.push((3, 5));
+//!     }
+//!     next
+//! });
+//!
+//! assert_eq!(paths.distance(&4), Some(&7));
+//! assert_eq!(paths.path_to(&4), Some(vec![0, 3, 4]));
+//! ```
+
+use crate::PriorityQueue;
+
+use std::cmp::{Ord, Reverse};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::iter::IntoIterator;
+use std::ops::Add;
+
+/// The result of [`dijkstra`]: the distance of every node reachable
+/// from the source and the predecessor of each of them in a shortest path.
+#[derive(Clone, Debug)]
+pub struct ShortestPaths<N, W>
+where
+    N: Hash + Eq,
+{
+    source: N,
+    distances: HashMap<N, W>,
+    predecessors: HashMap<N, N>,
+}
+
+impl<N, W> ShortestPaths<N, W>
+where
+    N: Hash + Eq + Clone,
+{
+    /// Returns the node from which the paths start
+    pub fn source(&self) -> &N {
+        &self.source
+    }
+
+    /// Returns the length of the shortest path from the source to `node`,
+    /// or `None` if `node` is not reachable
+    pub fn distance(&self, node: &N) -> Option<&W> {
+        self.distances.get(node)
+    }
+
+    /// Returns the node preceding `node` in the shortest path from the source.
+    ///
+    /// `None` is returned for the source itself and for the nodes that are
+    /// not reachable.
+    pub fn predecessor(&self, node: &N) -> Option<&N> {
+        self.predecessors.get(node)
+    }
+
+    /// Returns the nodes in the shortest path from the source to `target`,
+    /// both included, or `None` if `target` is not reachable.
+    ///
+    /// Computes in **O(L)** time, where L is the length of the path.
+    pub fn path_to(&self, target: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(target) {
+            return None;
+        }
+        Some(reconstruct(&self.predecessors, target.clone()))
+    }
+
+    /// Returns the distances of all the reachable nodes
+    pub fn distances(&self) -> &HashMap<N, W> {
+        &self.distances
+    }
+
+    /// Returns the predecessors of all the reachable nodes but the source
+    pub fn predecessors(&self) -> &HashMap<N, N> {
+        &self.predecessors
+    }
+
+    /// Returns the distances and the predecessors maps, consuming `self`
+    pub fn into_parts(self) -> (HashMap<N, W>, HashMap<N, N>) {
+        (self.distances, self.predecessors)
+    }
+}
+
+/// Computes the shortest paths from `source` to every reachable node.
+///
+/// `neighbours` is called once for each reachable node and returns the
+/// nodes it links to, together with the weights of the edges.
+/// The weights must not be negative, meaning that adding a weight to a
+/// distance must never make it smaller; `W::default()` is used as the
+/// distance of the source.
+///
+/// The frontier is a [`PriorityQueue`] of `Reverse` distances: when a
+/// shorter path to a node is found, its priority is raised in place
+/// with [`push_increase`](PriorityQueue::push_increase) instead of
+/// inserting a duplicate entry.
+///
+/// Computes in **O((V + E)log(V))** time.
+pub fn dijkstra<N, W, F, IT>(source: N, mut neighbours: F) -> ShortestPaths<N, W>
+where
+    N: Hash + Eq + Clone,
+    W: Ord + Clone + Add<Output = W> + Default,
+    F: FnMut(&N) -> IT,
+    IT: IntoIterator<Item = (N, W)>,
+{
+    let mut frontier = PriorityQueue::new();
+    let mut distances = HashMap::new();
+    let mut predecessors = HashMap::new();
+
+    frontier.push(source.clone(), Reverse(W::default()));
+    while let Some((node, Reverse(distance))) = frontier.pop() {
+        for (next, weight) in neighbours(&node) {
+            if next == node || distances.contains_key(&next) {
+                continue;
+            }
+            let candidate = distance.clone() + weight;
+            if improves(&frontier, &next, &candidate) {
+                predecessors.insert(next.clone(), node.clone());
+                frontier.push_increase(next, Reverse(candidate));
+            }
+        }
+        distances.insert(node, distance);
+    }
+
+    ShortestPaths {
+        source,
+        distances,
+        predecessors,
+    }
+}
+
+/// Finds a shortest path from `source` to a node satisfying `is_goal`,
+/// exploring first the nodes that look closer to the goal.
+///
+/// `heuristic` estimates the length of the path from a node to the
+/// goal. It must never overestimate it, and it must be consistent:
+/// the estimate for a node must not be greater than the weight of an
+/// edge leaving it plus the estimate for the node on the other end.
+/// With `|_| W::default()` this is equivalent to [`dijkstra`] stopping at
+/// the goal.
+///
+/// Returns the nodes in the path, both ends included, and its length, or
+/// `None` if no goal is reachable.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::algorithms::astar;
+///
+/// // a 10x10 grid without walls, where the manhattan distance is exact
+/// let neighbours = |&(x, y): &(i32, i32)| {
+///     [(1, 0), (-1, 0), (0, 1), (0, -1)]
+///         .iter()
+///         .map(move |(dx, dy)| ((x + dx, y + dy), 1))
+///         .filter(|((x, y), _)| (0..10).contains(x) && (0..10).contains(y))
+///         .collect::<Vec<_>>()
+/// };
+/// let (path, length) = astar(
+///     (0, 0),
+///     |n| *n == (9, 9),
+///     neighbours,
+///     |(x, y)| (9 - x) + (9 - y),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(length, 18);
+/// assert_eq!(path.len(), 19);
+/// ```
+pub fn astar<N, W, G, F, IT, E>(
+    source: N,
+    mut is_goal: G,
+    mut neighbours: F,
+    mut heuristic: E,
+) -> Option<(Vec<N>, W)>
+where
+    N: Hash + Eq + Clone,
+    W: Ord + Clone + Add<Output = W> + Default,
+    G: FnMut(&N) -> bool,
+    F: FnMut(&N) -> IT,
+    IT: IntoIterator<Item = (N, W)>,
+    E: FnMut(&N) -> W,
+{
+    let mut frontier = PriorityQueue::new();
+    let mut distances = HashMap::new();
+    let mut predecessors = HashMap::new();
+    let mut closed = HashSet::new();
+
+    let estimate = heuristic(&source);
+    distances.insert(source.clone(), W::default());
+    frontier.push(source, Reverse(estimate));
+    while let Some((node, _)) = frontier.pop() {
+        let distance = distances[&node].clone();
+        if is_goal(&node) {
+            return Some((reconstruct(&predecessors, node), distance));
+        }
+        for (next, weight) in neighbours(&node) {
+            if closed.contains(&next) {
+                continue;
+            }
+            let candidate = distance.clone() + weight;
+            if !matches!(distances.get(&next), Some(known) if *known <= candidate) {
+                let estimate = candidate.clone() + heuristic(&next);
+                distances.insert(next.clone(), candidate);
+                predecessors.insert(next.clone(), node.clone());
+                frontier.push_increase(next, Reverse(estimate));
+            }
+        }
+        closed.insert(node);
+    }
+    None
+}
+
+/// Computes a minimum spanning tree of the connected component
+/// containing `source`.
+///
+/// The graph must be undirected, meaning that if `neighbours(a)` yields
+/// `(b, w)`, then `neighbours(b)` yields `(a, w)`.
+///
+/// Returns the edges of the tree as `(parent, child, weight)` triples,
+/// in the order in which they were added: the parent of each edge is
+/// either `source` or the child of a previous edge.
+///
+/// The frontier is a [`PriorityQueue`] of the nodes not yet in the tree,
+/// whose priority is the `Reverse` of the lightest edge connecting them
+/// to the tree.
+///
+/// Computes in **O((V + E)log(V))** time.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::algorithms::prim;
+///
+/// let edges = [(0, 1, 4), (0, 2, 1), (1, 2, 2), (2, 3, 7), (1, 3, 5)];
+/// let tree = prim(0, |&n| {
+///     edges
+///         .iter()
+///         .filter_map(move |&(a, b, w)| {
+///             if n == a {
+///                 Some((b, w))
+///             } else if n == b {
+///                 Some((a, w))
+///             } else {
+///                 None
+///             }
+///         })
+/// });
+///
+/// assert_eq!(tree, vec![(0, 2, 1), (2, 1, 2), (1, 3, 5)]);
+/// ```
+pub fn prim<N, W, F, IT>(source: N, mut neighbours: F) -> Vec<(N, N, W)>
+where
+    N: Hash + Eq + Clone,
+    W: Ord + Clone + Default,
+    F: FnMut(&N) -> IT,
+    IT: IntoIterator<Item = (N, W)>,
+{
+    let mut frontier = PriorityQueue::new();
+    let mut parents = HashMap::new();
+    let mut tree = HashSet::new();
+    let mut edges = Vec::new();
+
+    frontier.push(source, Reverse(W::default()));
+    while let Some((node, Reverse(weight))) = frontier.pop() {
+        if let Some(parent) = parents.remove(&node) {
+            edges.push((parent, node.clone(), weight));
+        }
+        tree.insert(node.clone());
+        for (next, weight) in neighbours(&node) {
+            if tree.contains(&next) {
+                continue;
+            }
+            if improves(&frontier, &next, &weight) {
+                parents.insert(next.clone(), node.clone());
+                frontier.push_increase(next, Reverse(weight));
+            }
+        }
+    }
+    edges
+}
+
+/// Returns true if `cost` is lower than the one `node` has in `frontier`,
+/// or if `node` is not in `frontier` at all
+fn improves<N, W>(frontier: &PriorityQueue<N, Reverse<W>>, node: &N, cost: &W) -> bool
+where
+    N: Hash + Eq,
+    W: Ord,
+{
+    !matches!(frontier.get_priority(node), Some(Reverse(known)) if known <= cost)
+}
+
+fn reconstruct<N>(predecessors: &HashMap<N, N>, target: N) -> Vec<N>
+where
+    N: Hash + Eq + Clone,
+{
+    let mut path = vec![target];
+    while let Some(previous) = predecessors.get(path.last().unwrap()) {
+        path.push(previous.clone());
+    }
+    path.reverse();
+    path
+}
@@ -0,0 +1,92 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+//! This module defines iterator types that are used only with the [`CalendarQueue`].
+//!
+//! Usually you don't need to explicitly `use` any of the types declared here.
+
+#[cfg(not(has_std))]
+pub(crate) mod std {
+    pub use core::*;
+    pub mod alloc {
+        pub use ::alloc::*;
+    }
+    pub mod collections {
+        pub use ::alloc::collections::*;
+    }
+    pub use ::alloc::vec;
+}
+
+use std::cmp::Eq;
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::iter::*;
+
+use crate::calendar_queue::Timestamp;
+use crate::CalendarQueue;
+
+/// An iterator in arbitrary order over the couples
+/// `(item, priority)` in the queue.
+///
+/// It can be obtained calling the `iter` method.
+pub struct Iter<'a, I, T> {
+    pub(crate) iter: ::indexmap::map::Iter<'a, I, T>,
+}
+
+impl<'a, I, T> Iterator for Iter<'a, I, T> {
+    type Item = (&'a I, &'a T);
+    fn next(&mut self) -> Option<(&'a I, &'a T)> {
+        self.iter.next()
+    }
+}
+
+/// A consuming iterator over the couples `(item, priority)` of the `CalendarQueue`
+/// ordered by priority, from the lowest to the highest.
+///
+/// It can be obtained calling the `into_sorted_iter` method.
+#[cfg(has_std)]
+pub struct IntoSortedIter<I, T, H = RandomState>
+where
+    I: Hash + Eq,
+    T: Timestamp,
+{
+    pub(crate) cq: CalendarQueue<I, T, H>,
+}
+
+#[cfg(not(has_std))]
+pub struct IntoSortedIter<I, T, H>
+where
+    I: Hash + Eq,
+    T: Timestamp,
+{
+    pub(crate) cq: CalendarQueue<I, T, H>,
+}
+
+impl<I, T, H> Iterator for IntoSortedIter<I, T, H>
+where
+    I: Hash + Eq,
+    T: Timestamp,
+    H: BuildHasher,
+{
+    type Item = (I, T);
+    fn next(&mut self) -> Option<(I, T)> {
+        self.cq.pop()
+    }
+}
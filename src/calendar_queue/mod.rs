@@ -0,0 +1,617 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+//! This module contains the [`CalendarQueue`] type, the [`Timestamp`]
+//! trait of its priorities and the related iterators.
+//!
+//! See the type level documentation for more details and examples.
+
+pub mod iterators;
+
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+use iterators::*;
+
+use std::borrow::Borrow;
+use std::cmp::{Eq, PartialOrd};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::{Extend, FromIterator, IntoIterator, Iterator};
+use std::time::Duration;
+
+use indexmap::map::IndexMap;
+
+/// The number of buckets of an empty queue
+const MIN_BUCKETS: usize = 2;
+
+/// A timestamp that can be the priority of a [`CalendarQueue`].
+///
+/// The queue assigns the timestamps to the periods of the calendar by
+/// their value as `f64`, and compares them with `PartialOrd`, so the
+/// integers that are too large to be represented exactly by a `f64`
+/// may share a period, but they are still extracted in order.
+pub trait Timestamp: PartialOrd + Copy {
+    /// Returns the position of the timestamp on the time line
+    fn as_f64(self) -> f64;
+}
+
+macro_rules! impl_timestamp {
+    ($($t:ty),*) => {
+        $(
+            impl Timestamp for $t {
+                fn as_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_timestamp!(f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// The position of a `Duration` is the number of seconds
+impl Timestamp for Duration {
+    fn as_f64(self) -> f64 {
+        self.as_secs_f64()
+    }
+}
+
+/// A min-priority queue of timestamps, implementing the calendar queue
+/// by R. Brown, with the same interface of [`PriorityQueue`](crate::PriorityQueue).
+///
+/// The time line is divided in periods of the same width, like the days
+/// of a calendar, and the periods are assigned to a fixed number of buckets
+/// in round robin, like the days of the year.
+/// The items are hashed in an `IndexMap`, like in the other queues
+/// of this crate, while the buckets contain their indices.
+/// The next item is searched starting from the period of the last one
+/// extracted, looking only at the items of the current year in each bucket.
+///
+/// The number of buckets is doubled or halved as the queue grows or
+/// shrinks, and the width of the periods is recomputed to keep about
+/// three items in each of them.
+/// When the timestamps are distributed uniformly enough, pushing, popping,
+/// changing the priority of an item or removing it are performed in
+/// **O(1)** amortized time.
+///
+/// Unlike the other queues, the element with the *lowest* priority is
+/// extracted first, so it does not implement
+/// [`PriorityQueueLike`](crate::PriorityQueueLike).
+///
+/// The priority is of type T, that must implement [`Timestamp`],
+/// like the floats, the integers and `Duration`.
+///
+/// The item is of type I, that must implement `Hash` and `Eq`.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::CalendarQueue;
+///
+/// let mut cq = CalendarQueue::new();
+/// cq.push("lunch", 12.5);
+/// cq.push("meeting", 9.0);
+/// cq.push("call", 16.25);
+/// assert_eq!(cq.peek(), Some((&"meeting", &9.0)));
+///
+/// cq.change_priority("call", 8.0);
+/// assert_eq!(cq.pop(), Some(("call", 8.0)));
+/// assert_eq!(cq.remove("meeting"), Some(("meeting", 9.0)));
+/// assert_eq!(cq.pop(), Some(("lunch", 12.5)));
+/// ```
+#[derive(Clone)]
+#[cfg(has_std)]
+pub struct CalendarQueue<I, T, H = RandomState>
+where
+    I: Hash + Eq,
+    T: Timestamp,
+{
+    map: IndexMap<I, T, H>,
+    // the buckets of the calendar, holding the indices of the items in the map
+    buckets: Vec<Vec<usize>>,
+    // the position of each item, by index in the map, inside its bucket
+    positions: Vec<usize>,
+    width: f64,
+    // the period where the search of the next item starts:
+    // no item lies in a period before it
+    current: i64,
+}
+
+#[derive(Clone)]
+#[cfg(not(has_std))]
+pub struct CalendarQueue<I, T, H>
+where
+    I: Hash + Eq,
+    T: Timestamp,
+{
+    map: IndexMap<I, T, H>,
+    // the buckets of the calendar, holding the indices of the items in the map
+    buckets: Vec<Vec<usize>>,
+    // the position of each item, by index in the map, inside its bucket
+    positions: Vec<usize>,
+    width: f64,
+    // the period where the search of the next item starts:
+    // no item lies in a period before it
+    current: i64,
+}
+
+impl<I, T, H> Default for CalendarQueue<I, T, H>
+where
+    I: Hash + Eq,
+    T: Timestamp,
+    H: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+#[cfg(has_std)]
+impl<I, T> CalendarQueue<I, T>
+where
+    I: Hash + Eq,
+    T: Timestamp,
+{
+    /// Creates an empty `CalendarQueue`
+    pub fn new() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+impl<I, T, H> CalendarQueue<I, T, H>
+where
+    I: Hash + Eq,
+    T: Timestamp,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty `CalendarQueue` with the default hasher
+    pub fn with_default_hasher() -> Self {
+        Self::with_hasher(H::default())
+    }
+}
+
+impl<I, T, H> CalendarQueue<I, T, H>
+where
+    I: Hash + Eq,
+    T: Timestamp,
+    H: BuildHasher,
+{
+    /// Creates an empty `CalendarQueue` with the specified hasher
+    pub fn with_hasher(hash_builder: H) -> Self {
+        let mut buckets = Vec::new();
+        buckets.resize_with(MIN_BUCKETS, Vec::new);
+        Self {
+            map: IndexMap::with_hasher(hash_builder),
+            buckets,
+            positions: Vec::new(),
+            width: 1.0,
+            current: 0,
+        }
+    }
+
+    /// Returns the number of elements in the queue
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if the queue contains no elements
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns an iterator in arbitrary order over the
+    /// (item, priority) elements in the queue
+    pub fn iter(&self) -> Iter<'_, I, T> {
+        Iter {
+            iter: self.map.iter(),
+        }
+    }
+
+    /// Drops all the elements
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.positions.clear();
+        self.buckets.truncate(MIN_BUCKETS);
+        self.buckets.iter_mut().for_each(Vec::clear);
+        self.current = 0;
+    }
+
+    /// Returns the couple (item, priority) with the lowest
+    /// priority in the queue, or None if it is empty.
+    ///
+    /// Computes in **O(1)** amortized time.
+    pub fn peek(&self) -> Option<(&I, &T)> {
+        self.find_min()
+            .map(|(index, _)| self.map.get_index(index).unwrap())
+    }
+
+    /// Removes the item with the lowest priority from
+    /// the queue and returns the pair (item, priority),
+    /// or None if the queue is empty.
+    ///
+    /// Computes in **O(1)** amortized time.
+    pub fn pop(&mut self) -> Option<(I, T)> {
+        let (index, period) = self.find_min()?;
+        self.current = period;
+        let popped = self.swap_remove(index);
+        self.resize_if_needed();
+        Some(popped)
+    }
+
+    /// Insert the item-priority pair into the queue.
+    ///
+    /// If an element equal to `item` was already into the queue,
+    /// it is updated and the old value of its priority returned in `Some`;
+    /// otherwise, returns `None`.
+    ///
+    /// Computes in **O(1)** amortized time.
+    ///
+    /// # Panics
+    /// Panics if `priority` converts to NaN
+    pub fn push(&mut self, item: I, priority: T) -> Option<T> {
+        checked(priority);
+        if let Some(index) = self.map.get_index_of(&item) {
+            return Some(self.move_to(index, priority));
+        }
+        let index = self.map.len();
+        self.map.insert(item, priority);
+        self.positions.push(0);
+        self.link(index);
+        self.resize_if_needed();
+        None
+    }
+
+    /// Increase the priority of an existing item in the queue, or
+    /// insert it if not present.
+    ///
+    /// If an element equal to `item` is already in the queue with a
+    /// lower priority, its priority is increased to the new one,
+    /// postponing it, and the old priority is returned.
+    /// Otherwise, the new element is inserted into the queue.
+    ///
+    /// Returns `Some` if an element equal to `item` is already in the
+    /// queue. If its priority is higher than `priority`, the latter is returned back,
+    /// otherwise, the old priority is contained in the Option.
+    /// If the item is not in the queue, `None` is returned.
+    ///
+    /// Computes in **O(1)** amortized time.
+    ///
+    /// # Panics
+    /// Panics if `priority` converts to NaN
+    pub fn push_increase(&mut self, item: I, priority: T) -> Option<T> {
+        checked(priority);
+        match self.get_priority(&item) {
+            Some(old) if priority <= *old => Some(priority),
+            _ => self.push(item, priority),
+        }
+    }
+
+    /// Decrease the priority of an existing item in the queue, or
+    /// insert it if not present.
+    ///
+    /// If an element equal to `item` is already in the queue with a
+    /// higher priority, its priority is decreased to the new one,
+    /// bringing it forward, and the old priority is returned.
+    /// Otherwise, the new element is inserted into the queue.
+    ///
+    /// Returns `Some` if an element equal to `item` is already in the
+    /// queue. If its priority is lower than `priority`, the latter is returned back,
+    /// otherwise, the old priority is contained in the Option.
+    /// If the item is not in the queue, `None` is returned.
+    ///
+    /// Computes in **O(1)** amortized time.
+    ///
+    /// # Panics
+    /// Panics if `priority` converts to NaN
+    pub fn push_decrease(&mut self, item: I, priority: T) -> Option<T> {
+        checked(priority);
+        match self.get_priority(&item) {
+            Some(old) if priority >= *old => Some(priority),
+            _ => self.push(item, priority),
+        }
+    }
+
+    /// Changes the priority of `item` and returns the old one,
+    /// or `None` if the item is not in the queue.
+    ///
+    /// Computes in **O(1)** amortized time.
+    ///
+    /// # Panics
+    /// Panics if `new_priority` converts to NaN
+    pub fn change_priority<Q>(&mut self, item: &Q, new_priority: T) -> Option<T>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let index = self.map.get_index_of(item)?;
+        Some(self.move_to(index, new_priority))
+    }
+
+    /// Changes the priority of `item` using the provided function.
+    ///
+    /// Computes in **O(1)** amortized time.
+    ///
+    /// # Panics
+    /// Panics if the new priority converts to NaN
+    pub fn change_priority_by<Q, F>(&mut self, item: &Q, priority_setter: F)
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&mut T),
+    {
+        if let Some(index) = self.map.get_index_of(item) {
+            let mut priority = self.time(index);
+            priority_setter(&mut priority);
+            self.move_to(index, priority);
+        }
+    }
+
+    /// Returns the couple (item, priority) of the element equal to `item`,
+    /// if it is in the queue
+    pub fn get<Q>(&self, item: &Q) -> Option<(&I, &T)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.get_key_value(item)
+    }
+
+    /// Returns the priority of `item`, if it is in the queue
+    pub fn get_priority<Q>(&self, item: &Q) -> Option<&T>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.get(item)
+    }
+
+    /// Removes an arbitrary element from the queue and
+    /// returns it with its priority, if present.
+    ///
+    /// Computes in **O(1)** amortized time.
+    pub fn remove<Q>(&mut self, item: &Q) -> Option<(I, T)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let index = self.map.get_index_of(item)?;
+        let removed = self.swap_remove(index);
+        self.resize_if_needed();
+        Some(removed)
+    }
+
+    /// Generates a new iterator from self that
+    /// will extract the elements from the one with the lowest priority
+    /// to the highest one.
+    pub fn into_sorted_iter(self) -> IntoSortedIter<I, T, H> {
+        IntoSortedIter { cq: self }
+    }
+
+    /// Returns the items sorted from the one with the lowest priority
+    /// to the one with the highest.
+    pub fn into_sorted_vec(mut self) -> Vec<I> {
+        let mut res = Vec::with_capacity(self.len());
+        while let Some((i, _)) = self.pop() {
+            res.push(i);
+        }
+        res
+    }
+
+    /// Returns the period of the time line containing `time`
+    fn period(&self, time: T) -> i64 {
+        let time = time.as_f64();
+        assert!(
+            !time.is_nan(),
+            "the priority of a calendar queue cannot be NaN"
+        );
+        floor(time / self.width)
+    }
+
+    fn bucket(&self, period: i64) -> usize {
+        period.rem_euclid(self.buckets.len() as i64) as usize
+    }
+
+    fn time(&self, index: usize) -> T {
+        *self.map.get_index(index).unwrap().1
+    }
+
+    /// Returns the index in the map of the element with the lowest priority
+    /// and its period
+    fn find_min(&self) -> Option<(usize, i64)> {
+        if self.map.is_empty() {
+            return None;
+        }
+        for period in self.current..self.current.saturating_add(self.buckets.len() as i64) {
+            let mut min: Option<(usize, T)> = None;
+            for &index in &self.buckets[self.bucket(period)] {
+                let time = self.time(index);
+                // the items of the following years are skipped
+                if self.period(time) == period && !matches!(min, Some((_, m)) if m <= time) {
+                    min = Some((index, time));
+                }
+            }
+            if let Some((index, _)) = min {
+                return Some((index, period));
+            }
+        }
+        // no item in the next year: fall back to a direct search
+        let (index, _) = (0..self.map.len())
+            .map(|index| (index, self.time(index)))
+            .fold(None, |min: Option<(usize, T)>, (index, time)| match min {
+                Some((_, m)) if m <= time => min,
+                _ => Some((index, time)),
+            })?;
+        Some((index, self.period(self.time(index))))
+    }
+
+    /// Adds the element at `index` of the map to the bucket of its priority
+    fn link(&mut self, index: usize) {
+        let period = self.period(self.time(index));
+        self.current = self.current.min(period);
+        let bucket = self.bucket(period);
+        self.positions[index] = self.buckets[bucket].len();
+        self.buckets[bucket].push(index);
+    }
+
+    /// Removes the element at `index` of the map from its bucket
+    fn unlink(&mut self, index: usize) {
+        let bucket = self.bucket(self.period(self.time(index)));
+        let position = self.positions[index];
+        self.buckets[bucket].swap_remove(position);
+        if let Some(&moved) = self.buckets[bucket].get(position) {
+            self.positions[moved] = position;
+        }
+    }
+
+    fn move_to(&mut self, index: usize, priority: T) -> T {
+        checked(priority);
+        self.unlink(index);
+        let old = std::mem::replace(self.map.get_index_mut(index).unwrap().1, priority);
+        self.link(index);
+        old
+    }
+
+    /// Removes the element at `index` of the map, moving the last
+    /// element in its place
+    fn swap_remove(&mut self, index: usize) -> (I, T) {
+        self.unlink(index);
+        let removed = self.map.swap_remove_index(index).unwrap();
+        self.positions.swap_remove(index);
+        if index < self.map.len() {
+            let bucket = self.bucket(self.period(self.time(index)));
+            self.buckets[bucket][self.positions[index]] = index;
+        }
+        removed
+    }
+
+    /// Doubles or halves the buckets when there are more than two items
+    /// or less than half an item per bucket
+    fn resize_if_needed(&mut self) {
+        let (len, buckets) = (self.map.len(), self.buckets.len());
+        if len > 2 * buckets {
+            self.resize(2 * buckets);
+        } else if buckets > MIN_BUCKETS && 2 * len < buckets {
+            self.resize(buckets / 2);
+        }
+    }
+
+    /// Redistributes the elements in `buckets` buckets, recomputing the
+    /// width of the periods from the average distance between the priorities
+    fn resize(&mut self, buckets: usize) {
+        let times = self.map.values().map(|t| t.as_f64());
+        let (min, max) = times.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), t| {
+            (min.min(t), max.max(t))
+        });
+        let width = 3.0 * (max - min) / self.map.len() as f64;
+        if width > 0.0 && width.is_finite() {
+            self.width = width;
+        }
+
+        self.buckets.iter_mut().for_each(Vec::clear);
+        self.buckets.resize_with(buckets, Vec::new);
+        self.current = i64::MAX;
+        for index in 0..self.map.len() {
+            self.link(index);
+        }
+        if self.map.is_empty() {
+            self.current = 0;
+        }
+    }
+}
+
+/// Returns the greatest integer less than or equal to `x`, saturating
+/// at the bounds of `i64`, since `f64::floor` needs the standard library
+fn floor(x: f64) -> i64 {
+    let truncated = x as i64;
+    if truncated as f64 > x {
+        truncated - 1
+    } else {
+        truncated
+    }
+}
+
+/// Returns the position of `time`, checking that it is not NaN
+fn checked<T>(time: T) -> f64
+where
+    T: Timestamp,
+{
+    let time = time.as_f64();
+    assert!(
+        !time.is_nan(),
+        "the priority of a calendar queue cannot be NaN"
+    );
+    time
+}
+
+impl<I, T, H> FromIterator<(I, T)> for CalendarQueue<I, T, H>
+where
+    I: Hash + Eq,
+    T: Timestamp,
+    H: BuildHasher + Default,
+{
+    fn from_iter<IT>(iter: IT) -> Self
+    where
+        IT: IntoIterator<Item = (I, T)>,
+    {
+        let mut cq = Self::with_default_hasher();
+        cq.extend(iter);
+        cq
+    }
+}
+
+impl<I, T, H> Extend<(I, T)> for CalendarQueue<I, T, H>
+where
+    I: Hash + Eq,
+    T: Timestamp,
+    H: BuildHasher,
+{
+    fn extend<IT>(&mut self, iter: IT)
+    where
+        IT: IntoIterator<Item = (I, T)>,
+    {
+        for (item, priority) in iter {
+            self.push(item, priority);
+        }
+    }
+}
+
+impl<'a, I, T, H> IntoIterator for &'a CalendarQueue<I, T, H>
+where
+    I: Hash + Eq,
+    T: Timestamp,
+    H: BuildHasher,
+{
+    type Item = (&'a I, &'a T);
+    type IntoIter = Iter<'a, I, T>;
+    fn into_iter(self) -> Iter<'a, I, T> {
+        self.iter()
+    }
+}
+
+impl<I, T, H> fmt::Debug for CalendarQueue<I, T, H>
+where
+    I: Hash + Eq + fmt::Debug,
+    T: Timestamp + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.map.iter()).finish()
+    }
+}
@@ -36,11 +36,13 @@ pub(crate) mod std {
 use std::cmp::{Eq, Ord};
 #[cfg(has_std)]
 use std::collections::hash_map::RandomState;
-use std::hash::Hash;
+use std::hash::{BuildHasher, Hash};
 use std::iter::*;
 
 use crate::DoublePriorityQueue;
 
+use indexmap::map::MutableKeys;
+
 /// A mutable iterator over the couples `(item, priority)` of the `DoublePriorityQueue`
 /// in arbitrary order.
 ///
@@ -86,6 +88,7 @@ impl<'a, 'b: 'a, I: 'a, P: 'a, H: 'a> Iterator for IterMut<'a, I, P, H>
 where
     I: Hash + Eq,
     P: Ord,
+    H: BuildHasher,
 {
     type Item = (&'a mut I, &'a mut P);
     fn next(&mut self) -> Option<Self::Item> {
@@ -93,7 +96,7 @@ where
             .pq
             .store
             .map
-            .get_index_mut(self.pos)
+            .get_index_mut2(self.pos)
             .map(|(i, p)| (i as *mut I, p as *mut P))
             .map(|(i, p)| unsafe { (i.as_mut().unwrap(), p.as_mut().unwrap()) });
         self.pos += 1;
@@ -107,6 +110,7 @@ where
     P: Ord,
 {
     fn drop(&mut self) {
+        self.pq.store.mark_all_unchecked();
         self.pq.heap_build();
     }
 }
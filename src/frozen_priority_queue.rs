@@ -0,0 +1,366 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+//! This module contains the [`FrozenPriorityQueue`] type.
+//!
+//! See the type level documentation for more details and examples.
+
+#[cfg(not(has_std))]
+use crate::std;
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+use crate::core_iterators::{IntoIter, Iter};
+use crate::store::Store;
+use crate::{DoublePriorityQueue, PriorityQueue};
+
+use std::borrow::Borrow;
+use std::cmp::{Eq, Ord};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::iter::{IntoIterator, Iterator};
+use std::ops::{Bound, RangeBounds};
+
+use indexmap::map::IndexMap;
+
+/// An immutable snapshot of a priority queue, with the elements
+/// sorted from the greatest priority to the lowest.
+///
+/// It is obtained converting a [`PriorityQueue`] or a [`DoublePriorityQueue`]
+/// with `From`/`Into`, sorting the elements once in **O(N log(N))** time.
+/// The elements are kept in the same `IndexMap` of the queue, reordered,
+/// so that the position of an element in the map is its position in the
+/// sorted order, and the hash table is never rebuilt by rehashing the items.
+///
+/// Looking up the priority or the rank of an item takes **O(1)** time,
+/// while queries on a range of priorities are answered with a binary
+/// search in **O(log(N))** time.
+///
+/// The snapshot can be converted back into a `PriorityQueue` or a
+/// `DoublePriorityQueue`, reusing the same map.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::{FrozenPriorityQueue, PriorityQueue};
+///
+/// let mut pq = PriorityQueue::new();
+/// pq.push("Apples", 5);
+/// pq.push("Bananas", 8);
+/// pq.push("Strawberries", 23);
+///
+/// let frozen = FrozenPriorityQueue::from(pq);
+/// assert_eq!(frozen.rank(&"Apples"), Some(2));
+/// assert_eq!(frozen.get_priority(&"Bananas"), Some(&8));
+/// assert_eq!(
+///     frozen.range(5..10).collect::<Vec<_>>(),
+///     vec![(&"Bananas", &8), (&"Apples", &5)]
+/// );
+///
+/// let mut pq: PriorityQueue<_, _> = frozen.into();
+/// assert_eq!(pq.pop(), Some(("Strawberries", 23)));
+/// ```
+#[derive(Clone)]
+#[cfg(has_std)]
+pub struct FrozenPriorityQueue<I, P, H = RandomState>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    map: IndexMap<I, P, H>,
+}
+
+#[derive(Clone)]
+#[cfg(not(has_std))]
+pub struct FrozenPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    map: IndexMap<I, P, H>,
+}
+
+impl<I, P, H> FrozenPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    /// Get the priority of an item, or `None`, if the item is not in the queue
+    ///
+    /// Computes in **O(1)** time
+    pub fn get_priority<Q>(&self, item: &Q) -> Option<&P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.get(item)
+    }
+
+    /// Get the couple (item, priority) of an arbitrary element, as reference
+    /// or `None` if the item is not in the queue.
+    ///
+    /// Computes in **O(1)** time
+    pub fn get<Q>(&self, item: &Q) -> Option<(&I, &P)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.get_key_value(item)
+    }
+
+    /// Returns true if the item is in the queue
+    pub fn contains<Q>(&self, item: &Q) -> bool
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.contains_key(item)
+    }
+
+    /// Returns the position of the item in the sorted order, starting
+    /// from 0 for the greatest priority, or `None` if the item is not in the queue.
+    ///
+    /// The items with the same priority occupy consecutive positions,
+    /// in an arbitrary but fixed order.
+    ///
+    /// Computes in **O(1)** time
+    pub fn rank<Q>(&self, item: &Q) -> Option<usize>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.get_index_of(item)
+    }
+
+    /**************************************************************************/
+    /*                            internal functions                          */
+
+    /// Sorts the elements of the map from the greatest priority to the lowest
+    fn from_map(mut map: IndexMap<I, P, H>) -> Self {
+        map.sort_by(|_, p1, _, p2| p2.cmp(p1));
+        Self { map }
+    }
+}
+
+impl<I, P, H> FrozenPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    /// Returns an iterator over the (item, priority) elements
+    /// in the queue, from the greatest priority to the lowest
+    pub fn iter(&self) -> Iter<'_, I, P> {
+        Iter {
+            iter: self.map.iter(),
+        }
+    }
+
+    /// Returns the couple (item, priority) in position `n` in the sorted order,
+    /// starting from 0 for the greatest priority, or `None` if `n` is out of bounds.
+    ///
+    /// Computes in **O(1)** time
+    pub fn nth(&self, n: usize) -> Option<(&I, &P)> {
+        self.map.get_index(n)
+    }
+
+    /// Returns the couple (item, priority) with the greatest
+    /// priority in the queue, or None if it is empty.
+    ///
+    /// Computes in **O(1)** time
+    pub fn peek(&self) -> Option<(&I, &P)> {
+        self.map.first()
+    }
+
+    /// Returns the couple (item, priority) with the lowest
+    /// priority in the queue, or None if it is empty.
+    ///
+    /// Computes in **O(1)** time
+    pub fn peek_min(&self) -> Option<(&I, &P)> {
+        self.map.last()
+    }
+
+    /// Returns an iterator over the (item, priority) elements whose priority
+    /// is contained in `range`, from the greatest priority to the lowest.
+    ///
+    /// The bounds of the range are found in **O(log(N))** time
+    pub fn range<R>(&self, range: R) -> impl Iterator<Item = (&I, &P)> + '_
+    where
+        R: RangeBounds<P>,
+    {
+        let (start, end) = self.bounds(range);
+        (start..end).map(move |i| self.map.get_index(i).unwrap())
+    }
+
+    /// Returns the number of elements whose priority is contained in `range`.
+    ///
+    /// Computes in **O(log(N))** time
+    pub fn count_in_range<R>(&self, range: R) -> usize
+    where
+        R: RangeBounds<P>,
+    {
+        let (start, end) = self.bounds(range);
+        end.saturating_sub(start)
+    }
+
+    /// Returns the number of elements in the queue.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if the queue contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns a `Vec<I>` sorted from the item associated to the highest priority to the lowest.
+    pub fn into_sorted_vec(self) -> Vec<I> {
+        self.map.into_iter().map(|(i, _)| i).collect()
+    }
+
+    /**************************************************************************/
+    /*                            internal functions                          */
+
+    /// Returns the positions delimiting the elements whose priority is in `range`
+    fn bounds<R>(&self, range: R) -> (usize, usize)
+    where
+        R: RangeBounds<P>,
+    {
+        // the elements above the range come first
+        let start = match range.end_bound() {
+            Bound::Included(e) => self.partition_point(|p| p > e),
+            Bound::Excluded(e) => self.partition_point(|p| p >= e),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.start_bound() {
+            Bound::Included(s) => self.partition_point(|p| p >= s),
+            Bound::Excluded(s) => self.partition_point(|p| p > s),
+            Bound::Unbounded => self.len(),
+        };
+        (start, end)
+    }
+
+    /// Returns the position of the first element whose priority does not satisfy
+    /// `pred`, given that the ones satisfying it come first
+    fn partition_point<F>(&self, pred: F) -> usize
+    where
+        F: Fn(&P) -> bool,
+    {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if pred(self.map.get_index(mid).unwrap().1) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+}
+
+impl<I, P, H> From<PriorityQueue<I, P, H>> for FrozenPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    fn from(pq: PriorityQueue<I, P, H>) -> Self {
+        Self::from_map(pq.store.map)
+    }
+}
+
+impl<I, P, H> From<DoublePriorityQueue<I, P, H>> for FrozenPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    fn from(pq: DoublePriorityQueue<I, P, H>) -> Self {
+        Self::from_map(pq.store.map)
+    }
+}
+
+impl<I, P, H> From<FrozenPriorityQueue<I, P, H>> for PriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    fn from(frozen: FrozenPriorityQueue<I, P, H>) -> Self {
+        // the elements sorted from the greatest are already a valid heap
+        PriorityQueue {
+            store: Store::from(frozen.map),
+        }
+    }
+}
+
+impl<I, P, H> From<FrozenPriorityQueue<I, P, H>> for DoublePriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    fn from(frozen: FrozenPriorityQueue<I, P, H>) -> Self {
+        let mut pq = DoublePriorityQueue {
+            store: Store::from(frozen.map),
+        };
+        pq.heap_build();
+        pq
+    }
+}
+
+impl<I, P, H> IntoIterator for FrozenPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    type Item = (I, P);
+    type IntoIter = IntoIter<I, P>;
+    fn into_iter(self) -> IntoIter<I, P> {
+        IntoIter {
+            iter: self.map.into_iter(),
+        }
+    }
+}
+
+impl<'a, I, P, H> IntoIterator for &'a FrozenPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    type Item = (&'a I, &'a P);
+    type IntoIter = Iter<'a, I, P>;
+    fn into_iter(self) -> Iter<'a, I, P> {
+        self.iter()
+    }
+}
+
+use std::fmt;
+
+impl<I, P, H> fmt::Debug for FrozenPriorityQueue<I, P, H>
+where
+    I: Hash + Eq + fmt::Debug,
+    P: Ord + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.map.iter()).finish()
+    }
+}
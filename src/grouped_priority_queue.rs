@@ -0,0 +1,506 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+//! This module contains the [`GroupedPriorityQueue`] type.
+//!
+//! See the type level documentation for more details and examples.
+
+#[cfg(not(has_std))]
+use crate::std;
+
+use crate::index_heap::IndexHeap;
+use crate::PriorityQueue;
+
+use std::borrow::Borrow;
+use std::cmp::{Eq, Ord};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::iter::Iterator;
+use std::mem::replace;
+
+use indexmap::map::IndexMap;
+
+/// A group of items of a [`GroupedPriorityQueue`] with its own priority.
+#[derive(Clone)]
+struct Group<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    priority: P,
+    queue: PriorityQueue<I, P, H>,
+}
+
+/// A priority queue whose items are partitioned in groups,
+/// each one with its own priority.
+///
+/// Every group `G` holds a [`PriorityQueue`] of its items. The groups
+/// are kept in a heap ordered by their own priority and then by the
+/// priority of their top item, so the global top is the greatest item of
+/// the group with the greatest priority. Empty groups are always at the bottom.
+///
+/// The same item may belong to more than one group: item level methods take
+/// both the group and the item to identify an element.
+///
+/// Group priorities and item priorities share the same type `P`.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::GroupedPriorityQueue;
+///
+/// let mut pq = GroupedPriorityQueue::new();
+///
+/// pq.insert_group("free tier", 0);
+/// pq.insert_group("premium", 10);
+/// pq.push("free tier", "resize image", 8);
+/// pq.push("premium", "send invoice", 1);
+/// pq.push("premium", "backup", 5);
+///
+/// assert_eq!(pq.peek(), Some((&"premium", &"backup", &5)));
+/// assert_eq!(pq.peek_group(&"free tier"), Some((&"resize image", &8)));
+///
+/// pq.change_group_priority(&"free tier", 20);
+/// assert_eq!(pq.pop(), Some(("free tier", "resize image", 8)));
+/// assert_eq!(pq.pop(), Some(("premium", "backup", 5)));
+/// ```
+#[derive(Clone)]
+#[cfg(has_std)]
+pub struct GroupedPriorityQueue<G, I, P, H = RandomState>
+where
+    G: Hash + Eq,
+    I: Hash + Eq,
+    P: Ord,
+{
+    groups: IndexMap<G, Group<I, P, H>, H>,
+    heads: IndexHeap,
+    size: usize,
+}
+
+#[derive(Clone)]
+#[cfg(not(has_std))]
+pub struct GroupedPriorityQueue<G, I, P, H>
+where
+    G: Hash + Eq,
+    I: Hash + Eq,
+    P: Ord,
+{
+    groups: IndexMap<G, Group<I, P, H>, H>,
+    heads: IndexHeap,
+    size: usize,
+}
+
+impl<G, I, P, H> Default for GroupedPriorityQueue<G, I, P, H>
+where
+    G: Hash + Eq,
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+#[cfg(has_std)]
+impl<G, I, P> GroupedPriorityQueue<G, I, P>
+where
+    G: Hash + Eq,
+    I: Hash + Eq,
+    P: Ord,
+{
+    /// Creates an empty `GroupedPriorityQueue`
+    pub fn new() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+impl<G, I, P, H> GroupedPriorityQueue<G, I, P, H>
+where
+    G: Hash + Eq,
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty `GroupedPriorityQueue` with the default hasher
+    pub fn with_default_hasher() -> Self {
+        Self::with_hasher(H::default())
+    }
+}
+
+impl<G, I, P, H> GroupedPriorityQueue<G, I, P, H>
+where
+    G: Hash + Eq,
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    /// Creates an empty `GroupedPriorityQueue` with the specified hasher.
+    ///
+    /// The queues of the groups use a clone of the same hasher.
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self {
+            groups: IndexMap::with_hasher(hash_builder),
+            heads: IndexHeap::with_capacity(0),
+            size: 0,
+        }
+    }
+}
+
+impl<G, I, P, H> GroupedPriorityQueue<G, I, P, H>
+where
+    G: Hash + Eq,
+    I: Hash + Eq,
+    P: Ord,
+{
+    /// Returns the triple (group, item, priority) of the greatest item
+    /// of the group with the greatest priority, or None if there are no items.
+    ///
+    /// Computes in **O(1)** time
+    pub fn peek(&self) -> Option<(&G, &I, &P)> {
+        let (g, group) = self.groups.get_index(*self.heads.heap.first()?)?;
+        group.queue.peek().map(|(i, p)| (g, i, p))
+    }
+
+    /// Returns the total number of items in all the groups.
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if there are no items in the queue.
+    ///
+    /// There could still be some empty groups.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns the number of groups, including the empty ones.
+    pub fn groups_len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Returns an iterator in arbitrary order over the groups
+    /// and their priorities.
+    pub fn groups(&self) -> impl Iterator<Item = (&G, &P)> {
+        self.groups.iter().map(|(g, group)| (g, &group.priority))
+    }
+}
+
+impl<G, I, P, H> GroupedPriorityQueue<G, I, P, H>
+where
+    G: Hash + Eq,
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Clone,
+{
+    /// Insert a new empty group with the given priority.
+    ///
+    /// If the group already exists, its priority is changed and the old
+    /// one returned in `Some`; otherwise, returns `None`.
+    ///
+    /// Computes in **O(log(G))** time, where G is the number of groups.
+    pub fn insert_group(&mut self, group: G, priority: P) -> Option<P> {
+        if let Some(index) = self.groups.get_index_of(&group) {
+            let old = replace(&mut self.groups[index].priority, priority);
+            self.fix_head(index);
+            return Some(old);
+        }
+        self.new_group(group, priority);
+        None
+    }
+
+    /// Insert the item-priority pair into the given group.
+    ///
+    /// If the group does not exist, it is created with the default priority.
+    ///
+    /// If an element equal to `item` was already into the group,
+    /// it is updated and the old value of its priority returned in `Some`;
+    /// otherwise, returns `None`.
+    ///
+    /// Computes in **O(log(N) + log(G))** time.
+    pub fn push(&mut self, group: G, item: I, priority: P) -> Option<P>
+    where
+        P: Default,
+    {
+        let index = match self.groups.get_index_of(&group) {
+            Some(index) => index,
+            None => self.new_group(group, P::default()),
+        };
+        let old = self.groups[index].queue.push(item, priority);
+        if old.is_none() {
+            self.size += 1;
+        }
+        self.fix_head(index);
+        old
+    }
+
+    /**************************************************************************/
+    /*                            internal functions                          */
+
+    /// Adds a new empty group, returning its index
+    fn new_group(&mut self, group: G, priority: P) -> usize {
+        let queue = PriorityQueue::with_hasher(self.groups.hasher().clone());
+        let (index, _) = self.groups.insert_full(group, Group { priority, queue });
+        let GroupedPriorityQueue { groups, heads, .. } = self;
+        heads.push(&greater_group(groups));
+        index
+    }
+}
+
+impl<G, I, P, H> GroupedPriorityQueue<G, I, P, H>
+where
+    G: Hash + Eq,
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    /// Returns an iterator in arbitrary order over the
+    /// (group, item, priority) elements in the queue
+    pub fn iter(&self) -> impl Iterator<Item = (&G, &I, &P)> {
+        self.groups
+            .iter()
+            .flat_map(|(g, group)| group.queue.iter().map(move |(i, p)| (g, i, p)))
+    }
+
+    /// Removes the greatest item of the group with the greatest priority
+    /// and returns the triple (group, item, priority), or None if there are no items.
+    ///
+    /// The group is not removed, even if it becomes empty.
+    ///
+    /// Computes in **O(log(N) + log(G))** time.
+    pub fn pop(&mut self) -> Option<(G, I, P)>
+    where
+        G: Clone,
+    {
+        let index = *self.heads.heap.first()?;
+        let (g, group) = self.groups.get_index_mut(index)?;
+        let (i, p) = group.queue.pop()?;
+        let g = g.clone();
+        self.size -= 1;
+        self.fix_head(index);
+        Some((g, i, p))
+    }
+
+    /// Returns the couple (item, priority) with the greatest
+    /// priority in the given group, or None if the group is empty or missing.
+    ///
+    /// Computes in **O(1)** time
+    pub fn peek_group<Q>(&self, group: &Q) -> Option<(&I, &P)>
+    where
+        G: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.groups.get(group)?.queue.peek()
+    }
+
+    /// Removes the item with the greatest priority from the given group,
+    /// or returns None if the group is empty or missing.
+    ///
+    /// Computes in **O(log(N) + log(G))** time.
+    pub fn pop_group<Q>(&mut self, group: &Q) -> Option<(I, P)>
+    where
+        G: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let (index, _, g) = self.groups.get_full_mut(group)?;
+        let r = g.queue.pop()?;
+        self.size -= 1;
+        self.fix_head(index);
+        Some(r)
+    }
+
+    /// Returns a reference to the queue of the items of a group,
+    /// or None if the group does not exist.
+    pub fn group<Q>(&self, group: &Q) -> Option<&PriorityQueue<I, P, H>>
+    where
+        G: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.groups.get(group).map(|g| &g.queue)
+    }
+
+    /// Get the priority of a group, or `None`, if the group does not exist
+    pub fn get_group_priority<Q>(&self, group: &Q) -> Option<&P>
+    where
+        G: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.groups.get(group).map(|g| &g.priority)
+    }
+
+    /// Change the priority of a group returning the old value of priority,
+    /// or `None` if the group does not exist.
+    ///
+    /// The group level priority takes precedence over the priorities of
+    /// the items when looking for the global top.
+    ///
+    /// Computes in **O(log(G))** time.
+    pub fn change_group_priority<Q>(&mut self, group: &Q, new_priority: P) -> Option<P>
+    where
+        G: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let (index, _, g) = self.groups.get_full_mut(group)?;
+        let old = replace(&mut g.priority, new_priority);
+        self.fix_head(index);
+        Some(old)
+    }
+
+    /// Remove a group with all its items.
+    /// Returns the group, its priority and its queue, or `None`
+    /// if the group does not exist.
+    ///
+    /// Computes in **O(log(G))** time.
+    pub fn remove_group<Q>(&mut self, group: &Q) -> Option<(G, P, PriorityQueue<I, P, H>)>
+    where
+        G: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let (index, g, group) = self.groups.swap_remove_full(group)?;
+        self.size -= group.queue.len();
+        let GroupedPriorityQueue { groups, heads, .. } = self;
+        heads.swap_remove(index, &greater_group(groups));
+        Some((g, group.priority, group.queue))
+    }
+
+    /// Change the priority of an item of a group returning the old value of priority,
+    /// or `None` if the item wasn't in the group.
+    ///
+    /// Computes in **O(log(N) + log(G))** time.
+    pub fn change_priority<Q, R>(&mut self, group: &Q, item: &R, new_priority: P) -> Option<P>
+    where
+        G: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        I: Borrow<R>,
+        R: ?Sized + Eq + Hash,
+    {
+        let (index, _, g) = self.groups.get_full_mut(group)?;
+        let old = g.queue.change_priority(item, new_priority)?;
+        self.fix_head(index);
+        Some(old)
+    }
+
+    /// Change the priority of an item of a group using the provided function.
+    ///
+    /// Computes in **O(log(N) + log(G))** time.
+    pub fn change_priority_by<Q, R, F>(&mut self, group: &Q, item: &R, priority_setter: F)
+    where
+        G: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        I: Borrow<R>,
+        R: ?Sized + Eq + Hash,
+        F: FnOnce(&mut P),
+    {
+        if let Some((index, _, g)) = self.groups.get_full_mut(group) {
+            g.queue.change_priority_by(item, priority_setter);
+            self.fix_head(index);
+        }
+    }
+
+    /// Get the priority of an item of a group, or `None`, if the item is not in the group
+    pub fn get_priority<Q, R>(&self, group: &Q, item: &R) -> Option<&P>
+    where
+        G: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        I: Borrow<R>,
+        R: ?Sized + Eq + Hash,
+    {
+        self.groups.get(group)?.queue.get_priority(item)
+    }
+
+    /// Remove an item from a group.
+    /// Returns the (item, priority) couple or None if the item
+    /// is not found in the group.
+    ///
+    /// Computes in **O(log(N) + log(G))** time.
+    pub fn remove<Q, R>(&mut self, group: &Q, item: &R) -> Option<(I, P)>
+    where
+        G: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        I: Borrow<R>,
+        R: ?Sized + Eq + Hash,
+    {
+        let (index, _, g) = self.groups.get_full_mut(group)?;
+        let r = g.queue.remove(item)?;
+        self.size -= 1;
+        self.fix_head(index);
+        Some(r)
+    }
+
+    /// Drops all the groups and their items
+    pub fn clear(&mut self) {
+        self.groups.clear();
+        self.heads.heap.clear();
+        self.heads.qp.clear();
+        self.size = 0;
+    }
+
+    /**************************************************************************/
+    /*                            internal functions                          */
+
+    /// Moves the group in position `index` of the map to its
+    /// correct place in the heap of the groups
+    fn fix_head(&mut self, index: usize) {
+        let GroupedPriorityQueue { groups, heads, .. } = self;
+        let pos = heads.qp[index];
+        heads.up_heapify(pos, &greater_group(groups));
+    }
+}
+
+/// Returns a function that compares two groups of the map, given their indexes.
+///
+/// The groups are compared by their priority and then by the priority of
+/// their top item. An empty group is always less than a non empty one.
+fn greater_group<G, I, P, H>(
+    groups: &IndexMap<G, Group<I, P, H>, H>,
+) -> impl Fn(usize, usize) -> bool + '_
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    move |a, b| {
+        let key = |i| {
+            let (_, group) = groups.get_index(i).unwrap();
+            group.queue.peek().map(|(_, p)| (&group.priority, p))
+        };
+        key(a) > key(b)
+    }
+}
+
+use std::fmt;
+
+impl<G, I, P, H> fmt::Debug for GroupedPriorityQueue<G, I, P, H>
+where
+    G: Hash + Eq + fmt::Debug,
+    I: Hash + Eq + fmt::Debug,
+    P: Ord + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.heads
+                    .heap
+                    .iter()
+                    .map(|&i| self.groups.get_index(i).unwrap())
+                    .map(|(g, group)| (g, (&group.priority, &group.queue.store))),
+            )
+            .finish()
+    }
+}
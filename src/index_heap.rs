@@ -0,0 +1,150 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+#[cfg(not(has_std))]
+use crate::std;
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+/// A heap of indexes into an `IndexMap`, whose order is defined
+/// by a comparison function over the indexes of the map.
+#[derive(Clone)]
+pub(crate) struct IndexHeap {
+    pub heap: Vec<usize>, // Implements the heap of indexes
+    pub qp: Vec<usize>,   // Performs the translation from the index
+                          // of the map to the index of the heap
+}
+
+impl IndexHeap {
+    /// Creates an empty `IndexHeap` with the specified capacity
+    pub fn with_capacity(capacity: usize) -> Self {
+        IndexHeap {
+            heap: Vec::with_capacity(capacity),
+            qp: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Swap two elements keeping a consistent state.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        let (i, j) = (self.heap[a], self.heap[b]);
+        self.heap.swap(a, b);
+        self.qp.swap(i, j);
+    }
+
+    /// Moves the element in position `pos` toward the root while it is
+    /// greater than its parent. Returns its final position.
+    pub fn bubble_up<F>(&mut self, mut pos: usize, greater: &F) -> usize
+    where
+        F: Fn(usize, usize) -> bool,
+    {
+        while pos > 0 && greater(self.heap[pos], self.heap[parent(pos)]) {
+            self.swap(pos, parent(pos));
+            pos = parent(pos);
+        }
+        pos
+    }
+
+    /// Restores the functional property of the sub-heap rooted in `pos`
+    pub fn heapify<F>(&mut self, mut pos: usize, greater: &F)
+    where
+        F: Fn(usize, usize) -> bool,
+    {
+        loop {
+            let (l, r) = (left(pos), right(pos));
+            let mut largest = pos;
+            if l < self.heap.len() && greater(self.heap[l], self.heap[largest]) {
+                largest = l;
+            }
+            if r < self.heap.len() && greater(self.heap[r], self.heap[largest]) {
+                largest = r;
+            }
+            if largest == pos {
+                return;
+            }
+            self.swap(pos, largest);
+            pos = largest;
+        }
+    }
+
+    /// Moves the element in position `pos` to its correct place
+    pub fn up_heapify<F>(&mut self, pos: usize, greater: &F)
+    where
+        F: Fn(usize, usize) -> bool,
+    {
+        let pos = self.bubble_up(pos, greater);
+        self.heapify(pos, greater);
+    }
+
+    /// Adds the last element of the map to the heap
+    pub fn push<F>(&mut self, greater: &F)
+    where
+        F: Fn(usize, usize) -> bool,
+    {
+        let i = self.heap.len();
+        self.heap.push(i);
+        self.qp.push(i);
+        self.bubble_up(i, greater);
+    }
+
+    /// Removes from the heap the element that was in position `index`
+    /// of the map, given that the last element of the map has been
+    /// swapped in its place.
+    pub fn swap_remove<F>(&mut self, index: usize, greater: &F)
+    where
+        F: Fn(usize, usize) -> bool,
+    {
+        let pos = self.qp[index];
+        let last = self.heap.len() - 1;
+        self.swap(pos, last);
+        self.heap.pop();
+        if index != last {
+            // the last element of the map took the place of the removed one
+            let moved = self.qp[last];
+            self.heap[moved] = index;
+            self.qp[index] = moved;
+        }
+        self.qp.pop();
+        if pos < self.heap.len() {
+            self.up_heapify(pos, greater);
+        }
+    }
+
+    /// Transforms the `heap` vector in a heap with its properties
+    pub fn build<F>(&mut self, greater: &F)
+    where
+        F: Fn(usize, usize) -> bool,
+    {
+        for i in (0..self.heap.len() / 2).rev() {
+            self.heapify(i, greater);
+        }
+    }
+}
+
+/// Compute the index of the left child of an item from its index
+fn left(i: usize) -> usize {
+    (i * 2) + 1
+}
+/// Compute the index of the right child of an item from its index
+fn right(i: usize) -> usize {
+    (i * 2) + 2
+}
+/// Compute the index of the parent element in the heap from its index
+fn parent(i: usize) -> usize {
+    (i - 1) / 2
+}
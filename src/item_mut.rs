@@ -0,0 +1,165 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+//! This module contains the [`ItemMut`] guard, shared by the queues.
+
+#[cfg(not(has_std))]
+use crate::std;
+
+use crate::store::Store;
+
+use indexmap::map::MutableKeys;
+
+use std::cmp::{Eq, Ord};
+use std::hash::{BuildHasher, Hash};
+use std::ops::{Deref, DerefMut};
+
+/// A guard giving mutable access to an item of a queue,
+/// even in a way that changes its hash.
+///
+/// When the guard is committed or dropped, the hash of the item is computed
+/// again and the item is moved to its place in the hash table,
+/// so that it can still be found by the queue.
+/// Its priority, and so its position in the heap, do not change.
+///
+/// Since a queue cannot hold two equal items, if the item was made equal to
+/// another item in the queue, it is removed from the queue: [`commit`](ItemMut::commit)
+/// gives it back, while dropping the guard drops it, losing the item and its
+/// priority. Use `commit` whenever such a collision is possible.
+///
+/// It can be obtained calling the `get_mut_rehash` method.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::PriorityQueue;
+///
+/// let mut pq = PriorityQueue::new();
+/// pq.push(String::from("Apples"), 5);
+/// pq.push(String::from("Pears"), 3);
+///
+/// if let Some(mut item) = pq.get_mut_rehash("Apples") {
+///     item.push_str(" and pears");
+/// }
+///
+/// assert_eq!(pq.get_priority("Apples and pears"), Some(&5));
+/// assert_eq!(pq.get_priority("Apples"), None);
+///
+/// let mut item = pq.get_mut_rehash("Pears").unwrap();
+/// *item = String::from("Apples and pears");
+/// assert_eq!(item.commit(), Err(String::from("Apples and pears")));
+///
+/// assert_eq!(pq.get_priority("Apples and pears"), Some(&5));
+/// assert_eq!(pq.len(), 1);
+/// ```
+pub struct ItemMut<'a, I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    queue: &'a mut dyn Rehash<I, P, H>,
+    index: usize,
+}
+
+/// The queues that can hand out an [`ItemMut`] guard
+pub(crate) trait Rehash<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    fn store(&self) -> &Store<I, P, H>;
+
+    fn store_mut(&mut self) -> &mut Store<I, P, H>;
+
+    /// Moves the item in position `index` of the map to the bucket of its
+    /// current hash, or removes it, restoring the properties of the heap,
+    /// if it is now equal to another item
+    fn reindex(&mut self, index: usize) -> Result<(), I>;
+}
+
+impl<'a, I, P, H> ItemMut<'a, I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    pub(crate) fn new(queue: &'a mut dyn Rehash<I, P, H>, index: usize) -> Self {
+        ItemMut { queue, index }
+    }
+
+    /// Returns the priority of the item
+    pub fn priority(&self) -> &P {
+        self.queue.store().map.get_index(self.index).unwrap().1
+    }
+
+    /// Moves the item to its place in the hash table for its current hash.
+    ///
+    /// Returns `Err` with the item, that is removed from the queue,
+    /// if it is now equal to another item in the queue.
+    ///
+    /// Computes in **O(1)** time (average), or in **O(log(N))**
+    /// if the item is removed.
+    #[must_use = "the item is dropped if it was removed from the queue"]
+    pub fn commit(self) -> Result<(), I> {
+        let r = self.queue.reindex(self.index);
+        std::mem::forget(self);
+        r
+    }
+}
+
+impl<I, P, H> Deref for ItemMut<'_, I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    type Target = I;
+    fn deref(&self) -> &I {
+        self.queue.store().map.get_index(self.index).unwrap().0
+    }
+}
+
+impl<I, P, H> DerefMut for ItemMut<'_, I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    fn deref_mut(&mut self) -> &mut I {
+        self.queue
+            .store_mut()
+            .map
+            .get_index_mut2(self.index)
+            .unwrap()
+            .0
+    }
+}
+
+impl<I, P, H> Drop for ItemMut<'_, I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    fn drop(&mut self) {
+        let _ = self.queue.reindex(self.index);
+    }
+}
@@ -0,0 +1,210 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+//! This module contains the [`IteratorExt`] trait, that adds to the
+//! iterators over `(item, priority)` couples some methods implemented
+//! with the priority queues of this crate.
+//!
+//! # Example
+//! ```rust
+//! use priority_queue::IteratorExt;
+//!
+//! let scores = vec![("Alice", 7), ("Bob", 3), ("Carol", 9), ("Alice", 10), ("Dave", 1)];
+//!
+//! assert_eq!(scores.clone().into_iter().top_k(2), vec![("Alice", 10), ("Carol", 9)]);
+//! assert_eq!(scores.into_iter().bottom_k(2), vec![("Dave", 1), ("Bob", 3)]);
+//! ```
+
+#[cfg(not(has_std))]
+use crate::std;
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+use crate::PriorityQueue;
+
+use std::cmp::Ordering;
+#[cfg(has_std)]
+use std::cmp::Reverse;
+use std::hash::{BuildHasher, Hash};
+
+/// Extends the iterators over `(item, priority)` couples
+/// with methods implemented through the priority queues.
+///
+/// The items are deduplicated by `Hash` and `Eq`, as they are in the queues:
+/// the selection methods keep, for each item, the best of its priorities.
+///
+/// The selection methods keep at most `k` elements in memory and compute
+/// in **O(N log(k))** time, where N is the length of the iterator.
+pub trait IteratorExt<I, P>: Iterator<Item = (I, P)> + Sized {
+    /// Returns the `k` items with the greatest priorities, sorted
+    /// from the greatest priority to the lowest.
+    #[cfg(has_std)]
+    fn top_k(self, k: usize) -> Vec<(I, P)>
+    where
+        I: Hash + Eq,
+        P: Ord,
+    {
+        top(self, k)
+    }
+
+    /// Returns the `k` items with the lowest priorities, sorted
+    /// from the lowest priority to the greatest.
+    #[cfg(has_std)]
+    fn bottom_k(self, k: usize) -> Vec<(I, P)>
+    where
+        I: Hash + Eq,
+        P: Ord,
+    {
+        top(self.map(|(i, p)| (i, Reverse(p))), k)
+            .into_iter()
+            .map(|(i, Reverse(p))| (i, p))
+            .collect()
+    }
+
+    /// Returns the `k` items with the greatest keys, computed from each
+    /// couple by `f`, sorted from the greatest key to the lowest.
+    ///
+    /// For each item, the couple with the greatest key is kept.
+    #[cfg(has_std)]
+    fn top_k_by_key<K, F>(self, k: usize, mut f: F) -> Vec<(I, P)>
+    where
+        I: Hash + Eq,
+        K: Ord,
+        F: FnMut(&I, &P) -> K,
+    {
+        let keyed = self.map(|(item, priority)| {
+            let key = f(&item, &priority);
+            (item, ByKey { key, priority })
+        });
+        top(keyed, k)
+            .into_iter()
+            .map(|(i, by_key)| (i, by_key.priority))
+            .collect()
+    }
+
+    /// Collects the couples in a [`PriorityQueue`] using the given hasher.
+    ///
+    /// If an item appears more than once, the last priority is kept.
+    fn collect_pq_with_hasher<H>(self, hash_builder: H) -> PriorityQueue<I, P, H>
+    where
+        I: Hash + Eq,
+        P: Ord,
+        H: BuildHasher,
+    {
+        let mut pq = PriorityQueue::with_hasher(hash_builder);
+        pq.extend(self);
+        pq
+    }
+}
+
+impl<I, P, T> IteratorExt<I, P> for T where T: Iterator<Item = (I, P)> {}
+
+/// Returns the first `k` elements of `iter` in the order given by `cmp`,
+/// sorted, with a partial sort of all the elements.
+///
+/// Unlike [`IteratorExt::top_k`], the elements are not deduplicated:
+/// this is meant for the iterators over a queue, whose items are distinct.
+///
+/// Computes in **O(N + k log(k))** time.
+pub(crate) fn select_by<T, F>(iter: impl Iterator<Item = T>, k: usize, mut cmp: F) -> Vec<T>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if k == 0 {
+        return Vec::new();
+    }
+    let mut all: Vec<_> = iter.collect();
+    if k < all.len() {
+        all.select_nth_unstable_by(k - 1, &mut cmp);
+        all.truncate(k);
+    }
+    all.sort_unstable_by(cmp);
+    all
+}
+
+/// Keeps the `k` items with the greatest priorities in a queue
+/// whose top is the lowest of them, to be replaced when a greater one arrives
+#[cfg(has_std)]
+fn top<I, P, T>(iter: T, k: usize) -> Vec<(I, P)>
+where
+    I: Hash + Eq,
+    P: Ord,
+    T: Iterator<Item = (I, P)>,
+{
+    if k == 0 {
+        return Vec::new();
+    }
+    let mut pq: PriorityQueue<I, Reverse<P>> = PriorityQueue::with_capacity(k);
+    for (item, priority) in iter {
+        match pq.get_priority(&item) {
+            Some(Reverse(old)) => {
+                if priority > *old {
+                    pq.change_priority(&item, Reverse(priority));
+                }
+            }
+            None if pq.len() < k => {
+                pq.push(item, Reverse(priority));
+            }
+            None => {
+                if matches!(pq.peek(), Some((_, Reverse(lowest))) if priority > *lowest) {
+                    pq.pop();
+                    pq.push(item, Reverse(priority));
+                }
+            }
+        }
+    }
+    let mut v: Vec<_> = pq
+        .into_sorted_iter()
+        .map(|(i, Reverse(p))| (i, p))
+        .collect();
+    v.reverse();
+    v
+}
+
+/// A priority compared only by its key
+#[cfg(has_std)]
+struct ByKey<K, P> {
+    key: K,
+    priority: P,
+}
+
+#[cfg(has_std)]
+impl<K: Ord, P> Ord for ByKey<K, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+#[cfg(has_std)]
+impl<K: Ord, P> PartialOrd for ByKey<K, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(has_std)]
+impl<K: Ord, P> PartialEq for ByKey<K, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+#[cfg(has_std)]
+impl<K: Ord, P> Eq for ByKey<K, P> {}
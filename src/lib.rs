@@ -22,6 +22,29 @@
 //!  *  a [priority queue](PriorityQueue)
 //!  *  a [double priority queue](DoublePriorityQueue).
 //!
+//! Some more specialized data structures are built on top of them:
+//!  *  a [priority map](PriorityMap), that hashes only the key of each entry;
+//!  *  a [priority multi-queue](PriorityMultiQueue), that allows repeated items;
+//!  *  a [multi priority queue](MultiPriorityQueue), that orders the same items by several priorities;
+//!  *  a [grouped priority queue](GroupedPriorityQueue), that partitions the items in prioritized groups;
+//!  *  a [tagged priority queue](TaggedPriorityQueue), that operates in bulk on the items sharing a tag;
+//!  *  a [ranked priority queue](RankedPriorityQueue), that answers order statistic queries;
+//!  *  a [median queue](MedianQueue), that tracks the median, or another quantile, of the priorities;
+//!  *  a [sliding window queue](SlidingWindowQueue), that evicts the elements falling out of a window over a stream;
+//!  *  a [frozen priority queue](FrozenPriorityQueue), an immutable sorted snapshot for read-heavy phases;
+//!  *  a [priority counter](PriorityCounter), that counts the occurrences of its items;
+//!  *  a [space saving](SpaceSaving) sketch, that approximates the most frequent items of a stream in fixed memory;
+//!  *  a [sampled priority queue](SampledPriorityQueue), that samples the items with probability proportional to their priority;
+//!  *  a [weighted reservoir](WeightedReservoir), that draws a weighted sample without replacement from a stream;
+//!  *  a [priority cache](PriorityCache), that evicts its entries according to a pluggable policy;
+//!  *  a [calendar queue](CalendarQueue), that extracts uniformly distributed timestamps in constant time.
+//!
+//! The `sim` module provides a scheduler for discrete-event simulations,
+//! that can cancel and reschedule the pending events.
+//!
+//! With the `algorithms` feature, the `algorithms` module provides
+//! Dijkstra, A* and Prim algorithms built on the decrease-key operation.
+//!
 //! Both data structures are backed by an hashmap, allowing
 //! to change the priority of an element with some efficient methods in
 //! **O(log(N))** time (worst case).
@@ -81,10 +104,50 @@ pub(crate) mod std {
     pub use ::alloc::vec;
 }
 
+#[cfg(all(feature = "algorithms", has_std))]
+pub mod algorithms;
+pub mod calendar_queue;
 pub mod core_iterators;
 pub mod double_priority_queue;
+pub mod frozen_priority_queue;
+pub mod grouped_priority_queue;
+mod index_heap;
+pub mod item_mut;
+pub mod iterator_ext;
+pub mod median_queue;
+pub mod merge_sorted;
+pub mod multi_priority_queue;
+pub mod priority_cache;
+pub mod priority_counter;
+pub mod priority_map;
+pub mod priority_multi_queue;
 pub mod priority_queue;
+pub mod ranked_priority_queue;
+pub mod sampled_priority_queue;
+pub mod sim;
+pub mod sliding_window_queue;
+pub mod space_saving;
 mod store;
+pub mod tagged_priority_queue;
+pub mod traits;
+pub mod weighted_reservoir;
 
+pub use crate::calendar_queue::CalendarQueue;
 pub use crate::double_priority_queue::DoublePriorityQueue;
+pub use crate::frozen_priority_queue::FrozenPriorityQueue;
+pub use crate::grouped_priority_queue::GroupedPriorityQueue;
+pub use crate::iterator_ext::IteratorExt;
+pub use crate::median_queue::MedianQueue;
+pub use crate::multi_priority_queue::MultiPriorityQueue;
+pub use crate::priority_cache::PriorityCache;
+pub use crate::priority_counter::PriorityCounter;
+pub use crate::priority_map::PriorityMap;
+pub use crate::priority_multi_queue::PriorityMultiQueue;
 pub use crate::priority_queue::PriorityQueue;
+pub use crate::ranked_priority_queue::RankedPriorityQueue;
+pub use crate::sampled_priority_queue::SampledPriorityQueue;
+pub use crate::sliding_window_queue::SlidingWindowQueue;
+pub use crate::space_saving::SpaceSaving;
+pub use crate::tagged_priority_queue::TaggedPriorityQueue;
+pub use crate::traits::{DoubleEndedPriorityQueue, PriorityQueueLike};
+pub use crate::weighted_reservoir::WeightedReservoir;
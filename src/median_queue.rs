@@ -0,0 +1,407 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+//! This module contains the [`MedianQueue`] type.
+//!
+//! See the type level documentation for more details and examples.
+
+#[cfg(not(has_std))]
+use crate::std;
+
+use crate::PriorityQueue;
+
+use std::borrow::Borrow;
+use std::cmp::{Eq, Ord, Reverse};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::iter::{Extend, FromIterator, IntoIterator, Iterator};
+
+/// A queue that keeps track of the median, or of another quantile,
+/// of the priorities of its elements while they are pushed, removed or changed.
+///
+/// The elements are split between two heaps: a max-heap holding the
+/// lower part of the priorities and a min-heap holding the upper part.
+/// After each operation the heaps are rebalanced, so that the lower heap
+/// always contains the elements up to the configured fraction, and the
+/// element at the split is at the top of it.
+///
+/// With a fraction `q` and `N` elements, the element at the split is the one
+/// in position `⌊q (N - 1)⌋` in ascending order of priority, and it is
+/// returned by [`quantile`](MedianQueue::quantile).
+/// The default fraction is 0.5, that gives the (lower) median,
+/// also returned by [`median`](MedianQueue::median).
+///
+/// The queue tracks a single fraction at a time: reading a different
+/// quantile requires moving the split with
+/// [`set_quantile`](MedianQueue::set_quantile), that moves about
+/// `|Δq| N` elements from one heap to the other and therefore takes
+/// **O(|Δq| N log(N))** time.
+///
+/// The priority is of type P, that must implement `std::cmp::Ord`.
+///
+/// The item is of type I, that must implement `Hash` and `Eq`.
+///
+/// Pushing, removing and changing the priority of an element are performed in
+/// **O(log(N))** time, while the element at the split is read in **O(1)**.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::MedianQueue;
+///
+/// let mut mq = MedianQueue::new();
+///
+/// mq.push("a", 5);
+/// mq.push("b", 1);
+/// mq.push("c", 9);
+/// assert_eq!(mq.median(), Some((&"a", &5)));
+///
+/// mq.change_priority(&"b", 7);
+/// assert_eq!(mq.median(), Some((&"b", &7)));
+///
+/// mq.set_quantile(1.0);
+/// assert_eq!(mq.quantile(), Some((&"c", &9)));
+/// ```
+#[derive(Clone)]
+#[cfg(has_std)]
+pub struct MedianQueue<I, P, H = RandomState>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    lower: PriorityQueue<I, P, H>,
+    upper: PriorityQueue<I, Reverse<P>, H>,
+    fraction: f64,
+}
+
+#[derive(Clone)]
+#[cfg(not(has_std))]
+pub struct MedianQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    lower: PriorityQueue<I, P, H>,
+    upper: PriorityQueue<I, Reverse<P>, H>,
+    fraction: f64,
+}
+
+impl<I, P, H> Default for MedianQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+#[cfg(has_std)]
+impl<I, P> MedianQueue<I, P>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    /// Creates an empty `MedianQueue` tracking the median
+    pub fn new() -> Self {
+        Self::with_default_hasher()
+    }
+
+    /// Creates an empty `MedianQueue` tracking the quantile `q`
+    ///
+    /// # Panics
+    /// Panics if `q` is not in the range `0.0..=1.0`
+    pub fn with_quantile(q: f64) -> Self {
+        let mut mq = Self::new();
+        mq.set_quantile(q);
+        mq
+    }
+}
+
+impl<I, P, H> MedianQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty `MedianQueue` with the default hasher
+    pub fn with_default_hasher() -> Self {
+        Self {
+            lower: PriorityQueue::with_default_hasher(),
+            upper: PriorityQueue::with_default_hasher(),
+            fraction: 0.5,
+        }
+    }
+}
+
+impl<I, P, H> MedianQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Clone,
+{
+    /// Creates an empty `MedianQueue` with the specified hasher
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self {
+            lower: PriorityQueue::with_hasher(hash_builder.clone()),
+            upper: PriorityQueue::with_hasher(hash_builder),
+            fraction: 0.5,
+        }
+    }
+}
+
+impl<I, P, H> MedianQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    /// Returns an iterator in arbitrary order over the
+    /// (item, priority) elements in the queue
+    pub fn iter(&self) -> impl Iterator<Item = (&I, &P)> {
+        self.lower
+            .iter()
+            .chain(self.upper.iter().map(|(i, p)| (i, &p.0)))
+    }
+
+    /// Sets the fraction of the elements that sit at or below the split,
+    /// and rebalances the heaps accordingly.
+    ///
+    /// Moving the split by k positions takes **O(k log(N))** time,
+    /// where k is about `|Δq| N`: this is meant to configure the queue,
+    /// not to read several quantiles in turn.
+    ///
+    /// # Panics
+    /// Panics if `q` is not in the range `0.0..=1.0`
+    pub fn set_quantile(&mut self, q: f64) {
+        assert!(
+            (0.0..=1.0).contains(&q),
+            "the quantile must be in the range 0.0..=1.0"
+        );
+        self.fraction = q;
+        self.rebalance();
+    }
+
+    /// Insert the item-priority pair into the queue.
+    ///
+    /// If an element equal to `item` was already into the queue,
+    /// it is updated and the old value of its priority returned in `Some`;
+    /// otherwise, returns `None`.
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn push(&mut self, item: I, priority: P) -> Option<P> {
+        let old = self.take(&item).map(|(_, p)| p);
+        if matches!(self.lower.peek(), Some((_, max)) if priority <= *max) {
+            self.lower.push(item, priority);
+        } else {
+            self.upper.push(item, Reverse(priority));
+        }
+        self.rebalance();
+        old
+    }
+
+    /// Change the priority of an Item returning the old value of priority,
+    /// or `None` if the item wasn't in the queue.
+    ///
+    /// The operation is performed in **O(log(N))** time.
+    pub fn change_priority<Q>(&mut self, item: &Q, new_priority: P) -> Option<P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let (item, old) = self.take(item)?;
+        self.push(item, new_priority);
+        Some(old)
+    }
+
+    /// Remove an arbitrary element from the queue.
+    /// Returns the (item, priority) couple or None if the item
+    /// is not found in the queue.
+    ///
+    /// The operation is performed in **O(log(N))** time.
+    pub fn remove<Q>(&mut self, item: &Q) -> Option<(I, P)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let r = self.take(item);
+        self.rebalance();
+        r
+    }
+
+    /// Get the priority of an item, or `None`, if the item is not in the queue
+    pub fn get_priority<Q>(&self, item: &Q) -> Option<&P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.lower
+            .get_priority(item)
+            .or_else(|| self.upper.get_priority(item).map(|p| &p.0))
+    }
+
+    /// Returns true if the item is in the queue
+    pub fn contains<Q>(&self, item: &Q) -> bool
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.get_priority(item).is_some()
+    }
+
+    /// Drops all items from the queue
+    pub fn clear(&mut self) {
+        self.lower.clear();
+        self.upper.clear();
+    }
+
+    /**************************************************************************/
+    /*                            internal functions                          */
+
+    /// Removes an element from whichever heap holds it, without rebalancing
+    fn take<Q>(&mut self, item: &Q) -> Option<(I, P)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.lower
+            .remove(item)
+            .or_else(|| self.upper.remove(item).map(|(i, p)| (i, p.0)))
+    }
+
+    /// Moves elements across the split until the lower heap has the
+    /// size required by the fraction
+    fn rebalance(&mut self) {
+        let target = self.target_len();
+        while self.lower.len() > target {
+            let (i, p) = self.lower.pop().unwrap();
+            self.upper.push(i, Reverse(p));
+        }
+        while self.lower.len() < target {
+            let (i, p) = self.upper.pop().unwrap();
+            self.lower.push(i, p.0);
+        }
+    }
+}
+
+impl<I, P, H> MedianQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    /// Returns the couple (item, priority) with the lower median priority,
+    /// that is in position `⌊(N - 1) / 2⌋` in ascending order of priority,
+    /// or `None` if the queue is empty.
+    ///
+    /// Computes in **O(1)** time
+    ///
+    /// # Panics
+    /// Panics if the queue tracks a fraction other than 0.5,
+    /// set with [`set_quantile`](MedianQueue::set_quantile):
+    /// use [`quantile`](MedianQueue::quantile) in that case.
+    pub fn median(&self) -> Option<(&I, &P)> {
+        assert!(
+            self.fraction == 0.5,
+            "the queue does not track the median, but the quantile {}",
+            self.fraction
+        );
+        self.lower.peek()
+    }
+
+    /// Returns the couple (item, priority) in position `⌊q (N - 1)⌋`
+    /// in ascending order of priority, where `q` is the configured fraction,
+    /// or `None` if the queue is empty.
+    ///
+    /// Unless a different fraction was set with [`set_quantile`](MedianQueue::set_quantile),
+    /// that is the lower median of the priorities.
+    ///
+    /// Computes in **O(1)** time
+    pub fn quantile(&self) -> Option<(&I, &P)> {
+        self.lower.peek()
+    }
+
+    /// Returns the fraction at which the queue is split
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    /// Returns the number of elements in the queue.
+    pub fn len(&self) -> usize {
+        self.lower.len() + self.upper.len()
+    }
+
+    /// Returns true if the queue contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.lower.is_empty() && self.upper.is_empty()
+    }
+
+    /// Returns the number of elements that the lower heap must hold
+    fn target_len(&self) -> usize {
+        match self.len() {
+            0 => 0,
+            n => (self.fraction * (n - 1) as f64) as usize + 1,
+        }
+    }
+}
+
+impl<I, P, H> FromIterator<(I, P)> for MedianQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    fn from_iter<IT>(iter: IT) -> Self
+    where
+        IT: IntoIterator<Item = (I, P)>,
+    {
+        let mut mq = Self::with_default_hasher();
+        mq.extend(iter);
+        mq
+    }
+}
+
+impl<I, P, H> Extend<(I, P)> for MedianQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (I, P)>>(&mut self, iter: T) {
+        for (item, priority) in iter {
+            self.push(item, priority);
+        }
+    }
+}
+
+use std::fmt;
+
+impl<I, P, H> fmt::Debug for MedianQueue<I, P, H>
+where
+    I: Hash + Eq + fmt::Debug,
+    P: Ord + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.lower.store.map.iter())
+            .entries(self.upper.store.map.iter().map(|(i, p)| (i, &p.0)))
+            .finish()
+    }
+}
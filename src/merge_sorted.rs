@@ -0,0 +1,214 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+//! This module contains the [`MergeSorted`] iterator, that merges
+//! several sorted iterators in a single sorted stream.
+//!
+//! # Example
+//! ```rust
+//! use priority_queue::merge_sorted::{merge_sorted, merge_sorted_dedup};
+//!
+//! let shards = vec![vec![1, 4, 7], vec![2, 4, 8], vec![3, 9]];
+//!
+//! let merged: Vec<_> = merge_sorted(shards.clone().into_iter().map(|s| s.into_iter())).collect();
+//! assert_eq!(merged, vec![1, 2, 3, 4, 4, 7, 8, 9]);
+//!
+//! let merged: Vec<_> = merge_sorted_dedup(shards.into_iter().map(|s| s.into_iter())).collect();
+//! assert_eq!(merged, vec![1, 2, 3, 4, 7, 8, 9]);
+//! ```
+
+#[cfg(not(has_std))]
+use crate::std;
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+use crate::PriorityQueue;
+
+use std::cmp::{Ord, Reverse};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::iter::{FusedIterator, IntoIterator, Iterator};
+
+/// Merges N iterators over elements sorted in ascending order.
+///
+/// The first element of each source is kept in a [`PriorityQueue`]
+/// whose items are the indices of the sources. When an element is
+/// extracted, the priority of its source is changed to the next element
+/// of the source, instead of popping and pushing it again.
+///
+/// Equal elements from different sources are yielded in the order of
+/// their sources. If the iterator was created to deduplicate the elements,
+/// only the first of a run of equal elements is yielded.
+///
+/// Each element is yielded in **O(log(N))** time.
+///
+/// It can be obtained calling [`merge_sorted`] or [`merge_sorted_dedup`].
+#[cfg(has_std)]
+pub struct MergeSorted<T, H = RandomState>
+where
+    T: Iterator,
+    T::Item: Ord,
+{
+    sources: Vec<T>,
+    heads: PriorityQueue<usize, Reverse<(T::Item, usize)>, H>,
+    dedup: bool,
+}
+
+#[cfg(not(has_std))]
+pub struct MergeSorted<T, H>
+where
+    T: Iterator,
+    T::Item: Ord,
+{
+    sources: Vec<T>,
+    heads: PriorityQueue<usize, Reverse<(T::Item, usize)>, H>,
+    dedup: bool,
+}
+
+/// Merges the sorted iterators in `iters` in a single sorted iterator.
+///
+/// See [`MergeSorted`] for more details.
+#[cfg(has_std)]
+pub fn merge_sorted<IT>(iters: IT) -> MergeSorted<IT::Item>
+where
+    IT: IntoIterator,
+    IT::Item: Iterator,
+    <IT::Item as Iterator>::Item: Ord,
+{
+    MergeSorted::with_hasher(iters, RandomState::new())
+}
+
+/// Merges the sorted iterators in `iters` in a single sorted iterator,
+/// yielding only once the elements that are equal.
+///
+/// See [`MergeSorted`] for more details.
+#[cfg(has_std)]
+pub fn merge_sorted_dedup<IT>(iters: IT) -> MergeSorted<IT::Item>
+where
+    IT: IntoIterator,
+    IT::Item: Iterator,
+    <IT::Item as Iterator>::Item: Ord,
+{
+    MergeSorted::dedup_with_hasher(iters, RandomState::new())
+}
+
+impl<T, H> MergeSorted<T, H>
+where
+    T: Iterator,
+    T::Item: Ord,
+    H: BuildHasher,
+{
+    /// Merges the sorted iterators in `iters`, using the specified
+    /// hasher for the internal queue
+    pub fn with_hasher<IT>(iters: IT, hash_builder: H) -> Self
+    where
+        IT: IntoIterator<Item = T>,
+    {
+        Self::build(iters, hash_builder, false)
+    }
+
+    /// Merges the sorted iterators in `iters`, yielding only once the
+    /// elements that are equal, using the specified hasher for the internal queue
+    pub fn dedup_with_hasher<IT>(iters: IT, hash_builder: H) -> Self
+    where
+        IT: IntoIterator<Item = T>,
+    {
+        Self::build(iters, hash_builder, true)
+    }
+
+    fn build<IT>(iters: IT, hash_builder: H, dedup: bool) -> Self
+    where
+        IT: IntoIterator<Item = T>,
+    {
+        let mut sources: Vec<T> = iters.into_iter().collect();
+        let mut heads = PriorityQueue::with_capacity_and_hasher(sources.len(), hash_builder);
+        for (i, source) in sources.iter_mut().enumerate() {
+            if let Some(head) = source.next() {
+                heads.push(i, Reverse((head, i)));
+            }
+        }
+        MergeSorted {
+            sources,
+            heads,
+            dedup,
+        }
+    }
+
+    /// Moves a source to its next element, returning the current one
+    fn advance(&mut self, source: usize) -> Option<T::Item> {
+        match self.sources[source].next() {
+            Some(next) => self.heads.change_priority(&source, Reverse((next, source))),
+            None => self.heads.remove(&source).map(|(_, head)| head),
+        }
+        .map(|Reverse((head, _))| head)
+    }
+}
+
+impl<T, H> Iterator for MergeSorted<T, H>
+where
+    T: Iterator,
+    T::Item: Ord,
+    H: BuildHasher,
+{
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<T::Item> {
+        let source = *self.heads.peek()?.0;
+        let element = self.advance(source)?;
+        if self.dedup {
+            while let Some((&source, Reverse((head, _)))) = self.heads.peek() {
+                if *head != element {
+                    break;
+                }
+                self.advance(source);
+            }
+        }
+        Some(element)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (low, high) = self
+            .sources
+            .iter()
+            .fold((0usize, Some(0usize)), |(low, high), s| {
+                let (l, h) = s.size_hint();
+                (
+                    low.saturating_add(l),
+                    high.and_then(|high| high.checked_add(h?)),
+                )
+            });
+        let len = self.heads.len();
+        let high = high.and_then(|high| high.checked_add(len));
+        if self.dedup {
+            (len.min(1), high)
+        } else {
+            (low.saturating_add(len), high)
+        }
+    }
+}
+
+impl<T, H> FusedIterator for MergeSorted<T, H>
+where
+    T: Iterator,
+    T::Item: Ord,
+    H: BuildHasher,
+{
+}
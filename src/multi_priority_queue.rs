@@ -0,0 +1,631 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+//! This module contains the [`MultiPriorityQueue`] type and the traits
+//! used to describe its tuple of priorities.
+//!
+//! See the type level documentation for more details and examples.
+
+#[cfg(not(has_std))]
+use crate::std;
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+use crate::core_iterators::{IntoIter, Iter};
+use crate::index_heap::IndexHeap;
+
+use std::borrow::Borrow;
+use std::cmp::{Eq, Ord, Ordering};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::iter::{Extend, FromIterator, IntoIterator, Iterator};
+use std::mem::replace;
+
+use indexmap::map::IndexMap;
+
+/// A tuple of priorities, each one defining a different order
+/// on the items of a [`MultiPriorityQueue`].
+///
+/// It is implemented for the tuples from 1 up to 6 elements,
+/// whose members all implement `Ord`.
+pub trait Priorities: Ord {
+    /// The number of orders defined by the tuple
+    const DIMENSIONS: usize;
+
+    /// Compares the element in position `dimension` of the two tuples
+    fn cmp_dimension(&self, other: &Self, dimension: usize) -> Ordering;
+}
+
+/// Gives access to the element in position `N` of a tuple of [`Priorities`].
+pub trait Dimension<const N: usize>: Priorities {
+    /// The type of the priority in position `N`
+    type Priority: Ord;
+
+    /// Returns a reference to the priority in position `N`
+    fn get(&self) -> &Self::Priority;
+
+    /// Returns a mutable reference to the priority in position `N`
+    fn get_mut(&mut self) -> &mut Self::Priority;
+}
+
+macro_rules! impl_dimension {
+    (($($p:ident),+); $n:tt => $t:ident) => {
+        impl<$($p: Ord),+> Dimension<$n> for ($($p,)+) {
+            type Priority = $t;
+
+            fn get(&self) -> &$t {
+                &self.$n
+            }
+
+            fn get_mut(&mut self) -> &mut $t {
+                &mut self.$n
+            }
+        }
+    };
+}
+
+macro_rules! impl_priorities {
+    (@dimensions $all:tt; $($n:tt $p:ident),+) => {
+        $(impl_dimension!($all; $n => $p);)+
+    };
+    ($len:expr; $($n:tt $p:ident),+) => {
+        impl<$($p: Ord),+> Priorities for ($($p,)+) {
+            const DIMENSIONS: usize = $len;
+
+            fn cmp_dimension(&self, other: &Self, dimension: usize) -> Ordering {
+                match dimension {
+                    $($n => self.$n.cmp(&other.$n),)+
+                    _ => panic!("dimension {} out of bounds", dimension),
+                }
+            }
+        }
+
+        impl_priorities!(@dimensions ($($p),+); $($n $p),+);
+    };
+}
+
+impl_priorities!(1; 0 P0);
+impl_priorities!(2; 0 P0, 1 P1);
+impl_priorities!(3; 0 P0, 1 P1, 2 P2);
+impl_priorities!(4; 0 P0, 1 P1, 2 P2, 3 P3);
+impl_priorities!(5; 0 P0, 1 P1, 2 P2, 3 P3, 4 P4);
+impl_priorities!(6; 0 P0, 1 P1, 2 P2, 3 P3, 4 P4, 5 P5);
+
+/// A set of items ordered at the same time by several priorities.
+///
+/// The items, of type I, must implement `Hash` and `Eq`, and are stored
+/// only once inside an `IndexMap`, together with their tuple of priorities `Ps`.
+/// Every element of the tuple must implement `Ord` and defines a different
+/// order over the items, implemented as a separate heap of indexes into the map.
+///
+/// The item with the greatest priority along the dimension `N` can be
+/// extracted with [`pop_by::<N>`](MultiPriorityQueue::pop_by),
+/// and it is removed from all the heaps at once.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::MultiPriorityQueue;
+/// use std::cmp::Reverse;
+///
+/// // Tasks ordered by earliest deadline and by importance
+/// let mut pq = MultiPriorityQueue::new();
+///
+/// pq.push("Write report", (Reverse(5), 3));
+/// pq.push("Fix the build", (Reverse(1), 2));
+/// pq.push("Release", (Reverse(9), 10));
+///
+/// assert_eq!(pq.peek_by::<0>(), Some((&"Fix the build", &(Reverse(1), 2))));
+/// assert_eq!(pq.peek_by::<1>(), Some((&"Release", &(Reverse(9), 10))));
+///
+/// pq.change_priority::<1, _>(&"Write report", 20);
+/// assert_eq!(pq.pop_by::<1>(), Some(("Write report", (Reverse(5), 20))));
+/// assert_eq!(pq.pop_by::<0>(), Some(("Fix the build", (Reverse(1), 2))));
+/// assert_eq!(pq.len(), 1);
+/// ```
+#[derive(Clone)]
+#[cfg(has_std)]
+pub struct MultiPriorityQueue<I, Ps, H = RandomState>
+where
+    I: Hash + Eq,
+    Ps: Priorities,
+{
+    map: IndexMap<I, Ps, H>,
+    heaps: Vec<IndexHeap>,
+}
+
+#[derive(Clone)]
+#[cfg(not(has_std))]
+pub struct MultiPriorityQueue<I, Ps, H>
+where
+    I: Hash + Eq,
+    Ps: Priorities,
+{
+    map: IndexMap<I, Ps, H>,
+    heaps: Vec<IndexHeap>,
+}
+
+impl<I, Ps, H> Default for MultiPriorityQueue<I, Ps, H>
+where
+    I: Hash + Eq,
+    Ps: Priorities,
+    H: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+#[cfg(has_std)]
+impl<I, Ps> MultiPriorityQueue<I, Ps>
+where
+    I: Hash + Eq,
+    Ps: Priorities,
+{
+    /// Creates an empty `MultiPriorityQueue`
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates an empty `MultiPriorityQueue` with the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_default_hasher(capacity)
+    }
+}
+
+impl<I, Ps, H> MultiPriorityQueue<I, Ps, H>
+where
+    I: Hash + Eq,
+    Ps: Priorities,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty `MultiPriorityQueue` with the default hasher
+    pub fn with_default_hasher() -> Self {
+        Self::with_capacity_and_default_hasher(0)
+    }
+
+    /// Creates an empty `MultiPriorityQueue` with the specified capacity and default hasher
+    pub fn with_capacity_and_default_hasher(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, H::default())
+    }
+}
+
+impl<I, Ps, H> MultiPriorityQueue<I, Ps, H>
+where
+    I: Hash + Eq,
+    Ps: Priorities,
+    H: BuildHasher,
+{
+    /// Creates an empty `MultiPriorityQueue` with the specified hasher
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    /// Creates an empty `MultiPriorityQueue` with the specified capacity and hasher
+    ///
+    /// The internal collections will be able to hold at least `capacity`
+    /// elements without reallocating.
+    /// If `capacity` is 0, there will be no allocation.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: H) -> Self {
+        Self {
+            map: IndexMap::with_capacity_and_hasher(capacity, hash_builder),
+            heaps: (0..Ps::DIMENSIONS)
+                .map(|_| IndexHeap::with_capacity(capacity))
+                .collect(),
+        }
+    }
+}
+
+impl<I, Ps, H> MultiPriorityQueue<I, Ps, H>
+where
+    I: Hash + Eq,
+    Ps: Priorities,
+{
+    /// Returns an iterator in arbitrary order over the
+    /// (item, priorities) elements in the queue
+    pub fn iter(&self) -> Iter<'_, I, Ps> {
+        Iter {
+            iter: self.map.iter(),
+        }
+    }
+
+    /// Returns the couple (item, priorities) with the greatest
+    /// priority along the dimension `N`, or None if the queue is empty.
+    ///
+    /// Computes in **O(1)** time
+    pub fn peek_by<const N: usize>(&self) -> Option<(&I, &Ps)>
+    where
+        Ps: Dimension<N>,
+    {
+        self.heaps[N]
+            .heap
+            .first()
+            .and_then(|i| self.map.get_index(*i))
+    }
+
+    /// Returns the number of elements the internal map can hold without
+    /// reallocating.
+    ///
+    /// This number is a lower bound; the map might be able to hold more,
+    /// but is guaranteed to be able to hold at least this many.
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Returns the number of elements in the queue.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if the queue contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<I, Ps, H> MultiPriorityQueue<I, Ps, H>
+where
+    I: Hash + Eq,
+    Ps: Priorities,
+    H: BuildHasher,
+{
+    /// Reserves capacity for at least `additional` more elements to be inserted
+    /// in the given `MultiPriorityQueue`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+        for h in &mut self.heaps {
+            h.heap.reserve(additional);
+            h.qp.reserve(additional);
+        }
+    }
+
+    /// Insert the item into the queue with the given priorities.
+    ///
+    /// If an element equal to `item` was already into the queue,
+    /// its priorities are updated in every heap and the old ones
+    /// are returned in `Some`; otherwise, returns `None`.
+    ///
+    /// Computes in **O(D log(N))** time, where D is the number of dimensions.
+    pub fn push(&mut self, item: I, priorities: Ps) -> Option<Ps> {
+        use indexmap::map::Entry::*;
+
+        match self.map.entry(item) {
+            Occupied(mut e) => {
+                let index = e.index();
+                let old = replace(e.get_mut(), priorities);
+                for d in 0..Ps::DIMENSIONS {
+                    self.fix(d, index);
+                }
+                Some(old)
+            }
+            Vacant(e) => {
+                e.insert(priorities);
+                let MultiPriorityQueue { map, heaps } = self;
+                for (d, h) in heaps.iter_mut().enumerate() {
+                    h.push(&greater_by(map, d));
+                }
+                None
+            }
+        }
+    }
+
+    /// Removes the item with the greatest priority along the dimension `N`
+    /// from all the heaps and returns the couple (item, priorities),
+    /// or None if the queue is empty.
+    ///
+    /// Computes in **O(D log(N))** time, where D is the number of dimensions.
+    pub fn pop_by<const N: usize>(&mut self) -> Option<(I, Ps)>
+    where
+        Ps: Dimension<N>,
+    {
+        let index = *self.heaps[N].heap.first()?;
+        Some(self.swap_remove_index(index))
+    }
+
+    /// Change the priority of an item along the dimension `N`,
+    /// returning the old value, or `None` if the item wasn't in the queue.
+    ///
+    /// Only the heap of the dimension `N` is updated.
+    ///
+    /// The operation is performed in **O(log(N))** time.
+    pub fn change_priority<const N: usize, Q>(
+        &mut self,
+        item: &Q,
+        new_priority: <Ps as Dimension<N>>::Priority,
+    ) -> Option<<Ps as Dimension<N>>::Priority>
+    where
+        Ps: Dimension<N>,
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let (index, _, ps) = self.map.get_full_mut(item)?;
+        let old = replace(ps.get_mut(), new_priority);
+        self.fix(N, index);
+        Some(old)
+    }
+
+    /// Change the priority of an item along the dimension `N` using the
+    /// provided function.
+    ///
+    /// The operation is performed in **O(log(N))** time.
+    pub fn change_priority_by<const N: usize, Q, F>(&mut self, item: &Q, priority_setter: F)
+    where
+        Ps: Dimension<N>,
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&mut <Ps as Dimension<N>>::Priority),
+    {
+        if let Some((index, _, ps)) = self.map.get_full_mut(item) {
+            priority_setter(ps.get_mut());
+            self.fix(N, index);
+        }
+    }
+
+    /// Replace all the priorities of an item, returning the old ones,
+    /// or `None` if the item wasn't in the queue.
+    ///
+    /// Computes in **O(D log(N))** time, where D is the number of dimensions.
+    pub fn change_priorities<Q>(&mut self, item: &Q, new_priorities: Ps) -> Option<Ps>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let (index, _, ps) = self.map.get_full_mut(item)?;
+        let old = replace(ps, new_priorities);
+        for d in 0..Ps::DIMENSIONS {
+            self.fix(d, index);
+        }
+        Some(old)
+    }
+
+    /// Get the priorities of an item, or `None`, if the item is not in the queue
+    pub fn get_priorities<Q>(&self, item: &Q) -> Option<&Ps>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.get(item)
+    }
+
+    /// Get the priority of an item along the dimension `N`,
+    /// or `None`, if the item is not in the queue
+    pub fn get_priority<const N: usize, Q>(
+        &self,
+        item: &Q,
+    ) -> Option<&<Ps as Dimension<N>>::Priority>
+    where
+        Ps: Dimension<N>,
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.get(item).map(Dimension::<N>::get)
+    }
+
+    /// Get the couple (item, priorities) of an arbitrary element, as reference
+    /// or `None` if the item is not in the queue.
+    pub fn get<Q>(&self, item: &Q) -> Option<(&I, &Ps)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.get_key_value(item)
+    }
+
+    /// Remove an arbitrary element from all the heaps.
+    /// Returns the (item, priorities) couple or None if the item
+    /// is not found in the queue.
+    ///
+    /// Computes in **O(D log(N))** time, where D is the number of dimensions.
+    pub fn remove<Q>(&mut self, item: &Q) -> Option<(I, Ps)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let index = self.map.get_index_of(item)?;
+        Some(self.swap_remove_index(index))
+    }
+
+    /// Drops all items from the queue
+    pub fn clear(&mut self) {
+        self.map.clear();
+        for h in &mut self.heaps {
+            h.heap.clear();
+            h.qp.clear();
+        }
+    }
+
+    /**************************************************************************/
+    /*                            internal functions                          */
+
+    /// Moves the element in position `index` of the map to its
+    /// correct place in the heap of the dimension `d`
+    fn fix(&mut self, d: usize, index: usize) {
+        let MultiPriorityQueue { map, heaps } = self;
+        let h = &mut heaps[d];
+        let pos = h.qp[index];
+        h.up_heapify(pos, &greater_by(map, d));
+    }
+
+    /// Removes the element in position `index` of the map from the map
+    /// and from all the heaps
+    fn swap_remove_index(&mut self, index: usize) -> (I, Ps) {
+        let r = self.map.swap_remove_index(index).unwrap();
+        let MultiPriorityQueue { map, heaps } = self;
+        for (d, h) in heaps.iter_mut().enumerate() {
+            h.swap_remove(index, &greater_by(map, d));
+        }
+        r
+    }
+
+    /// Rebuilds all the heaps from scratch
+    fn heap_build(&mut self) {
+        let MultiPriorityQueue { map, heaps } = self;
+        for (d, h) in heaps.iter_mut().enumerate() {
+            h.heap = (0..map.len()).collect();
+            h.qp = (0..map.len()).collect();
+            h.build(&greater_by(map, d));
+        }
+    }
+}
+
+/// Returns a function that compares two elements of the map,
+/// given their indexes, along the dimension `d`
+fn greater_by<I, Ps, H>(map: &IndexMap<I, Ps, H>, d: usize) -> impl Fn(usize, usize) -> bool + '_
+where
+    Ps: Priorities,
+{
+    move |a, b| {
+        let (_, pa) = map.get_index(a).unwrap();
+        let (_, pb) = map.get_index(b).unwrap();
+        pa.cmp_dimension(pb, d) == Ordering::Greater
+    }
+}
+
+impl<I, Ps, H> FromIterator<(I, Ps)> for MultiPriorityQueue<I, Ps, H>
+where
+    I: Hash + Eq,
+    Ps: Priorities,
+    H: BuildHasher + Default,
+{
+    fn from_iter<IT>(iter: IT) -> Self
+    where
+        IT: IntoIterator<Item = (I, Ps)>,
+    {
+        let mut pq = Self::with_default_hasher();
+        pq.map.extend(iter);
+        pq.heap_build();
+        pq
+    }
+}
+
+impl<I, Ps, H> Extend<(I, Ps)> for MultiPriorityQueue<I, Ps, H>
+where
+    I: Hash + Eq,
+    Ps: Priorities,
+    H: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (I, Ps)>>(&mut self, iter: T) {
+        for (item, priorities) in iter {
+            self.push(item, priorities);
+        }
+    }
+}
+
+impl<I, Ps, H> IntoIterator for MultiPriorityQueue<I, Ps, H>
+where
+    I: Hash + Eq,
+    Ps: Priorities,
+{
+    type Item = (I, Ps);
+    type IntoIter = IntoIter<I, Ps>;
+    fn into_iter(self) -> IntoIter<I, Ps> {
+        IntoIter {
+            iter: self.map.into_iter(),
+        }
+    }
+}
+
+impl<'a, I, Ps, H> IntoIterator for &'a MultiPriorityQueue<I, Ps, H>
+where
+    I: Hash + Eq,
+    Ps: Priorities,
+{
+    type Item = (&'a I, &'a Ps);
+    type IntoIter = Iter<'a, I, Ps>;
+    fn into_iter(self) -> Iter<'a, I, Ps> {
+        self.iter()
+    }
+}
+
+use std::fmt;
+
+impl<I, Ps, H> fmt::Debug for MultiPriorityQueue<I, Ps, H>
+where
+    I: Hash + Eq + fmt::Debug,
+    Ps: Priorities + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.map.iter()).finish()
+    }
+}
+
+use std::cmp::PartialEq;
+
+impl<I, Ps, H1, H2> PartialEq<MultiPriorityQueue<I, Ps, H2>> for MultiPriorityQueue<I, Ps, H1>
+where
+    I: Hash + Eq,
+    Ps: Priorities,
+    H1: BuildHasher,
+    H2: BuildHasher,
+{
+    fn eq(&self, other: &MultiPriorityQueue<I, Ps, H2>) -> bool {
+        self.map == other.map
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use std::cmp::Eq;
+    use std::hash::{BuildHasher, Hash};
+
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+    #[cfg(not(has_std))]
+    use std::vec::Vec;
+
+    use super::{MultiPriorityQueue, Priorities};
+
+    impl<I, Ps, H> Serialize for MultiPriorityQueue<I, Ps, H>
+    where
+        I: Hash + Eq + Serialize,
+        Ps: Priorities + Serialize,
+        H: BuildHasher,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut seq_serializer = serializer.serialize_seq(Some(self.len()))?;
+            for element in self {
+                seq_serializer.serialize_element(&element)?;
+            }
+            seq_serializer.end()
+        }
+    }
+
+    impl<'de, I, Ps, H> Deserialize<'de> for MultiPriorityQueue<I, Ps, H>
+    where
+        I: Hash + Eq + Deserialize<'de>,
+        Ps: Priorities + Deserialize<'de>,
+        H: BuildHasher + Default,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<MultiPriorityQueue<I, Ps, H>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            Vec::<(I, Ps)>::deserialize(deserializer).map(|v| v.into_iter().collect())
+        }
+    }
+}
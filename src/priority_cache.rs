@@ -0,0 +1,564 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+//! This module contains the [`PriorityCache`] type and the
+//! [`CachePolicy`] trait that decides which entry it evicts,
+//! with the [`Lru`], [`Lfu`] and [`Lrfu`] implementations.
+//!
+//! See the type level documentation for more details and examples.
+
+#[cfg(not(has_std))]
+use crate::std;
+#[cfg(not(has_std))]
+use std::alloc::boxed::Box;
+
+use crate::PriorityQueue;
+
+use std::borrow::Borrow;
+#[cfg(has_std)]
+use std::boxed::Box;
+use std::cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::Iterator;
+
+/// Decides the order in which a [`PriorityCache`] evicts its entries.
+///
+/// Each entry has a score, and the entry with the lowest score is evicted
+/// first. The policy computes the score of the new entries and updates
+/// it on every access.
+///
+/// # Example
+/// A First In First Out policy, that ignores the accesses.
+/// ```rust
+/// use priority_queue::priority_cache::{CachePolicy, PriorityCache};
+///
+/// #[derive(Default)]
+/// struct Fifo {
+///     clock: u64,
+/// }
+///
+/// impl<K> CachePolicy<K> for Fifo {
+///     type Score = u64;
+///
+///     fn on_insert(&mut self, _key: &K) -> u64 {
+///         self.clock += 1;
+///         self.clock
+///     }
+///
+///     fn on_access(&mut self, _score: &mut u64) {}
+/// }
+///
+/// let mut cache = PriorityCache::with_policy(2, Fifo::default());
+/// cache.insert("a", 1);
+/// cache.insert("b", 2);
+/// cache.get("a");
+/// cache.insert("c", 3);
+///
+/// // "a" is evicted first, even if it was used after "b"
+/// assert!(!cache.contains("a"));
+/// assert!(cache.contains("b"));
+/// ```
+pub trait CachePolicy<K> {
+    /// The score of an entry
+    type Score: Ord;
+
+    /// Returns the score of an entry inserted in the cache
+    fn on_insert(&mut self, key: &K) -> Self::Score;
+
+    /// Updates the score of an entry that was accessed
+    fn on_access(&mut self, score: &mut Self::Score);
+
+    /// Called with the score of an entry evicted from the cache.
+    ///
+    /// The default implementation does nothing.
+    fn on_evict(&mut self, score: &Self::Score) {
+        let _ = score;
+    }
+}
+
+/// The Least Recently Used policy: the score of an entry is the time
+/// of its last access, measured by a logical clock.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Lru {
+    clock: u64,
+}
+
+impl<K> CachePolicy<K> for Lru {
+    type Score = u64;
+
+    fn on_insert(&mut self, _key: &K) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn on_access(&mut self, score: &mut u64) {
+        self.clock += 1;
+        *score = self.clock;
+    }
+}
+
+/// The Least Frequently Used policy, with dynamic aging.
+///
+/// The score of an entry is the number of its accesses, insertion included,
+/// plus the age of the cache when it was last accessed. The age is the
+/// score of the last entry evicted, so the entries that were popular long
+/// ago do not stay in the cache forever.
+///
+/// The score is the pair (priority, accesses), where the priority decides the order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Lfu {
+    age: u64,
+}
+
+impl<K> CachePolicy<K> for Lfu {
+    type Score = (u64, u64);
+
+    fn on_insert(&mut self, _key: &K) -> (u64, u64) {
+        (self.age + 1, 1)
+    }
+
+    fn on_access(&mut self, score: &mut (u64, u64)) {
+        score.1 += 1;
+        score.0 = self.age + score.1;
+    }
+
+    fn on_evict(&mut self, score: &(u64, u64)) {
+        self.age = score.0;
+    }
+}
+
+/// 2^512, the factor by which the weights of [`Lrfu`] are scaled down
+const LRFU_SCALE: f64 = 1.3407807929942597e154;
+
+/// The Least Recently/Frequently Used policy, that weighs both the recency
+/// and the frequency of the accesses.
+///
+/// The score of an entry is its Combined Recency and Frequency (CRF):
+/// each of its accesses, insertion included, counts as `decay` raised to
+/// the number of accesses made to the cache after it. With a `decay` of 1
+/// the policy counts the accesses, like [`Lfu`] without the aging, and as
+/// the `decay` gets closer to 0 it behaves more and more like [`Lru`].
+///
+/// # Example
+/// ```rust
+/// use priority_queue::priority_cache::{Lrfu, PriorityCache};
+///
+/// let mut cache = PriorityCache::with_policy(2, Lrfu::new(0.9));
+/// cache.insert("a", 1);
+/// for _ in 0..10 {
+///     cache.get("a");
+/// }
+/// cache.insert("b", 2);
+/// cache.insert("c", 3);
+///
+/// // "a" was used often enough to survive the more recent "b"
+/// assert!(cache.contains("a"));
+/// assert!(!cache.contains("b"));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lrfu {
+    decay: f64,
+    /// The weight of the last access, that grows by `1 / decay` at every
+    /// access instead of shrinking the weights of all the older ones
+    weight: f64,
+    /// How many times the weight was scaled down by 2^512
+    epoch: u64,
+}
+
+impl Lrfu {
+    /// Creates a policy where the weight of an access is multiplied
+    /// by `decay` at every later access to the cache.
+    ///
+    /// # Panics
+    /// Panics if `decay` is not in `(0, 1]`.
+    pub fn new(decay: f64) -> Self {
+        assert!(decay > 0.0 && decay <= 1.0, "the decay must be in (0, 1]");
+        Self {
+            // smaller values would overflow the weights, and make no
+            // difference since an access already outweighs all the older ones
+            decay: decay.max(1e-100),
+            weight: 1.0,
+            epoch: 0,
+        }
+    }
+
+    fn tick(&mut self) {
+        self.weight /= self.decay;
+        if self.weight > LRFU_SCALE {
+            self.weight /= LRFU_SCALE;
+            self.epoch += 1;
+        }
+    }
+}
+
+impl<K> CachePolicy<K> for Lrfu {
+    type Score = Crf;
+
+    fn on_insert(&mut self, _key: &K) -> Crf {
+        self.tick();
+        Crf {
+            value: self.weight,
+            epoch: self.epoch,
+        }
+    }
+
+    fn on_access(&mut self, score: &mut Crf) {
+        self.tick();
+        score.value = score.scaled(self.epoch) + self.weight;
+        score.epoch = self.epoch;
+    }
+}
+
+/// The score of an entry with the [`Lrfu`] policy.
+///
+/// It is the sum of the weights of the accesses, scaled down by 2^512
+/// for every epoch, and totally ordered.
+#[derive(Clone, Copy, Debug)]
+pub struct Crf {
+    value: f64,
+    epoch: u64,
+}
+
+impl Crf {
+    /// Returns the value scaled down to `epoch`, that is not older than its own
+    fn scaled(&self, epoch: u64) -> f64 {
+        let mut value = self.value;
+        // after a few epochs the value is 0
+        for _ in self.epoch..epoch {
+            if value == 0.0 {
+                break;
+            }
+            value /= LRFU_SCALE;
+        }
+        value
+    }
+}
+
+impl PartialEq for Crf {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Crf {}
+
+impl PartialOrd for Crf {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Crf {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let epoch = self.epoch.max(other.epoch);
+        self.scaled(epoch).total_cmp(&other.scaled(epoch))
+    }
+}
+
+/// A cache with a fixed capacity, that evicts its entries in the order
+/// decided by a [`CachePolicy`].
+///
+/// The entries are kept in a [`PriorityQueue`] where the key is the item
+/// and the score computed by the policy is the priority, in reverse order.
+/// So the values are found in **O(1)** time, and the score is updated
+/// on every access with a `change_priority` in **O(log(N))** time.
+///
+/// [`get`](PriorityCache::get) counts as an access, while
+/// [`peek`](PriorityCache::peek) does not.
+/// When an entry is inserted in a full cache, the entry with the lowest
+/// score is evicted, and passed to the callback set with
+/// [`set_on_evict`](PriorityCache::set_on_evict), if any.
+///
+/// The default policy is [`Lru`].
+///
+/// # Example
+/// ```rust
+/// use priority_queue::PriorityCache;
+/// use std::sync::{Arc, Mutex};
+///
+/// let evicted = Arc::new(Mutex::new(vec![]));
+/// let mut cache = PriorityCache::new(2);
+/// let log = evicted.clone();
+/// cache.set_on_evict(move |k, v| log.lock().unwrap().push((k, v)));
+///
+/// cache.insert("a", 1);
+/// cache.insert("b", 2);
+/// assert_eq!(cache.get("a"), Some(&1));
+/// // "b" is the least recently used
+/// cache.insert("c", 3);
+/// assert_eq!(*evicted.lock().unwrap(), vec![("b", 2)]);
+///
+/// // peek does not refresh "a"
+/// assert_eq!(cache.peek("a"), Some(&1));
+/// cache.insert("d", 4);
+/// assert_eq!(*evicted.lock().unwrap(), vec![("b", 2), ("a", 1)]);
+/// ```
+#[cfg(has_std)]
+pub struct PriorityCache<K, V, C = Lru, H = RandomState>
+where
+    K: Hash + Eq,
+    C: CachePolicy<K>,
+{
+    queue: PriorityQueue<K, Slot<V, C::Score>, H>,
+    policy: C,
+    capacity: usize,
+    on_evict: Option<Box<dyn FnMut(K, V) + Send>>,
+}
+
+#[cfg(not(has_std))]
+pub struct PriorityCache<K, V, C, H>
+where
+    K: Hash + Eq,
+    C: CachePolicy<K>,
+{
+    queue: PriorityQueue<K, Slot<V, C::Score>, H>,
+    policy: C,
+    capacity: usize,
+    on_evict: Option<Box<dyn FnMut(K, V) + Send>>,
+}
+
+/// A value with its score, ordered by reverse score only
+struct Slot<V, S> {
+    value: V,
+    score: S,
+}
+
+impl<V, S: Ord> PartialEq for Slot<V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.score == other.score
+    }
+}
+
+impl<V, S: Ord> Eq for Slot<V, S> {}
+
+impl<V, S: Ord> PartialOrd for Slot<V, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<V, S: Ord> Ord for Slot<V, S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.score.cmp(&self.score)
+    }
+}
+
+#[cfg(has_std)]
+impl<K, V> PriorityCache<K, V>
+where
+    K: Hash + Eq,
+{
+    /// Creates an empty LRU cache holding at most `capacity` entries
+    ///
+    /// # Panics
+    /// Panics if `capacity` is 0
+    pub fn new(capacity: usize) -> Self {
+        Self::with_policy(capacity, Lru::default())
+    }
+}
+
+#[cfg(has_std)]
+impl<K, V, C> PriorityCache<K, V, C>
+where
+    K: Hash + Eq,
+    C: CachePolicy<K>,
+{
+    /// Creates an empty cache holding at most `capacity` entries,
+    /// evicted according to `policy`
+    ///
+    /// # Panics
+    /// Panics if `capacity` is 0
+    pub fn with_policy(capacity: usize, policy: C) -> Self {
+        Self::with_policy_and_hasher(capacity, policy, RandomState::default())
+    }
+}
+
+impl<K, V, C, H> PriorityCache<K, V, C, H>
+where
+    K: Hash + Eq,
+    C: CachePolicy<K>,
+    H: BuildHasher,
+{
+    /// Creates an empty cache holding at most `capacity` entries,
+    /// evicted according to `policy`, with the specified hasher
+    ///
+    /// # Panics
+    /// Panics if `capacity` is 0
+    pub fn with_policy_and_hasher(capacity: usize, policy: C, hash_builder: H) -> Self {
+        assert!(capacity > 0, "the capacity of the cache must be positive");
+        Self {
+            queue: PriorityQueue::with_capacity_and_hasher(capacity, hash_builder),
+            policy,
+            capacity,
+            on_evict: None,
+        }
+    }
+
+    /// Sets the function called with the entries evicted to make room
+    /// for new ones, replacing the previous one.
+    ///
+    /// The function must be `Send`, so that the cache can be moved to,
+    /// or shared through a `Mutex` with, other threads.
+    pub fn set_on_evict<F>(&mut self, on_evict: F)
+    where
+        F: FnMut(K, V) + Send + 'static,
+    {
+        self.on_evict = Some(Box::new(on_evict));
+    }
+
+    /// Returns the maximum number of entries in the cache
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of entries in the cache
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns true if the cache contains no entries
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Returns the eviction policy
+    pub fn policy(&self) -> &C {
+        &self.policy
+    }
+
+    /// Returns an iterator in arbitrary order over the entries in the
+    /// cache, without accessing them
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.queue.iter().map(|(k, slot)| (k, &slot.value))
+    }
+
+    /// Returns true if `key` is in the cache, without accessing it
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.queue.get_priority(key).is_some()
+    }
+
+    /// Returns the value of `key` without accessing it, so its score
+    /// does not change
+    ///
+    /// Computes in **O(1)** time.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.queue.get_priority(key).map(|slot| &slot.value)
+    }
+
+    /// Returns the value of `key`, updating its score
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.queue.get_priority(key)?;
+        let policy = &mut self.policy;
+        self.queue
+            .change_priority_by(key, |slot| policy.on_access(&mut slot.score));
+        self.peek(key)
+    }
+
+    /// Inserts the value of `key` in the cache.
+    ///
+    /// If `key` was already in the cache, its value is replaced, counting
+    /// as an access, and the old value is returned.
+    /// Otherwise, if the cache is full, the entry with the lowest score is
+    /// evicted and passed to the eviction callback, and `None` is returned.
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if self.queue.get_priority(&key).is_some() {
+            let policy = &mut self.policy;
+            let mut old = None;
+            self.queue.change_priority_by(&key, |slot| {
+                policy.on_access(&mut slot.score);
+                old = Some(std::mem::replace(&mut slot.value, value));
+            });
+            return old;
+        }
+        if self.queue.len() >= self.capacity {
+            if let Some((k, v)) = self.evict() {
+                if let Some(on_evict) = self.on_evict.as_mut() {
+                    on_evict(k, v);
+                }
+            }
+        }
+        let score = self.policy.on_insert(&key);
+        self.queue.push(key, Slot { value, score });
+        None
+    }
+
+    /// Evicts the entry with the lowest score and returns it, without
+    /// calling the eviction callback
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn evict(&mut self) -> Option<(K, V)> {
+        let (key, slot) = self.queue.pop()?;
+        self.policy.on_evict(&slot.score);
+        Some((key, slot.value))
+    }
+
+    /// Removes `key` from the cache, returning its value if present
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.queue.remove(key).map(|(_, slot)| slot.value)
+    }
+
+    /// Drops all the entries, without calling the eviction callback
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+}
+
+impl<K, V, C, H> fmt::Debug for PriorityCache<K, V, C, H>
+where
+    K: Hash + Eq + fmt::Debug,
+    V: fmt::Debug,
+    C: CachePolicy<K>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.queue
+                    .store
+                    .map
+                    .iter()
+                    .map(|(k, slot)| (k, &slot.value)),
+            )
+            .finish()
+    }
+}
@@ -0,0 +1,428 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+//! This module contains the [`PriorityCounter`] type.
+//!
+//! See the type level documentation for more details and examples.
+
+#[cfg(not(has_std))]
+use crate::std;
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+use crate::iterator_ext::select_by;
+use crate::DoublePriorityQueue;
+
+use std::borrow::Borrow;
+use std::cmp::{Eq, Ord, PartialEq};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::{Extend, FromIterator, IntoIterator, Iterator};
+
+/// A multiset that counts the occurrences of its items, like Python's
+/// `collections.Counter`, and keeps them ordered by their counts.
+///
+/// The counts are the priorities of a [`DoublePriorityQueue`], so both
+/// the most and the least common items are found in **O(1)** time,
+/// and incrementing or decrementing a count takes **O(log(N))** time.
+///
+/// Only the items with a positive count are stored: an item whose count
+/// drops to zero is removed, and looking up an item that is not stored
+/// gives 0.
+///
+/// The item is of type I, that must implement `Hash` and `Eq`.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::PriorityCounter;
+///
+/// let words: PriorityCounter<_> = "the cat and the dog and the bird"
+///     .split(' ')
+///     .collect();
+///
+/// assert_eq!(words.get("the"), 3);
+/// assert_eq!(words.get("fish"), 0);
+/// assert_eq!(words.total(), 8);
+/// assert_eq!(words.most_common(2), vec![(&"the", 3), (&"and", 2)]);
+/// ```
+#[derive(Clone)]
+#[cfg(has_std)]
+pub struct PriorityCounter<I, H = RandomState>
+where
+    I: Hash + Eq,
+{
+    queue: DoublePriorityQueue<I, usize, H>,
+    total: usize,
+}
+
+#[derive(Clone)]
+#[cfg(not(has_std))]
+pub struct PriorityCounter<I, H>
+where
+    I: Hash + Eq,
+{
+    queue: DoublePriorityQueue<I, usize, H>,
+    total: usize,
+}
+
+impl<I, H> Default for PriorityCounter<I, H>
+where
+    I: Hash + Eq,
+    H: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+#[cfg(has_std)]
+impl<I> PriorityCounter<I>
+where
+    I: Hash + Eq,
+{
+    /// Creates an empty `PriorityCounter`
+    pub fn new() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+impl<I, H> PriorityCounter<I, H>
+where
+    I: Hash + Eq,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty `PriorityCounter` with the default hasher
+    pub fn with_default_hasher() -> Self {
+        Self::with_hasher(H::default())
+    }
+}
+
+impl<I, H> PriorityCounter<I, H>
+where
+    I: Hash + Eq,
+    H: BuildHasher,
+{
+    /// Returns the number of distinct items in the counter
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns true if the counter contains no items
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Returns the sum of all the counts
+    ///
+    /// Computes in **O(1)** time.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns an iterator in arbitrary order over the
+    /// (item, count) pairs in the counter
+    pub fn iter(&self) -> impl Iterator<Item = (&I, usize)> {
+        self.queue.iter().map(|(i, c)| (i, *c))
+    }
+
+    /// Returns the underlying queue, with the counts as priorities
+    pub fn as_queue(&self) -> &DoublePriorityQueue<I, usize, H> {
+        &self.queue
+    }
+
+    /// Drops all the items
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.total = 0;
+    }
+
+    /// Returns the `n` items with the highest counts, in descending order
+    /// of count, without removing them.
+    ///
+    /// The order of the items with the same count is arbitrary.
+    ///
+    /// Computes in **O(1)** time if `n` is 1, in **O(N + n log(n))**
+    /// time otherwise.
+    pub fn most_common(&self, n: usize) -> Vec<(&I, usize)> {
+        if n == 1 {
+            return self
+                .queue
+                .peek_max()
+                .map(|(i, c)| (i, *c))
+                .into_iter()
+                .collect();
+        }
+        select_by(self.iter(), n, |a, b| b.1.cmp(&a.1))
+    }
+
+    /// Returns the `n` items with the lowest counts, in ascending order
+    /// of count, without removing them.
+    ///
+    /// The order of the items with the same count is arbitrary.
+    ///
+    /// Computes in **O(1)** time if `n` is 1, in **O(N + n log(n))**
+    /// time otherwise.
+    pub fn least_common(&self, n: usize) -> Vec<(&I, usize)> {
+        if n == 1 {
+            return self
+                .queue
+                .peek_min()
+                .map(|(i, c)| (i, *c))
+                .into_iter()
+                .collect();
+        }
+        select_by(self.iter(), n, |a, b| a.1.cmp(&b.1))
+    }
+}
+
+impl<I, H> PriorityCounter<I, H>
+where
+    I: Hash + Eq,
+    H: BuildHasher,
+{
+    /// Creates an empty `PriorityCounter` with the specified hasher
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self {
+            queue: DoublePriorityQueue::with_hasher(hash_builder),
+            total: 0,
+        }
+    }
+
+    /// Returns the count of `item`, that is 0 if it is not in the counter
+    pub fn get<Q>(&self, item: &Q) -> usize
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.queue.get_priority(item).copied().unwrap_or(0)
+    }
+
+    /// Returns true if `item` has a positive count
+    pub fn contains<Q>(&self, item: &Q) -> bool
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.queue.get_priority(item).is_some()
+    }
+
+    /// Adds `by` to the count of `item`, inserting it if needed,
+    /// and returns the new count.
+    ///
+    /// An item that is not in the counter is not inserted if `by` is 0.
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn increment(&mut self, item: I, by: usize) -> usize {
+        self.total += by;
+        if let Some(count) = self.queue.get_priority(&item).copied() {
+            self.queue.change_priority(&item, count + by);
+            count + by
+        } else {
+            if by > 0 {
+                self.queue.push(item, by);
+            }
+            by
+        }
+    }
+
+    /// Subtracts `by` from the count of `item` and returns the new count.
+    ///
+    /// The count does not go below zero, and the item is removed from
+    /// the counter when it reaches zero.
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn decrement<Q>(&mut self, item: &Q, by: usize) -> usize
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let count = self.get(item);
+        if count == 0 {
+            return 0;
+        }
+        if by >= count {
+            self.remove(item);
+            0
+        } else {
+            self.queue.change_priority(item, count - by);
+            self.total -= by;
+            count - by
+        }
+    }
+
+    /// Removes `item` from the counter, returning it with its count, if present
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn remove<Q>(&mut self, item: &Q) -> Option<(I, usize)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let removed = self.queue.remove(item);
+        if let Some((_, count)) = removed {
+            self.total -= count;
+        }
+        removed
+    }
+
+    /// Removes and returns the item with the highest count, if any
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn pop_most_common(&mut self) -> Option<(I, usize)> {
+        let popped = self.queue.pop_max();
+        if let Some((_, count)) = popped {
+            self.total -= count;
+        }
+        popped
+    }
+
+    /// Removes and returns the item with the lowest count, if any
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn pop_least_common(&mut self) -> Option<(I, usize)> {
+        let popped = self.queue.pop_min();
+        if let Some((_, count)) = popped {
+            self.total -= count;
+        }
+        popped
+    }
+}
+
+impl<I, H> PriorityCounter<I, H>
+where
+    I: Hash + Eq + Clone,
+    H: BuildHasher + Clone,
+{
+    /// Returns a new counter where the count of each item is the sum of
+    /// its counts in `self` and `other`
+    ///
+    /// Computes in **O((N + M)log(N + M))** time.
+    pub fn sum<H2>(&self, other: &PriorityCounter<I, H2>) -> Self
+    where
+        H2: BuildHasher,
+    {
+        let mut result = self.clone();
+        for (item, count) in other.iter() {
+            result.increment(item.clone(), count);
+        }
+        result
+    }
+
+    /// Returns a new counter where the count of each item is its count in
+    /// `self` minus its count in `other`, keeping only the positive results
+    ///
+    /// Computes in **O((N + M)log(N))** time.
+    pub fn difference<H2>(&self, other: &PriorityCounter<I, H2>) -> Self
+    where
+        H2: BuildHasher,
+    {
+        let mut result = self.clone();
+        for (item, count) in other.iter() {
+            result.decrement(item, count);
+        }
+        result
+    }
+
+    /// Returns a new counter where the count of each item is the minimum
+    /// of its counts in `self` and `other`
+    ///
+    /// Computes in **O(N log(N))** time.
+    pub fn intersection<H2>(&self, other: &PriorityCounter<I, H2>) -> Self
+    where
+        H2: BuildHasher,
+    {
+        let mut result = Self::with_hasher(self.queue.hasher().clone());
+        for (item, count) in self.iter() {
+            result.increment(item.clone(), count.min(other.get(item)));
+        }
+        result
+    }
+
+    /// Returns a new counter where the count of each item is the maximum
+    /// of its counts in `self` and `other`
+    ///
+    /// Computes in **O((N + M)log(N + M))** time.
+    pub fn union<H2>(&self, other: &PriorityCounter<I, H2>) -> Self
+    where
+        H2: BuildHasher,
+    {
+        let mut result = self.clone();
+        for (item, count) in other.iter() {
+            let own = result.get(item);
+            if count > own {
+                result.increment(item.clone(), count - own);
+            }
+        }
+        result
+    }
+}
+
+impl<I, H> FromIterator<I> for PriorityCounter<I, H>
+where
+    I: Hash + Eq,
+    H: BuildHasher + Default,
+{
+    /// Counts the occurrences of the items yielded by the iterator
+    fn from_iter<IT>(iter: IT) -> Self
+    where
+        IT: IntoIterator<Item = I>,
+    {
+        let mut counter = Self::with_default_hasher();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<I, H> Extend<I> for PriorityCounter<I, H>
+where
+    I: Hash + Eq,
+    H: BuildHasher,
+{
+    /// Increments by one the count of each item yielded by the iterator
+    fn extend<IT>(&mut self, iter: IT)
+    where
+        IT: IntoIterator<Item = I>,
+    {
+        for item in iter {
+            self.increment(item, 1);
+        }
+    }
+}
+
+impl<I, H1, H2> PartialEq<PriorityCounter<I, H2>> for PriorityCounter<I, H1>
+where
+    I: Hash + Eq,
+    H1: BuildHasher,
+    H2: BuildHasher,
+{
+    fn eq(&self, other: &PriorityCounter<I, H2>) -> bool {
+        self.total == other.total && self.queue == other.queue
+    }
+}
+
+impl<I, H> fmt::Debug for PriorityCounter<I, H>
+where
+    I: Hash + Eq + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.queue.store.map.iter()).finish()
+    }
+}
@@ -0,0 +1,130 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+//! This module defines iterator types that are used only with the [`PriorityMap`].
+//!
+//! Usually you don't need to explicitly `use` any of the types declared here.
+
+#[cfg(not(has_std))]
+pub(crate) mod std {
+    pub use core::*;
+    pub mod alloc {
+        pub use ::alloc::*;
+    }
+    pub mod collections {
+        pub use ::alloc::collections::*;
+    }
+    pub use ::alloc::vec;
+}
+
+use std::cmp::{Eq, Ord};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::hash::Hash;
+use std::iter::*;
+
+use super::Slot;
+use crate::PriorityMap;
+
+/// An iterator in arbitrary order over the triples
+/// `(key, value, priority)` in the map.
+///
+/// It can be obtained calling the `iter` method.
+pub struct Iter<'a, K, V, P> {
+    pub(crate) iter: ::indexmap::map::Iter<'a, K, Slot<V, P>>,
+}
+
+impl<'a, K, V, P> Iterator for Iter<'a, K, V, P> {
+    type Item = (&'a K, &'a V, &'a P);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|(k, slot)| (k, &slot.value, &slot.priority))
+    }
+}
+
+/// A mutable iterator in arbitrary order over the triples
+/// `(key, value, priority)` in the map.
+///
+/// It can be obtained calling the `iter_mut` method.
+///
+/// Only the value is mutable, so the map does not need to be
+/// restored when the iterator goes out of scope.
+pub struct IterMut<'a, K, V, P> {
+    pub(crate) iter: ::indexmap::map::IterMut<'a, K, Slot<V, P>>,
+}
+
+impl<'a, K, V, P> Iterator for IterMut<'a, K, V, P> {
+    type Item = (&'a K, &'a mut V, &'a P);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|(k, slot)| (k, &mut slot.value, &slot.priority))
+    }
+}
+
+/// An iterator in arbitrary order over the triples
+/// `(key, value, priority)` that consumes the map.
+///
+/// It can be obtained calling the `into_iter` method from the `IntoIterator` trait.
+pub struct IntoIter<K, V, P> {
+    pub(crate) iter: ::indexmap::map::IntoIter<K, Slot<V, P>>,
+}
+
+impl<K, V, P> Iterator for IntoIter<K, V, P> {
+    type Item = (K, V, P);
+    fn next(&mut self) -> Option<(K, V, P)> {
+        self.iter
+            .next()
+            .map(|(k, slot)| (k, slot.value, slot.priority))
+    }
+}
+
+/// A consuming iterator over the triples `(key, value, priority)` of the `PriorityMap`
+/// ordered by priority, from the highest to the lowest.
+///
+/// It can be obtained calling the `into_sorted_iter` method.
+#[cfg(has_std)]
+pub struct IntoSortedIter<K, V, P, H = RandomState>
+where
+    K: Hash + Eq,
+    P: Ord,
+{
+    pub(crate) pm: PriorityMap<K, V, P, H>,
+}
+
+#[cfg(not(has_std))]
+pub struct IntoSortedIter<K, V, P, H>
+where
+    K: Hash + Eq,
+    P: Ord,
+{
+    pub(crate) pm: PriorityMap<K, V, P, H>,
+}
+
+impl<K, V, P, H> Iterator for IntoSortedIter<K, V, P, H>
+where
+    K: Hash + Eq,
+    P: Ord,
+{
+    type Item = (K, V, P);
+    fn next(&mut self) -> Option<(K, V, P)> {
+        self.pm.pop()
+    }
+}
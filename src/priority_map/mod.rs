@@ -0,0 +1,614 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+//! This module contains the [`PriorityMap`] type and the related iterators.
+//!
+//! See the type level documentation for more details and examples.
+
+pub mod iterators;
+
+use crate::PriorityQueue;
+use iterators::*;
+
+use std::borrow::Borrow;
+use std::cmp::{Eq, Ord, Ordering, PartialEq};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::iter::{Extend, FromIterator, IntoIterator, Iterator};
+use std::mem::replace;
+
+/// The payload stored for each key of a [`PriorityMap`].
+///
+/// Only the priority takes part in the comparisons, so the value
+/// never influences the position of the entry in the heap.
+#[derive(Clone)]
+pub(crate) struct Slot<V, P> {
+    pub value: V,
+    pub priority: P,
+}
+
+impl<V, P: Ord> PartialEq for Slot<V, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<V, P: Ord> Eq for Slot<V, P> {}
+
+impl<V, P: Ord> PartialOrd for Slot<V, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<V, P: Ord> Ord for Slot<V, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority.cmp(&other.priority)
+    }
+}
+
+/// A priority queue that separates the hashed key from the payload.
+///
+/// Each entry is made of a key of type K, that must implement `Hash` and `Eq`,
+/// a value of type V, that has no requirements, and a priority of type P,
+/// that must implement `std::cmp::Ord`.
+///
+/// Only the key is used to find the entries, so the value can be freely
+/// modified through [`get_value_mut`](PriorityMap::get_value_mut) or
+/// [`iter_mut`](PriorityMap::iter_mut) without invalidating the hash table.
+///
+/// The entry with the greatest priority is extracted first, like in
+/// [`PriorityQueue`], on which this data structure is built.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::PriorityMap;
+///
+/// let mut pm = PriorityMap::new();
+///
+/// assert!(pm.is_empty());
+/// pm.insert(1, "Apples", 5);
+/// pm.insert(2, "Bananas", 8);
+/// pm.insert(3, "Strawberries", 23);
+///
+/// assert_eq!(pm.peek(), Some((&3, &"Strawberries", &23)));
+///
+/// pm.change_priority(&2, 25);
+/// *pm.get_value_mut(&2).unwrap() = "Yellow Bananas";
+/// assert_eq!(pm.pop(), Some((2, "Yellow Bananas", 25)));
+/// ```
+#[derive(Clone)]
+#[cfg(has_std)]
+pub struct PriorityMap<K, V, P, H = RandomState>
+where
+    K: Hash + Eq,
+    P: Ord,
+{
+    pub(crate) pq: PriorityQueue<K, Slot<V, P>, H>,
+}
+
+#[derive(Clone)]
+#[cfg(not(has_std))]
+pub struct PriorityMap<K, V, P, H>
+where
+    K: Hash + Eq,
+    P: Ord,
+{
+    pub(crate) pq: PriorityQueue<K, Slot<V, P>, H>,
+}
+
+impl<K, V, P, H> Default for PriorityMap<K, V, P, H>
+where
+    K: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+#[cfg(has_std)]
+impl<K, V, P> PriorityMap<K, V, P>
+where
+    K: Hash + Eq,
+    P: Ord,
+{
+    /// Creates an empty `PriorityMap`
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates an empty `PriorityMap` with the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_default_hasher(capacity)
+    }
+}
+
+impl<K, V, P, H> PriorityMap<K, V, P, H>
+where
+    K: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty `PriorityMap` with the default hasher
+    pub fn with_default_hasher() -> Self {
+        Self::with_capacity_and_default_hasher(0)
+    }
+
+    /// Creates an empty `PriorityMap` with the specified capacity and default hasher
+    pub fn with_capacity_and_default_hasher(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, H::default())
+    }
+}
+
+impl<K, V, P, H> PriorityMap<K, V, P, H>
+where
+    K: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    /// Creates an empty `PriorityMap` with the specified hasher
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    /// Creates an empty `PriorityMap` with the specified capacity and hasher
+    ///
+    /// The internal collections will be able to hold at least `capacity`
+    /// elements without reallocating.
+    /// If `capacity` is 0, there will be no allocation.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: H) -> Self {
+        Self {
+            pq: PriorityQueue::with_capacity_and_hasher(capacity, hash_builder),
+        }
+    }
+}
+
+impl<K, V, P, H> PriorityMap<K, V, P, H>
+where
+    K: Hash + Eq,
+    P: Ord,
+{
+    /// Returns an iterator in arbitrary order over the
+    /// (key, value, priority) entries in the map
+    pub fn iter(&self) -> Iter<'_, K, V, P> {
+        Iter {
+            iter: self.pq.store.map.iter(),
+        }
+    }
+
+    /// Returns an iterator in arbitrary order over the
+    /// (key, value, priority) entries in the map.
+    ///
+    /// Only the value is yielded as a mutable reference: since it does not
+    /// take part in hashing nor in ordering, it can be freely modified.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V, P> {
+        IterMut {
+            iter: self.pq.store.map.iter_mut(),
+        }
+    }
+
+    /// Returns the (key, value, priority) entry with the greatest
+    /// priority in the map, or None if it is empty.
+    ///
+    /// Computes in **O(1)** time
+    pub fn peek(&self) -> Option<(&K, &V, &P)> {
+        self.pq
+            .peek()
+            .map(|(k, slot)| (k, &slot.value, &slot.priority))
+    }
+
+    /// Returns the (key, value, priority) entry with the greatest
+    /// priority in the map, or None if it is empty.
+    ///
+    /// The value is returned as a mutable reference.
+    ///
+    /// Computes in **O(1)** time
+    pub fn peek_mut(&mut self) -> Option<(&K, &mut V, &P)> {
+        if self.pq.store.size == 0 {
+            return None;
+        }
+        self.pq
+            .store
+            .map
+            .get_index_mut(unsafe { *self.pq.store.heap.get_unchecked(0) })
+            .map(|(k, slot)| (k, &mut slot.value, &slot.priority))
+    }
+
+    /// Returns the number of elements the internal map can hold without
+    /// reallocating.
+    ///
+    /// This number is a lower bound; the map might be able to hold more,
+    /// but is guaranteed to be able to hold at least this many.
+    pub fn capacity(&self) -> usize {
+        self.pq.capacity()
+    }
+
+    /// Shrinks the capacity of the internal data structures
+    /// that support this operation as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.pq.shrink_to_fit();
+    }
+
+    /// Removes the entry with the greatest priority from
+    /// the map and returns the triple (key, value, priority),
+    /// or None if the map is empty.
+    pub fn pop(&mut self) -> Option<(K, V, P)> {
+        self.pq
+            .pop()
+            .map(|(k, slot)| (k, slot.value, slot.priority))
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.pq.len()
+    }
+
+    /// Returns true if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.pq.is_empty()
+    }
+
+    /// Generates a new iterator from self that
+    /// will extract the entries from the one with the highest priority
+    /// to the lowest one.
+    pub fn into_sorted_iter(self) -> IntoSortedIter<K, V, P, H> {
+        IntoSortedIter { pm: self }
+    }
+}
+
+impl<K, V, P, H> PriorityMap<K, V, P, H>
+where
+    K: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    /// Reserves capacity for at least `additional` more entries to be inserted
+    /// in the given `PriorityMap`. The collection may reserve more space to avoid
+    /// frequent reallocations. After calling `reserve`, capacity will be
+    /// greater than or equal to `self.len() + additional`. Does nothing if
+    /// capacity is already sufficient.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        self.pq.reserve(additional);
+    }
+
+    /// Insert the key-value-priority triple into the map.
+    ///
+    /// If an entry with a key equal to `key` was already in the map,
+    /// its value and priority are replaced and the old ones returned in `Some`;
+    /// otherwise, returns `None`.
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn insert(&mut self, key: K, value: V, priority: P) -> Option<(V, P)> {
+        self.pq
+            .push(key, Slot { value, priority })
+            .map(|slot| (slot.value, slot.priority))
+    }
+
+    /// Change the priority of an entry returning the old value of priority,
+    /// or `None` if the key wasn't in the map.
+    ///
+    /// The entry is found in **O(1)** thanks to the hash table.
+    /// The operation is performed in **O(log(N))** time.
+    pub fn change_priority<Q>(&mut self, key: &Q, new_priority: P) -> Option<P>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let mut old = None;
+        self.pq.change_priority_by(key, |slot| {
+            old = Some(replace(&mut slot.priority, new_priority));
+        });
+        old
+    }
+
+    /// Change the priority of an entry using the provided function.
+    /// The entry is found in **O(1)** thanks to the hash table.
+    /// The operation is performed in **O(log(N))** time (worst case).
+    pub fn change_priority_by<Q, F>(&mut self, key: &Q, priority_setter: F)
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&mut P),
+    {
+        self.pq
+            .change_priority_by(key, |slot| priority_setter(&mut slot.priority));
+    }
+
+    /// Get the priority of an entry, or `None`, if the key is not in the map
+    pub fn get_priority<Q>(&self, key: &Q) -> Option<&P>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.pq.get_priority(key).map(|slot| &slot.priority)
+    }
+
+    /// Get the value associated to a key, or `None`, if the key is not in the map
+    pub fn get_value<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.pq.get_priority(key).map(|slot| &slot.value)
+    }
+
+    /// Get a mutable reference to the value associated to a key,
+    /// or `None`, if the key is not in the map.
+    ///
+    /// Since the value is not hashed nor compared, it can be modified
+    /// in any way without breaking the map.
+    pub fn get_value_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.pq.store.map.get_mut(key).map(|slot| &mut slot.value)
+    }
+
+    /// Get the triple (key, value, priority) of an arbitrary entry, as reference
+    /// or `None` if the key is not in the map.
+    pub fn get<Q>(&self, key: &Q) -> Option<(&K, &V, &P)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.pq
+            .get(key)
+            .map(|(k, slot)| (k, &slot.value, &slot.priority))
+    }
+
+    /// Returns true if the map contains an entry for the given key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.pq.store.map.contains_key(key)
+    }
+
+    /// Remove an arbitrary entry from the map.
+    /// Returns the (key, value, priority) triple or None if the key
+    /// is not found in the map.
+    ///
+    /// The operation is performed in **O(log(N))** time (worst case).
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<(K, V, P)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.pq
+            .remove(key)
+            .map(|(k, slot)| (k, slot.value, slot.priority))
+    }
+
+    /// Drops all entries from the map
+    pub fn clear(&mut self) {
+        self.pq.clear();
+    }
+
+    /// Move all entries of the `other` map to `self`
+    /// ignoring the keys already in `self`
+    /// At the end, `other` will be empty.
+    ///
+    /// **Note** that at the end, the value and priority of the duplicated
+    /// keys inside self may be the ones of the entries in other,
+    /// if other is longer than self
+    pub fn append(&mut self, other: &mut Self) {
+        self.pq.append(&mut other.pq);
+    }
+}
+
+impl<K, V, P, H> FromIterator<(K, V, P)> for PriorityMap<K, V, P, H>
+where
+    K: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    fn from_iter<IT>(iter: IT) -> Self
+    where
+        IT: IntoIterator<Item = (K, V, P)>,
+    {
+        Self {
+            pq: iter
+                .into_iter()
+                .map(|(key, value, priority)| (key, Slot { value, priority }))
+                .collect(),
+        }
+    }
+}
+
+impl<K, V, P, H> Extend<(K, V, P)> for PriorityMap<K, V, P, H>
+where
+    K: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (K, V, P)>>(&mut self, iter: T) {
+        self.pq.extend(
+            iter.into_iter()
+                .map(|(key, value, priority)| (key, Slot { value, priority })),
+        );
+    }
+}
+
+impl<K, V, P, H> IntoIterator for PriorityMap<K, V, P, H>
+where
+    K: Hash + Eq,
+    P: Ord,
+{
+    type Item = (K, V, P);
+    type IntoIter = IntoIter<K, V, P>;
+    fn into_iter(self) -> IntoIter<K, V, P> {
+        IntoIter {
+            iter: self.pq.store.map.into_iter(),
+        }
+    }
+}
+
+impl<'a, K, V, P, H> IntoIterator for &'a PriorityMap<K, V, P, H>
+where
+    K: Hash + Eq,
+    P: Ord,
+{
+    type Item = (&'a K, &'a V, &'a P);
+    type IntoIter = Iter<'a, K, V, P>;
+    fn into_iter(self) -> Iter<'a, K, V, P> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, P, H> IntoIterator for &'a mut PriorityMap<K, V, P, H>
+where
+    K: Hash + Eq,
+    P: Ord,
+{
+    type Item = (&'a K, &'a mut V, &'a P);
+    type IntoIter = IterMut<'a, K, V, P>;
+    fn into_iter(self) -> IterMut<'a, K, V, P> {
+        self.iter_mut()
+    }
+}
+
+use std::fmt;
+
+impl<K, V, P, H> fmt::Debug for PriorityMap<K, V, P, H>
+where
+    K: Hash + Eq + fmt::Debug,
+    V: fmt::Debug,
+    P: Ord + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|(k, v, p)| (k, (v, p))))
+            .finish()
+    }
+}
+
+impl<K, V1, P1, H1, V2, P2, H2> PartialEq<PriorityMap<K, V2, P2, H2>> for PriorityMap<K, V1, P1, H1>
+where
+    K: Hash + Eq,
+    V1: PartialEq<V2>,
+    P1: Ord + PartialEq<P2>,
+    P2: Ord,
+    H1: BuildHasher,
+    H2: BuildHasher,
+{
+    fn eq(&self, other: &PriorityMap<K, V2, P2, H2>) -> bool {
+        self.len() == other.len()
+            && self.iter().all(|(k, v, p)| {
+                matches!(other.pq.store.map.get(k),
+                         Some(slot) if *v == slot.value && *p == slot.priority)
+            })
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use std::cmp::{Eq, Ord};
+    use std::fmt;
+    use std::hash::{BuildHasher, Hash};
+    use std::marker::PhantomData;
+
+    use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+    use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+    use super::{PriorityMap, Slot};
+    use crate::store::Store;
+    use crate::PriorityQueue;
+
+    impl<K, V, P, H> Serialize for PriorityMap<K, V, P, H>
+    where
+        K: Hash + Eq + Serialize,
+        V: Serialize,
+        P: Ord + Serialize,
+        H: BuildHasher,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut seq_serializer = serializer.serialize_seq(Some(self.len()))?;
+            for entry in self {
+                seq_serializer.serialize_element(&entry)?;
+            }
+            seq_serializer.end()
+        }
+    }
+
+    impl<'de, K, V, P, H> Deserialize<'de> for PriorityMap<K, V, P, H>
+    where
+        K: Hash + Eq + Deserialize<'de>,
+        V: Deserialize<'de>,
+        P: Ord + Deserialize<'de>,
+        H: BuildHasher + Default,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<PriorityMap<K, V, P, H>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_seq(PriorityMapVisitor {
+                marker: PhantomData,
+            })
+        }
+    }
+
+    struct PriorityMapVisitor<K, V, P, H> {
+        marker: PhantomData<(K, V, P, H)>,
+    }
+
+    impl<'de, K, V, P, H> Visitor<'de> for PriorityMapVisitor<K, V, P, H>
+    where
+        K: Hash + Eq + Deserialize<'de>,
+        V: Deserialize<'de>,
+        P: Ord + Deserialize<'de>,
+        H: BuildHasher + Default,
+    {
+        type Value = PriorityMap<K, V, P, H>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "A priority map")
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E> {
+            Ok(PriorityMap::with_default_hasher())
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut store: Store<K, Slot<V, P>, H> =
+                Store::with_capacity_and_default_hasher(seq.size_hint().unwrap_or(0));
+            while let Some((key, value, priority)) = seq.next_element()? {
+                store.extend(Some((key, Slot { value, priority })));
+            }
+            let mut pq = PriorityQueue { store };
+            pq.heap_build();
+            Ok(PriorityMap { pq })
+        }
+    }
+}
@@ -0,0 +1,107 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+//! This module defines iterator types that are used only with the [`PriorityMultiQueue`].
+//!
+//! Usually you don't need to explicitly `use` any of the types declared here.
+
+#[cfg(not(has_std))]
+pub(crate) mod std {
+    pub use core::*;
+    pub mod alloc {
+        pub use ::alloc::*;
+    }
+    pub mod collections {
+        pub use ::alloc::collections::*;
+    }
+    pub use ::alloc::vec;
+}
+
+use std::cmp::{Eq, Ord};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::hash::Hash;
+use std::iter::*;
+
+use crate::priority_map::iterators as map_iterators;
+use crate::PriorityMultiQueue;
+
+/// An iterator in arbitrary order over the instances
+/// `(item, priority)` in the queue.
+///
+/// It can be obtained calling the `iter` method.
+pub struct Iter<'a, I, P> {
+    pub(crate) iter: map_iterators::Iter<'a, u64, I, P>,
+}
+
+impl<'a, I, P> Iterator for Iter<'a, I, P> {
+    type Item = (&'a I, &'a P);
+    fn next(&mut self) -> Option<(&'a I, &'a P)> {
+        self.iter.next().map(|(_, i, p)| (i, p))
+    }
+}
+
+/// An iterator in arbitrary order over the instances
+/// `(item, priority)` that consumes the queue.
+///
+/// It can be obtained calling the `into_iter` method from the `IntoIterator` trait.
+pub struct IntoIter<I, P> {
+    pub(crate) iter: map_iterators::IntoIter<u64, I, P>,
+}
+
+impl<I, P> Iterator for IntoIter<I, P> {
+    type Item = (I, P);
+    fn next(&mut self) -> Option<(I, P)> {
+        self.iter.next().map(|(_, i, p)| (i, p))
+    }
+}
+
+/// A consuming iterator over the instances `(item, priority)` of the `PriorityMultiQueue`
+/// ordered by priority, from the highest to the lowest.
+///
+/// It can be obtained calling the `into_sorted_iter` method.
+#[cfg(has_std)]
+pub struct IntoSortedIter<I, P, H = RandomState>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    pub(crate) pq: PriorityMultiQueue<I, P, H>,
+}
+
+#[cfg(not(has_std))]
+pub struct IntoSortedIter<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    pub(crate) pq: PriorityMultiQueue<I, P, H>,
+}
+
+impl<I, P, H> Iterator for IntoSortedIter<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: std::hash::BuildHasher,
+{
+    type Item = (I, P);
+    fn next(&mut self) -> Option<(I, P)> {
+        self.pq.pop()
+    }
+}
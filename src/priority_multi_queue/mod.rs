@@ -0,0 +1,549 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+//! This module contains the [`PriorityMultiQueue`] type and the related iterators.
+//!
+//! See the type level documentation for more details and examples.
+
+pub mod iterators;
+
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+use crate::PriorityMap;
+use iterators::*;
+
+use std::borrow::Borrow;
+use std::cmp::{Eq, Ord};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::iter::{Extend, FromIterator, IntoIterator, Iterator};
+
+use indexmap::map::IndexMap;
+use indexmap::set::IndexSet;
+
+/// A priority queue that can hold several instances of the same item,
+/// each one with its own priority.
+///
+/// The priority is of type P, that must implement `std::cmp::Ord`.
+///
+/// The item is of type I, that must implement `Hash`, `Eq` and `Clone`:
+/// every distinct item is stored once more in an index that keeps track of
+/// the identifiers of its instances in a set.
+///
+/// Every instance is stored inside a [`PriorityMap`] under a unique
+/// identifier, so `push`, `pop` and `peek` keep the same complexity of
+/// [`PriorityQueue`](crate::PriorityQueue), while the operations on the
+/// instances of a given item scan all of them.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::PriorityMultiQueue;
+///
+/// let mut pq = PriorityMultiQueue::new();
+///
+/// pq.push("Water the plants", 8);
+/// pq.push("Stretch", 10);
+/// pq.push("Water the plants", 18);
+///
+/// assert_eq!(pq.len(), 3);
+/// assert_eq!(pq.count(&"Water the plants"), 2);
+/// assert_eq!(pq.peek(), Some((&"Water the plants", &18)));
+///
+/// pq.change_priority_of_max_instance(&"Water the plants", 9);
+/// assert_eq!(pq.pop(), Some(("Stretch", 10)));
+/// assert_eq!(pq.pop(), Some(("Water the plants", 9)));
+/// ```
+#[derive(Clone)]
+#[cfg(has_std)]
+pub struct PriorityMultiQueue<I, P, H = RandomState>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    pub(crate) pm: PriorityMap<u64, I, P, H>,
+    pub(crate) instances: IndexMap<I, IndexSet<u64, H>, H>,
+    pub(crate) next_id: u64,
+}
+
+#[derive(Clone)]
+#[cfg(not(has_std))]
+pub struct PriorityMultiQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    pub(crate) pm: PriorityMap<u64, I, P, H>,
+    pub(crate) instances: IndexMap<I, IndexSet<u64, H>, H>,
+    pub(crate) next_id: u64,
+}
+
+impl<I, P, H> Default for PriorityMultiQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+#[cfg(has_std)]
+impl<I, P> PriorityMultiQueue<I, P>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    /// Creates an empty `PriorityMultiQueue`
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates an empty `PriorityMultiQueue` with the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_default_hasher(capacity)
+    }
+}
+
+impl<I, P, H> PriorityMultiQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty `PriorityMultiQueue` with the default hasher
+    pub fn with_default_hasher() -> Self {
+        Self::with_capacity_and_default_hasher(0)
+    }
+
+    /// Creates an empty `PriorityMultiQueue` with the specified capacity and default hasher
+    pub fn with_capacity_and_default_hasher(capacity: usize) -> Self {
+        Self {
+            pm: PriorityMap::with_capacity_and_default_hasher(capacity),
+            instances: IndexMap::with_capacity_and_hasher(capacity, H::default()),
+            next_id: 0,
+        }
+    }
+}
+
+impl<I, P, H> PriorityMultiQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Clone,
+{
+    /// Creates an empty `PriorityMultiQueue` with the specified hasher
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    /// Creates an empty `PriorityMultiQueue` with the specified capacity and hasher
+    ///
+    /// The internal collections will be able to hold at least `capacity`
+    /// instances without reallocating.
+    /// If `capacity` is 0, there will be no allocation.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: H) -> Self {
+        Self {
+            pm: PriorityMap::with_capacity_and_hasher(capacity, hash_builder.clone()),
+            instances: IndexMap::with_capacity_and_hasher(capacity, hash_builder),
+            next_id: 0,
+        }
+    }
+}
+
+impl<I, P, H> PriorityMultiQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    /// Returns an iterator in arbitrary order over the
+    /// (item, priority) instances in the queue
+    pub fn iter(&self) -> Iter<'_, I, P> {
+        Iter {
+            iter: self.pm.iter(),
+        }
+    }
+
+    /// Returns the couple (item, priority) with the greatest
+    /// priority in the queue, or None if it is empty.
+    ///
+    /// Computes in **O(1)** time
+    pub fn peek(&self) -> Option<(&I, &P)> {
+        self.pm.peek().map(|(_, i, p)| (i, p))
+    }
+
+    /// Returns the number of instances in the queue.
+    pub fn len(&self) -> usize {
+        self.pm.len()
+    }
+
+    /// Returns true if the queue contains no instances.
+    pub fn is_empty(&self) -> bool {
+        self.pm.is_empty()
+    }
+
+    /// Returns the number of distinct items in the queue.
+    pub fn distinct_len(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Generates a new iterator from self that
+    /// will extract the instances from the one with the highest priority
+    /// to the lowest one.
+    pub fn into_sorted_iter(self) -> IntoSortedIter<I, P, H> {
+        IntoSortedIter { pq: self }
+    }
+}
+
+impl<I, P, H> PriorityMultiQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    /// Insert a new instance of `item` with the given priority.
+    ///
+    /// The instances already in the queue are not affected.
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn push(&mut self, item: I, priority: P)
+    where
+        I: Clone,
+        H: Clone,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+        let hash_builder = self.instances.hasher().clone();
+        self.instances
+            .entry(item.clone())
+            .or_insert_with(|| IndexSet::with_hasher(hash_builder))
+            .insert(id);
+        self.pm.insert(id, item, priority);
+    }
+
+    /// Removes the instance with the greatest priority from
+    /// the queue and returns the pair (item, priority),
+    /// or None if the queue is empty.
+    ///
+    /// Computes in **O(log(N))** time
+    pub fn pop(&mut self) -> Option<(I, P)> {
+        let (id, item, priority) = self.pm.pop()?;
+        self.forget(&item, id);
+        Some((item, priority))
+    }
+
+    /// Returns the number of instances of `item` in the queue.
+    ///
+    /// Computes in **O(1)** time
+    pub fn count<Q>(&self, item: &Q) -> usize
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.instances.get(item).map_or(0, IndexSet::len)
+    }
+
+    /// Returns true if there is at least one instance of `item` in the queue.
+    pub fn contains<Q>(&self, item: &Q) -> bool
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.instances.contains_key(item)
+    }
+
+    /// Returns an iterator in arbitrary order over the priorities
+    /// of the instances of `item`.
+    pub fn priorities<'a, Q>(&'a self, item: &Q) -> impl Iterator<Item = &'a P> + 'a
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.instances
+            .get(item)
+            .into_iter()
+            .flatten()
+            .filter_map(move |id| self.pm.get_priority(id))
+    }
+
+    /// Remove the instance of `item` with the greatest priority.
+    /// Returns the (item, priority) couple or None if the item
+    /// is not found in the queue.
+    ///
+    /// Computes in **O(log(N) + K)** time, where K is the number of
+    /// instances of `item`, that are scanned to find the greatest priority.
+    pub fn remove_one<Q>(&mut self, item: &Q) -> Option<(I, P)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let id = self.max_instance(item)?;
+        let (_, item, priority) = self.pm.remove(&id)?;
+        self.forget(&item, id);
+        Some((item, priority))
+    }
+
+    /// Remove all the instances of `item`.
+    /// Returns the removed (item, priority) couples in arbitrary order.
+    ///
+    /// Computes in **O(K log(N))** time, where K is the number of
+    /// instances of `item`.
+    pub fn remove_all<Q>(&mut self, item: &Q) -> Vec<(I, P)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let ids = match self.instances.swap_remove(item) {
+            Some(ids) => ids,
+            None => return Vec::new(),
+        };
+        let pm = &mut self.pm;
+        ids.iter()
+            .filter_map(|id| pm.remove(id))
+            .map(|(_, i, p)| (i, p))
+            .collect()
+    }
+
+    /// Change the priority of the instance of `item` with the lowest priority,
+    /// returning the old value of priority, or `None` if the item wasn't in the queue.
+    ///
+    /// Computes in **O(log(N) + K)** time, where K is the number of
+    /// instances of `item`, that are scanned to find the lowest priority.
+    pub fn change_priority_of_min_instance<Q>(&mut self, item: &Q, new_priority: P) -> Option<P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let id = self.min_instance(item)?;
+        self.pm.change_priority(&id, new_priority)
+    }
+
+    /// Change the priority of the instance of `item` with the greatest priority,
+    /// returning the old value of priority, or `None` if the item wasn't in the queue.
+    ///
+    /// Computes in **O(log(N) + K)** time, where K is the number of
+    /// instances of `item`, that are scanned to find the greatest priority.
+    pub fn change_priority_of_max_instance<Q>(&mut self, item: &Q, new_priority: P) -> Option<P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let id = self.max_instance(item)?;
+        self.pm.change_priority(&id, new_priority)
+    }
+
+    /// Drops all instances from the queue
+    pub fn clear(&mut self) {
+        self.pm.clear();
+        self.instances.clear();
+    }
+
+    /**************************************************************************/
+    /*                            internal functions                          */
+
+    /// Returns the identifier of the instance of `item` with the lowest priority
+    fn min_instance<Q>(&self, item: &Q) -> Option<u64>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.instances
+            .get(item)?
+            .iter()
+            .min_by_key(|id| self.pm.get_priority(*id))
+            .copied()
+    }
+
+    /// Returns the identifier of the instance of `item` with the greatest priority
+    fn max_instance<Q>(&self, item: &Q) -> Option<u64>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.instances
+            .get(item)?
+            .iter()
+            .max_by_key(|id| self.pm.get_priority(*id))
+            .copied()
+    }
+
+    /// Removes the identifier of an instance that has already been
+    /// removed from the heap from the index of the instances
+    ///
+    /// Computes in **O(1)** time (average)
+    fn forget(&mut self, item: &I, id: u64) {
+        if let Some((index, _, ids)) = self.instances.get_full_mut(item) {
+            ids.swap_remove(&id);
+            if ids.is_empty() {
+                self.instances.swap_remove_index(index);
+            }
+        }
+    }
+}
+
+impl<I, P, H> FromIterator<(I, P)> for PriorityMultiQueue<I, P, H>
+where
+    I: Hash + Eq + Clone,
+    P: Ord,
+    H: BuildHasher + Default + Clone,
+{
+    fn from_iter<IT>(iter: IT) -> Self
+    where
+        IT: IntoIterator<Item = (I, P)>,
+    {
+        let mut pq = Self::with_default_hasher();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<I, P, H> Extend<(I, P)> for PriorityMultiQueue<I, P, H>
+where
+    I: Hash + Eq + Clone,
+    P: Ord,
+    H: BuildHasher + Clone,
+{
+    fn extend<T: IntoIterator<Item = (I, P)>>(&mut self, iter: T) {
+        let next_id = &mut self.next_id;
+        let instances = &mut self.instances;
+        self.pm.extend(iter.into_iter().map(|(item, priority)| {
+            let id = *next_id;
+            *next_id += 1;
+            let hash_builder = instances.hasher().clone();
+            instances
+                .entry(item.clone())
+                .or_insert_with(|| IndexSet::with_hasher(hash_builder))
+                .insert(id);
+            (id, item, priority)
+        }));
+    }
+}
+
+impl<I, P, H> IntoIterator for PriorityMultiQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    type Item = (I, P);
+    type IntoIter = IntoIter<I, P>;
+    fn into_iter(self) -> IntoIter<I, P> {
+        IntoIter {
+            iter: self.pm.into_iter(),
+        }
+    }
+}
+
+impl<'a, I, P, H> IntoIterator for &'a PriorityMultiQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    type Item = (&'a I, &'a P);
+    type IntoIter = Iter<'a, I, P>;
+    fn into_iter(self) -> Iter<'a, I, P> {
+        self.iter()
+    }
+}
+
+use std::fmt;
+
+impl<I, P, H> fmt::Debug for PriorityMultiQueue<I, P, H>
+where
+    I: Hash + Eq + fmt::Debug,
+    P: Ord + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+use std::cmp::PartialEq;
+
+impl<I, P1, H1, P2, H2> PartialEq<PriorityMultiQueue<I, P2, H2>> for PriorityMultiQueue<I, P1, H1>
+where
+    I: Hash + Eq,
+    P1: Ord + PartialEq<P2>,
+    P2: Ord,
+    H1: BuildHasher,
+    H2: BuildHasher,
+{
+    /// Two queues are equal if they hold the same items, each one
+    /// with the same multiset of priorities.
+    fn eq(&self, other: &PriorityMultiQueue<I, P2, H2>) -> bool {
+        if self.len() != other.len() || self.distinct_len() != other.distinct_len() {
+            return false;
+        }
+        self.instances.keys().all(|item| {
+            let mut mine: Vec<&P1> = self.priorities(item).collect();
+            let mut theirs: Vec<&P2> = other.priorities(item).collect();
+            mine.sort();
+            theirs.sort();
+            mine.len() == theirs.len() && mine.iter().zip(theirs).all(|(a, b)| **a == *b)
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use std::cmp::{Eq, Ord};
+    use std::hash::{BuildHasher, Hash};
+
+    #[cfg(not(has_std))]
+    use std::vec::Vec;
+
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+    use super::PriorityMultiQueue;
+
+    impl<I, P, H> Serialize for PriorityMultiQueue<I, P, H>
+    where
+        I: Hash + Eq + Serialize,
+        P: Ord + Serialize,
+        H: BuildHasher,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut seq_serializer = serializer.serialize_seq(Some(self.len()))?;
+            for instance in self {
+                seq_serializer.serialize_element(&instance)?;
+            }
+            seq_serializer.end()
+        }
+    }
+
+    impl<'de, I, P, H> Deserialize<'de> for PriorityMultiQueue<I, P, H>
+    where
+        I: Hash + Eq + Clone + Deserialize<'de>,
+        P: Ord + Deserialize<'de>,
+        H: BuildHasher + Default + Clone,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<PriorityMultiQueue<I, P, H>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            Vec::<(I, P)>::deserialize(deserializer).map(|v| v.into_iter().collect())
+        }
+    }
+}
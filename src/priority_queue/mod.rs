@@ -28,6 +28,7 @@ pub mod iterators;
 use std::vec::Vec;
 
 use crate::core_iterators::{IntoIter, Iter};
+use crate::item_mut::{ItemMut, Rehash};
 use crate::store::Store;
 use iterators::*;
 
@@ -35,10 +36,13 @@ use std::borrow::Borrow;
 use std::cmp::{Eq, Ord};
 #[cfg(has_std)]
 use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, BinaryHeap};
 use std::hash::{BuildHasher, Hash};
 use std::iter::{Extend, FromIterator, IntoIterator, Iterator};
 use std::mem::replace;
 
+use indexmap::map::{IndexMap, MutableKeys};
+
 /// A priority queue with efficient change function to change the priority of an
 /// element.
 ///
@@ -215,14 +219,44 @@ where
     /// `change_priority_by`.
     ///
     /// Computes in **O(1)** time
-    pub fn peek_mut(&mut self) -> Option<(&mut I, &P)> {
+    pub fn peek_mut(&mut self) -> Option<(&mut I, &P)>
+    where
+        H: BuildHasher,
+    {
         if self.store.size == 0 {
             return None;
         }
-        self.store
-            .map
-            .get_index_mut(unsafe { *self.store.heap.get_unchecked(0) })
-            .map(|(k, v)| (k, &*v))
+        let index = unsafe { *self.store.heap.get_unchecked(0) };
+        self.store.mark_unchecked(index);
+        self.store.map.get_index_mut2(index).map(|(k, v)| (k, &*v))
+    }
+
+    /// Returns all the couples (item, priority) sharing the greatest
+    /// priority in the queue, in arbitrary order,
+    /// or an empty vector if the queue is empty.
+    ///
+    /// Only the top of the heap holding the tied elements is visited,
+    /// so it computes in **O(k)** time, where k is the number of ties.
+    pub fn peek_all_max(&self) -> Vec<(&I, &P)> {
+        let mut ties = Vec::new();
+        let top = match self.peek() {
+            Some((_, priority)) => priority,
+            None => return ties,
+        };
+        let mut stack = Vec::new();
+        stack.push(0);
+        while let Some(i) = stack.pop() {
+            let (item, priority) = self
+                .store
+                .map
+                .get_index(unsafe { *self.store.heap.get_unchecked(i) })
+                .unwrap();
+            if priority == top {
+                ties.push((item, priority));
+                stack.extend([left(i), right(i)].iter().filter(|&&c| c < self.store.size));
+            }
+        }
+        ties
     }
 
     /// Returns the number of elements the internal map can hold without
@@ -255,6 +289,20 @@ where
         }
     }
 
+    /// Removes all the items sharing the greatest priority from
+    /// the priority queue and returns the pairs (item, priority),
+    /// or an empty vector if the queue is empty.
+    ///
+    /// Computes in **O(k log(N))** time, where k is the number of ties.
+    pub fn pop_all_max(&mut self) -> Vec<(I, P)> {
+        let mut ties = Vec::new();
+        ties.extend(self.pop());
+        while matches!((self.peek(), ties.first()), (Some((_, p)), Some((_, top))) if p == top) {
+            ties.extend(self.pop());
+        }
+        ties
+    }
+
     /// Implements a HeapSort.
     ///
     /// Returns a `Vec<I>` sorted from the item associated to the highest priority to the lowest.
@@ -314,6 +362,7 @@ where
     /// Computes in **O(log(N))** time.
     pub fn push(&mut self, item: I, priority: P) -> Option<P> {
         use indexmap::map::Entry::*;
+        self.store.check_hashes();
         let mut pos = 0;
         let mut oldp = None;
 
@@ -444,6 +493,9 @@ where
     ///
     /// The item is a mutable reference, but it's a logic error to modify it
     /// in a way that change the result of  `Hash` or `Eq`.
+    /// Use [`get_mut_rehash`](Self::get_mut_rehash) to change the hash of an item,
+    /// or enable the `debug-hash-check` feature to have the next lookup
+    /// assert that the item can still be found.
     ///
     /// The priority cannot be modified with a call to this function.
     /// To modify the priority use `push`, `change_priority` or
@@ -456,6 +508,30 @@ where
         self.store.get_mut(item)
     }
 
+    /// Get a guard giving mutable access to an item, or `None`
+    /// if the item was not in the queue.
+    ///
+    /// Unlike [`get_mut`](Self::get_mut), the item can be modified in a way
+    /// that changes its hash: when the [`ItemMut`] guard is committed or dropped,
+    /// the item is moved to its new place in the hash table, or removed from the
+    /// queue if it was made equal to another item.
+    ///
+    /// **Warning:** dropping the guard silently drops an item that was made
+    /// equal to another one, together with its priority. Whenever that may
+    /// happen, end the modification with [`ItemMut::commit`], that gives the
+    /// removed item back.
+    ///
+    /// Computes in **O(1)** time (average)
+    pub fn get_mut_rehash<Q>(&mut self, item: &Q) -> Option<ItemMut<'_, I, P, H>>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.store.check_hashes();
+        let index = self.store.map.get_index_of(item)?;
+        Some(ItemMut::new(self, index))
+    }
+
     /// Remove an arbitrary element from the priority queue.
     /// Returns the (item, priority) couple or None if the item
     /// is not found in the queue.
@@ -475,11 +551,149 @@ where
         })
     }
 
+    /// Replaces the item `old` with `new`, keeping its priority and its
+    /// position in the heap, and returns the old item.
+    ///
+    /// Returns `Err(new)`, leaving the queue unchanged, if `old` is not in
+    /// the queue or `new` is equal to another item in the queue.
+    /// The result is not an `Option` so that, in that case, `new` is handed
+    /// back to the caller instead of being dropped.
+    ///
+    /// Computes in **O(1)** time (average)
+    pub fn replace_item<Q: ?Sized>(&mut self, old: &Q, new: I) -> Result<I, I>
+    where
+        I: Borrow<Q>,
+        Q: Eq + Hash,
+    {
+        self.store.replace_item(old, new)
+    }
+
+    /// Exchanges the priorities of the items `a` and `b`, returning true
+    /// if both were in the queue, or false, leaving the queue unchanged, otherwise.
+    ///
+    /// Only the two items are moved to restore the heap property.
+    ///
+    /// Computes in **O(log(N))** time
+    pub fn swap_priorities<Q1: ?Sized, Q2: ?Sized>(&mut self, a: &Q1, b: &Q2) -> bool
+    where
+        I: Borrow<Q1> + Borrow<Q2>,
+        Q1: Eq + Hash,
+        Q2: Eq + Hash,
+    {
+        match self.store.swap_priorities(a, b) {
+            Some((pa, pb)) => {
+                // the first fix may move the second item in the heap
+                let ib = unsafe { *self.store.heap.get_unchecked(pb) };
+                self.up_heapify(pa);
+                self.up_heapify(unsafe { *self.store.qp.get_unchecked(ib) });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves an item, with its priority, from this queue to `other`,
+    /// returning true if the item was in this queue.
+    ///
+    /// If an equal item is already in `other`, its priority is updated.
+    ///
+    /// Computes in **O(log(N) + log(M))** time, hashing the item once per queue
+    pub fn transfer<Q: ?Sized, H2>(&mut self, item: &Q, other: &mut PriorityQueue<I, P, H2>) -> bool
+    where
+        I: Borrow<Q>,
+        Q: Eq + Hash,
+        H2: BuildHasher,
+    {
+        match self.remove(item) {
+            Some((item, priority)) => {
+                other.push(item, priority);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Returns the items not ordered
     pub fn into_vec(self) -> Vec<I> {
         self.store.into_vec()
     }
 
+    /// Returns a reference to the `BuildHasher` used by the queue
+    pub fn hasher(&self) -> &H {
+        self.store.map.hasher()
+    }
+
+    /// Get the couple (item, priority) of the item with the given `hash`
+    /// for which `is_match` returns true, or `None` if there is no such item.
+    ///
+    /// The hash must be computed with the [`hasher`](Self::hasher) of the
+    /// queue, which allows to look up the same item in several queues sharing
+    /// a `BuildHasher` while hashing it only once.
+    ///
+    /// Computes in **O(1)** time (average)
+    pub fn get_by_hash<F>(&self, hash: u64, is_match: F) -> Option<(&I, &P)>
+    where
+        F: FnMut(&I) -> bool,
+    {
+        let index = self.store.get_index_by_hash(hash, is_match)?;
+        self.store.map.get_index(index)
+    }
+
+    /// Change the priority of the item with the given `hash` for which
+    /// `is_match` returns true, returning the old value of priority,
+    /// or `None` if there is no such item.
+    ///
+    /// The hash must be computed with the [`hasher`](Self::hasher) of the queue.
+    ///
+    /// The operation is performed in **O(log(N))** time.
+    pub fn change_priority_by_hash<F>(
+        &mut self,
+        hash: u64,
+        is_match: F,
+        new_priority: P,
+    ) -> Option<P>
+    where
+        F: FnMut(&I) -> bool,
+    {
+        let index = self.store.get_index_by_hash(hash, is_match)?;
+        let (r, pos) = self.store.change_priority_at(index, new_priority);
+        self.up_heapify(pos);
+        Some(r)
+    }
+
+    /// Remove the item with the given `hash` for which `is_match` returns
+    /// true, returning the (item, priority) couple, or `None` if there is
+    /// no such item.
+    ///
+    /// The hash must be computed with the [`hasher`](Self::hasher) of the queue.
+    ///
+    /// The operation is performed in **O(log(N))** time (worst case).
+    pub fn remove_by_hash<F>(&mut self, hash: u64, is_match: F) -> Option<(I, P)>
+    where
+        F: FnMut(&I) -> bool,
+    {
+        let index = self.store.get_index_by_hash(hash, is_match)?;
+        self.store.remove_index(index).map(|(item, priority, pos)| {
+            if pos < self.store.size {
+                self.up_heapify(pos);
+            }
+
+            (item, priority)
+        })
+    }
+
+    /// Returns a reference to the map backing the queue,
+    /// with the items in arbitrary order
+    pub fn as_index_map(&self) -> &IndexMap<I, P, H> {
+        &self.store.map
+    }
+
+    /// Consumes the queue and returns the map backing it,
+    /// with the items in arbitrary order, without rehashing them
+    pub fn into_index_map(self) -> IndexMap<I, P, H> {
+        self.store.map
+    }
+
     /// Drops all items from the priority queue
     pub fn clear(&mut self) {
         self.store.clear();
@@ -626,6 +840,73 @@ where
     }
 }
 
+impl<I, P, H> From<IndexMap<I, P, H>> for PriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    /// Builds the heap in **O(N)** time, reusing the map as it is
+    fn from(map: IndexMap<I, P, H>) -> Self {
+        let mut pq = PriorityQueue {
+            store: Store::from(map),
+        };
+        pq.heap_build();
+        pq
+    }
+}
+
+impl<I, P, H> From<BinaryHeap<(P, I)>> for PriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    /// If an item appears more than once, one of its priorities is kept arbitrarily
+    fn from(heap: BinaryHeap<(P, I)>) -> Self {
+        heap.into_iter().map(|(p, i)| (i, p)).collect()
+    }
+}
+
+impl<I, P, H> From<PriorityQueue<I, P, H>> for BinaryHeap<(P, I)>
+where
+    I: Hash + Eq + Ord,
+    P: Ord,
+{
+    fn from(pq: PriorityQueue<I, P, H>) -> Self {
+        pq.store.map.into_iter().map(|(i, p)| (p, i)).collect()
+    }
+}
+
+impl<I, P, H> From<BTreeMap<P, Vec<I>>> for PriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord + Clone,
+    H: BuildHasher + Default,
+{
+    /// If an item appears more than once, the greatest priority is kept
+    fn from(map: BTreeMap<P, Vec<I>>) -> Self {
+        map.into_iter()
+            .flat_map(|(p, items)| items.into_iter().map(move |i| (i, p.clone())))
+            .collect()
+    }
+}
+
+impl<I, P, H> From<PriorityQueue<I, P, H>> for BTreeMap<P, Vec<I>>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    /// The items sharing a priority are grouped in arbitrary order
+    fn from(pq: PriorityQueue<I, P, H>) -> Self {
+        let mut map = BTreeMap::<P, Vec<I>>::new();
+        for (i, p) in pq.store.map {
+            map.entry(p).or_default().push(i);
+        }
+        map
+    }
+}
+
 use crate::DoublePriorityQueue;
 
 impl<I, P, H> From<DoublePriorityQueue<I, P, H>> for PriorityQueue<I, P, H>
@@ -662,6 +943,31 @@ where
     }
 }
 
+impl<I, P, H> Rehash<I, P, H> for PriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    fn store(&self) -> &Store<I, P, H> {
+        &self.store
+    }
+
+    fn store_mut(&mut self) -> &mut Store<I, P, H> {
+        &mut self.store
+    }
+
+    fn reindex(&mut self, index: usize) -> Result<(), I> {
+        self.store.reindex(index).map_err(|(item, _, pos)| {
+            if pos < self.store.size {
+                self.up_heapify(pos);
+            }
+
+            item
+        })
+    }
+}
+
 impl<I, P, H> IntoIterator for PriorityQueue<I, P, H>
 where
     I: Hash + Eq,
@@ -692,6 +998,7 @@ impl<'a, I, P, H> IntoIterator for &'a mut PriorityQueue<I, P, H>
 where
     I: Hash + Eq,
     P: Ord,
+    H: BuildHasher,
 {
     type Item = (&'a mut I, &'a mut P);
     type IntoIter = IterMut<'a, I, P, H>;
@@ -729,6 +1036,66 @@ where
     }
 }
 
+use crate::traits::PriorityQueueLike;
+
+impl<I, P, H> PriorityQueueLike<I, P> for PriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    type Iter<'a>
+        = Iter<'a, I, P>
+    where
+        Self: 'a,
+        I: 'a,
+        P: 'a;
+
+    fn push(&mut self, item: I, priority: P) -> Option<P> {
+        PriorityQueue::push(self, item, priority)
+    }
+
+    fn pop(&mut self) -> Option<(I, P)> {
+        PriorityQueue::pop(self)
+    }
+
+    fn peek(&self) -> Option<(&I, &P)> {
+        PriorityQueue::peek(self)
+    }
+
+    fn change_priority<Q>(&mut self, item: &Q, new_priority: P) -> Option<P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        PriorityQueue::change_priority(self, item, new_priority)
+    }
+
+    fn remove<Q>(&mut self, item: &Q) -> Option<(I, P)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        PriorityQueue::remove(self, item)
+    }
+
+    fn get_priority<Q>(&self, item: &Q) -> Option<&P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        PriorityQueue::get_priority(self, item)
+    }
+
+    fn len(&self) -> usize {
+        PriorityQueue::len(self)
+    }
+
+    fn iter(&self) -> Iter<'_, I, P> {
+        PriorityQueue::iter(self)
+    }
+}
+
 use std::cmp::PartialEq;
 
 impl<I, P1, H1, P2, H2> PartialEq<PriorityQueue<I, P2, H2>> for PriorityQueue<I, P1, H1>
@@ -769,7 +1136,7 @@ fn log2_fast(x: usize) -> usize {
 // while `extend` takes O(len2 * log_2(len1)) operations
 // and about 1 * len2 * log_2(len1) comparisons in the worst case,
 // assuming len1 >= len2.
-fn better_to_rebuild(len1: usize, len2: usize) -> bool {
+pub(crate) fn better_to_rebuild(len1: usize, len2: usize) -> bool {
     // log(1) == 0, so the inequation always falsy
     // log(0) is inapplicable and produces panic
     if len1 <= 1 {
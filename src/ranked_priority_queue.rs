@@ -0,0 +1,648 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+//! This module contains the [`RankedPriorityQueue`] type.
+//!
+//! See the type level documentation for more details and examples.
+
+#[cfg(not(has_std))]
+use crate::std;
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+use crate::core_iterators::Iter;
+use crate::PriorityQueue;
+
+use std::borrow::Borrow;
+use std::cmp::{Eq, Ord};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::iter::{Extend, FromIterator, IntoIterator};
+use std::ops::{Bound, RangeBounds};
+
+use indexmap::map::IndexMap;
+
+const NIL: usize = usize::MAX;
+
+/// A node of the treap, that refers to an element of the map
+#[derive(Clone, Debug)]
+struct Node {
+    map_index: usize,
+    left: usize,
+    right: usize,
+    size: usize,
+    weight: u64,
+}
+
+/// An order statistic tree implemented as a treap whose nodes refer
+/// to the elements of the map of a `RankedPriorityQueue`.
+///
+/// The nodes are ordered by the priority of their element and then
+/// by their own index, that never changes while the node is in use,
+/// so that all the keys are distinct.
+#[derive(Clone, Debug)]
+struct Treap {
+    nodes: Vec<Node>,
+    free: Vec<usize>,
+    root: usize,
+    seed: u64,
+}
+
+impl Treap {
+    fn new() -> Self {
+        Treap {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: NIL,
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    fn size(&self, n: usize) -> usize {
+        if n == NIL {
+            0
+        } else {
+            self.nodes[n].size
+        }
+    }
+
+    fn update(&mut self, n: usize) {
+        self.nodes[n].size = 1 + self.size(self.nodes[n].left) + self.size(self.nodes[n].right);
+    }
+
+    /// Returns a new detached node referring to the element in position `map_index`
+    fn alloc(&mut self, map_index: usize) -> usize {
+        // xorshift64
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        let node = Node {
+            map_index,
+            left: NIL,
+            right: NIL,
+            size: 1,
+            weight: self.seed,
+        };
+        match self.free.pop() {
+            Some(n) => {
+                self.nodes[n] = node;
+                n
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn priority<'a, I, P, H>(&self, map: &'a IndexMap<I, P, H>, n: usize) -> &'a P {
+        map.get_index(self.nodes[n].map_index).unwrap().1
+    }
+
+    fn less<I, P: Ord, H>(&self, map: &IndexMap<I, P, H>, a: usize, b: usize) -> bool {
+        (self.priority(map, a), a) < (self.priority(map, b), b)
+    }
+
+    /// Splits the tree rooted in `n` in the nodes less than `k` and the others
+    fn split<I, P: Ord, H>(
+        &mut self,
+        map: &IndexMap<I, P, H>,
+        n: usize,
+        k: usize,
+    ) -> (usize, usize) {
+        if n == NIL {
+            return (NIL, NIL);
+        }
+        if self.less(map, n, k) {
+            let (l, r) = self.split(map, self.nodes[n].right, k);
+            self.nodes[n].right = l;
+            self.update(n);
+            (n, r)
+        } else {
+            let (l, r) = self.split(map, self.nodes[n].left, k);
+            self.nodes[n].left = r;
+            self.update(n);
+            (l, n)
+        }
+    }
+
+    /// Merges two trees, given that all the nodes of `a` are less than the ones of `b`
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL {
+            return b;
+        }
+        if b == NIL {
+            return a;
+        }
+        if self.nodes[a].weight > self.nodes[b].weight {
+            self.nodes[a].right = self.merge(self.nodes[a].right, b);
+            self.update(a);
+            a
+        } else {
+            self.nodes[b].left = self.merge(a, self.nodes[b].left);
+            self.update(b);
+            b
+        }
+    }
+
+    /// Removes the minimum node from the tree rooted in `n`
+    fn pop_min(&mut self, n: usize) -> usize {
+        if self.nodes[n].left == NIL {
+            return self.nodes[n].right;
+        }
+        self.nodes[n].left = self.pop_min(self.nodes[n].left);
+        self.update(n);
+        n
+    }
+
+    /// Inserts a detached node
+    fn insert<I, P: Ord, H>(&mut self, map: &IndexMap<I, P, H>, n: usize) {
+        let (l, r) = self.split(map, self.root, n);
+        let l = self.merge(l, n);
+        self.root = self.merge(l, r);
+    }
+
+    /// Detaches a node from the tree, without releasing it
+    fn detach<I, P: Ord, H>(&mut self, map: &IndexMap<I, P, H>, n: usize) {
+        let (l, r) = self.split(map, self.root, n);
+        // `n` is the minimum of `r`
+        let r = self.pop_min(r);
+        self.root = self.merge(l, r);
+        let node = &mut self.nodes[n];
+        node.left = NIL;
+        node.right = NIL;
+        node.size = 1;
+    }
+
+    /// Counts the nodes whose priority satisfies `below`, given that
+    /// they come before all the others in the order of the tree
+    fn count_below<I, P: Ord, H, F>(&self, map: &IndexMap<I, P, H>, below: F) -> usize
+    where
+        F: Fn(&P) -> bool,
+    {
+        let mut count = 0;
+        let mut n = self.root;
+        while n != NIL {
+            if below(self.priority(map, n)) {
+                count += self.size(self.nodes[n].left) + 1;
+                n = self.nodes[n].right;
+            } else {
+                n = self.nodes[n].left;
+            }
+        }
+        count
+    }
+
+    /// Returns the map index of the k-th smallest node
+    fn select(&self, mut k: usize) -> Option<usize> {
+        let mut n = self.root;
+        while n != NIL {
+            let left = self.size(self.nodes[n].left);
+            if k < left {
+                n = self.nodes[n].left;
+            } else if k == left {
+                return Some(self.nodes[n].map_index);
+            } else {
+                k -= left + 1;
+                n = self.nodes[n].right;
+            }
+        }
+        None
+    }
+
+    fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.root = NIL;
+    }
+}
+
+/// A priority queue that can answer order statistic queries,
+/// like the rank of an item or the k-th element in order of priority.
+///
+/// It behaves like a [`PriorityQueue`], and keeps in sync with its
+/// internal storage an order statistic tree over the priorities,
+/// so that the queries are computed in **O(log(N))** time,
+/// instead of sorting all the elements.
+///
+/// The priority is of type P, that must implement `std::cmp::Ord`.
+///
+/// The item is of type I, that must implement `Hash` and `Eq`.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::RankedPriorityQueue;
+///
+/// let mut pq = RankedPriorityQueue::new();
+///
+/// pq.push("Alice", 30);
+/// pq.push("Bob", 50);
+/// pq.push("Carol", 40);
+///
+/// // Bob is served first, then Carol, then Alice
+/// assert_eq!(pq.rank(&"Alice"), Some(2));
+/// assert_eq!(pq.nth(1), Some((&"Carol", &40)));
+/// assert_eq!(pq.count_above(&35), 2);
+/// assert_eq!(pq.count_in_range(30..50), 2);
+///
+/// pq.change_priority(&"Alice", 60);
+/// assert_eq!(pq.rank(&"Alice"), Some(0));
+/// ```
+#[derive(Clone)]
+#[cfg(has_std)]
+pub struct RankedPriorityQueue<I, P, H = RandomState>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    pq: PriorityQueue<I, P, H>,
+    treap: Treap,
+    node_of: Vec<usize>, // Performs the translation from the index
+                         // of the map to the node of the treap
+}
+
+#[derive(Clone)]
+#[cfg(not(has_std))]
+pub struct RankedPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    pq: PriorityQueue<I, P, H>,
+    treap: Treap,
+    node_of: Vec<usize>, // Performs the translation from the index
+                         // of the map to the node of the treap
+}
+
+impl<I, P, H> Default for RankedPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+#[cfg(has_std)]
+impl<I, P> RankedPriorityQueue<I, P>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    /// Creates an empty `RankedPriorityQueue`
+    pub fn new() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+impl<I, P, H> RankedPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty `RankedPriorityQueue` with the default hasher
+    pub fn with_default_hasher() -> Self {
+        Self::with_hasher(H::default())
+    }
+}
+
+impl<I, P, H> RankedPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    /// Creates an empty `RankedPriorityQueue` with the specified hasher
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self::from_queue(PriorityQueue::with_hasher(hash_builder))
+    }
+
+    /// Returns an iterator in arbitrary order over the
+    /// (item, priority) elements in the queue
+    pub fn iter(&self) -> Iter<'_, I, P> {
+        self.pq.iter()
+    }
+
+    /// Insert the item-priority pair into the queue.
+    ///
+    /// If an element equal to `item` was already into the queue,
+    /// it is updated and the old value of its priority returned in `Some`;
+    /// otherwise, returns `None`.
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn push(&mut self, item: I, priority: P) -> Option<P> {
+        if let Some(index) = self.pq.store.map.get_index_of(&item) {
+            let node = self.node_of[index];
+            self.treap.detach(&self.pq.store.map, node);
+            let old = self.pq.push(item, priority);
+            self.treap.insert(&self.pq.store.map, node);
+            return old;
+        }
+        self.pq.push(item, priority);
+        let node = self.treap.alloc(self.node_of.len());
+        self.node_of.push(node);
+        self.treap.insert(&self.pq.store.map, node);
+        None
+    }
+
+    /// Removes the item with the greatest priority from
+    /// the queue and returns the pair (item, priority),
+    /// or None if the queue is empty.
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn pop(&mut self) -> Option<(I, P)> {
+        let index = *self.pq.store.heap.first()?;
+        self.forget(index);
+        let r = self.pq.pop();
+        self.renumber(index);
+        r
+    }
+
+    /// Change the priority of an Item returning the old value of priority,
+    /// or `None` if the item wasn't in the queue.
+    ///
+    /// The operation is performed in **O(log(N))** time.
+    pub fn change_priority<Q>(&mut self, item: &Q, new_priority: P) -> Option<P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let index = self.pq.store.map.get_index_of(item)?;
+        let node = self.node_of[index];
+        self.treap.detach(&self.pq.store.map, node);
+        let old = self.pq.change_priority(item, new_priority);
+        self.treap.insert(&self.pq.store.map, node);
+        old
+    }
+
+    /// Change the priority of an Item using the provided function.
+    ///
+    /// The operation is performed in **O(log(N))** time.
+    pub fn change_priority_by<Q, F>(&mut self, item: &Q, priority_setter: F)
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&mut P),
+    {
+        if let Some(index) = self.pq.store.map.get_index_of(item) {
+            let node = self.node_of[index];
+            self.treap.detach(&self.pq.store.map, node);
+            self.pq.change_priority_by(item, priority_setter);
+            self.treap.insert(&self.pq.store.map, node);
+        }
+    }
+
+    /// Get the priority of an item, or `None`, if the item is not in the queue
+    pub fn get_priority<Q>(&self, item: &Q) -> Option<&P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.pq.get_priority(item)
+    }
+
+    /// Get the couple (item, priority) of an arbitrary element, as reference
+    /// or `None` if the item is not in the queue.
+    pub fn get<Q>(&self, item: &Q) -> Option<(&I, &P)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.pq.get(item)
+    }
+
+    /// Remove an arbitrary element from the queue.
+    /// Returns the (item, priority) couple or None if the item
+    /// is not found in the queue.
+    ///
+    /// The operation is performed in **O(log(N))** time.
+    pub fn remove<Q>(&mut self, item: &Q) -> Option<(I, P)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let index = self.pq.store.map.get_index_of(item)?;
+        self.forget(index);
+        let r = self.pq.remove(item);
+        self.renumber(index);
+        r
+    }
+
+    /// Returns the number of elements with a priority strictly greater
+    /// than the one of `item`, or `None` if the item is not in the queue.
+    ///
+    /// That is the position of the item in the queue, starting from 0,
+    /// with the items with the same priority sharing the same rank.
+    ///
+    /// Computes in **O(log(N))** time
+    pub fn rank<Q>(&self, item: &Q) -> Option<usize>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.get_priority(item).map(|p| self.count_above(p))
+    }
+
+    /// Returns the number of elements with a priority strictly greater than `priority`.
+    ///
+    /// Computes in **O(log(N))** time
+    pub fn count_above(&self, priority: &P) -> usize {
+        self.len()
+            - self
+                .treap
+                .count_below(&self.pq.store.map, |p| p <= priority)
+    }
+
+    /// Returns the number of elements with a priority strictly less than `priority`.
+    ///
+    /// Computes in **O(log(N))** time
+    pub fn count_below(&self, priority: &P) -> usize {
+        self.treap.count_below(&self.pq.store.map, |p| p < priority)
+    }
+
+    /// Returns the number of elements whose priority is contained in `range`.
+    ///
+    /// Computes in **O(log(N))** time
+    pub fn count_in_range<R>(&self, range: R) -> usize
+    where
+        R: RangeBounds<P>,
+    {
+        let map = &self.pq.store.map;
+        let end = match range.end_bound() {
+            Bound::Included(e) => self.treap.count_below(map, |p| p <= e),
+            Bound::Excluded(e) => self.treap.count_below(map, |p| p < e),
+            Bound::Unbounded => self.len(),
+        };
+        let start = match range.start_bound() {
+            Bound::Included(s) => self.treap.count_below(map, |p| p < s),
+            Bound::Excluded(s) => self.treap.count_below(map, |p| p <= s),
+            Bound::Unbounded => 0,
+        };
+        end.saturating_sub(start)
+    }
+
+    /// Returns the couple (item, priority) in position `n`, starting from 0,
+    /// in the order of extraction from the queue, or `None` if `n` is
+    /// out of bounds.
+    ///
+    /// The elements with the same priority are returned in an arbitrary,
+    /// but consistent, order.
+    ///
+    /// Computes in **O(log(N))** time
+    pub fn nth(&self, n: usize) -> Option<(&I, &P)> {
+        if n >= self.len() {
+            return None;
+        }
+        self.treap
+            .select(self.len() - 1 - n)
+            .and_then(|index| self.pq.store.map.get_index(index))
+    }
+
+    /// Drops all items from the queue
+    pub fn clear(&mut self) {
+        self.pq.clear();
+        self.treap.clear();
+        self.node_of.clear();
+    }
+
+    /**************************************************************************/
+    /*                            internal functions                          */
+
+    /// Builds the order statistic tree over an existing queue
+    fn from_queue(pq: PriorityQueue<I, P, H>) -> Self {
+        let mut this = Self {
+            pq,
+            treap: Treap::new(),
+            node_of: Vec::new(),
+        };
+        for index in 0..this.pq.len() {
+            let node = this.treap.alloc(index);
+            this.node_of.push(node);
+            this.treap.insert(&this.pq.store.map, node);
+        }
+        this
+    }
+
+    /// Removes from the treap the node of the element in position `index`
+    /// of the map, before the element is removed from the queue
+    fn forget(&mut self, index: usize) {
+        let node = self.node_of[index];
+        self.treap.detach(&self.pq.store.map, node);
+        self.treap.free.push(node);
+    }
+
+    /// Updates the translation table after the element in position `index`
+    /// of the map has been swap-removed
+    fn renumber(&mut self, index: usize) {
+        self.node_of.swap_remove(index);
+        if let Some(&node) = self.node_of.get(index) {
+            self.treap.nodes[node].map_index = index;
+        }
+    }
+}
+
+impl<I, P, H> RankedPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    /// Returns the couple (item, priority) with the greatest
+    /// priority in the queue, or None if it is empty.
+    ///
+    /// Computes in **O(1)** time
+    pub fn peek(&self) -> Option<(&I, &P)> {
+        self.pq.peek()
+    }
+
+    /// Returns the number of elements in the queue.
+    pub fn len(&self) -> usize {
+        self.pq.len()
+    }
+
+    /// Returns true if the queue contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.pq.is_empty()
+    }
+}
+
+impl<I, P, H> From<PriorityQueue<I, P, H>> for RankedPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    fn from(pq: PriorityQueue<I, P, H>) -> Self {
+        Self::from_queue(pq)
+    }
+}
+
+impl<I, P, H> From<RankedPriorityQueue<I, P, H>> for PriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    fn from(pq: RankedPriorityQueue<I, P, H>) -> Self {
+        pq.pq
+    }
+}
+
+impl<I, P, H> FromIterator<(I, P)> for RankedPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    fn from_iter<IT>(iter: IT) -> Self
+    where
+        IT: IntoIterator<Item = (I, P)>,
+    {
+        Self::from_queue(PriorityQueue::from_iter(iter))
+    }
+}
+
+impl<I, P, H> Extend<(I, P)> for RankedPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (I, P)>>(&mut self, iter: T) {
+        for (item, priority) in iter {
+            self.push(item, priority);
+        }
+    }
+}
+
+use std::fmt;
+
+impl<I, P, H> fmt::Debug for RankedPriorityQueue<I, P, H>
+where
+    I: Hash + Eq + fmt::Debug,
+    P: Ord + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.pq.store.map.iter()).finish()
+    }
+}
@@ -0,0 +1,455 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+//! This module contains the [`SampledPriorityQueue`] type.
+//!
+//! See the type level documentation for more details and examples.
+
+#[cfg(not(has_std))]
+use crate::std;
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+use crate::PriorityQueue;
+
+use std::borrow::Borrow;
+use std::cmp::{Eq, Ord};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::{Extend, FromIterator, IntoIterator, Iterator};
+
+/// A priority queue that can also sample its elements with probability
+/// proportional to their priority, as needed for example by prioritized
+/// experience replay.
+///
+/// Next to the [`PriorityQueue`], a sum tree is kept over the positions
+/// of the items in the underlying map: each leaf holds the weight of an item,
+/// that is its priority converted to `f64`, and each inner node the
+/// sum of its children.
+/// The tree is updated along with the queue, so sampling, like any
+/// other operation, is performed in **O(log(N))** time.
+///
+/// The randomness is supplied by the caller as a value in `[0, 1)`,
+/// so no random number generator is imposed.
+///
+/// The priority is of type P, that must implement `std::cmp::Ord` and
+/// be convertible into a non negative `f64` with `Into<f64>`.
+///
+/// The item is of type I, that must implement `Hash` and `Eq`.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::SampledPriorityQueue;
+///
+/// let mut pq = SampledPriorityQueue::new();
+/// pq.push("a", 1u32);
+/// pq.push("b", 3u32);
+///
+/// assert_eq!(pq.total(), 4.0);
+/// // "a" covers the first quarter of [0, 1), "b" the rest
+/// assert_eq!(pq.sample(0.2), Some((&"a", &1)));
+/// assert_eq!(pq.sample(0.3), Some((&"b", &3)));
+///
+/// pq.change_priority(&"a", 5);
+/// assert_eq!(pq.sample(0.3), Some((&"a", &5)));
+/// assert_eq!(pq.pop(), Some(("a", 5)));
+/// assert_eq!(pq.total(), 3.0);
+/// ```
+#[derive(Clone)]
+#[cfg(has_std)]
+pub struct SampledPriorityQueue<I, P, H = RandomState>
+where
+    I: Hash + Eq,
+    P: Ord + Clone + Into<f64>,
+{
+    queue: PriorityQueue<I, P, H>,
+    tree: SumTree,
+}
+
+#[derive(Clone)]
+#[cfg(not(has_std))]
+pub struct SampledPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord + Clone + Into<f64>,
+{
+    queue: PriorityQueue<I, P, H>,
+    tree: SumTree,
+}
+
+impl<I, P, H> Default for SampledPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord + Clone + Into<f64>,
+    H: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+#[cfg(has_std)]
+impl<I, P> SampledPriorityQueue<I, P>
+where
+    I: Hash + Eq,
+    P: Ord + Clone + Into<f64>,
+{
+    /// Creates an empty `SampledPriorityQueue`
+    pub fn new() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+impl<I, P, H> SampledPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord + Clone + Into<f64>,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty `SampledPriorityQueue` with the default hasher
+    pub fn with_default_hasher() -> Self {
+        Self::with_hasher(H::default())
+    }
+}
+
+impl<I, P, H> SampledPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord + Clone + Into<f64>,
+    H: BuildHasher,
+{
+    /// Creates an empty `SampledPriorityQueue` with the specified hasher
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self {
+            queue: PriorityQueue::with_hasher(hash_builder),
+            tree: SumTree::default(),
+        }
+    }
+
+    /// Returns the number of elements in the queue
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns true if the queue contains no elements
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Returns an iterator in arbitrary order over the
+    /// (item, priority) elements in the queue
+    pub fn iter(&self) -> impl Iterator<Item = (&I, &P)> {
+        self.queue.iter()
+    }
+
+    /// Returns the sum of the weights of all the elements
+    ///
+    /// Computes in **O(1)** time.
+    pub fn total(&self) -> f64 {
+        self.tree.total()
+    }
+
+    /// Returns the greatest priority in the queue, if any
+    ///
+    /// Computes in **O(1)** time.
+    pub fn max(&self) -> Option<&P> {
+        self.queue.peek().map(|(_, p)| p)
+    }
+
+    /// Returns the couple (item, priority) with the greatest
+    /// priority in the queue, or None if it is empty.
+    ///
+    /// Computes in **O(1)** time.
+    pub fn peek(&self) -> Option<(&I, &P)> {
+        self.queue.peek()
+    }
+
+    /// Returns the priority of `item`, if it is in the queue
+    pub fn get_priority<Q>(&self, item: &Q) -> Option<&P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.queue.get_priority(item)
+    }
+
+    /// Returns true if `item` is in the queue
+    pub fn contains<Q>(&self, item: &Q) -> bool
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.queue.get_priority(item).is_some()
+    }
+
+    /// Picks an element with probability proportional to its weight.
+    ///
+    /// `value` is the source of randomness: it must be drawn uniformly from
+    /// `[0, 1)`. The elements split the interval in consecutive ranges, as
+    /// wide as their share of the total weight, and the one whose range
+    /// contains `value` is returned.
+    ///
+    /// Returns `None` if the queue is empty or if all the weights are zero.
+    ///
+    /// Computes in **O(log(N))** time.
+    ///
+    /// # Panics
+    /// Panics if `value` is not in the range `[0, 1)`
+    pub fn sample(&self, value: f64) -> Option<(&I, &P)> {
+        assert!(
+            (0.0..1.0).contains(&value),
+            "the sampled value must be in the range [0, 1)"
+        );
+        if self.tree.total() <= 0.0 {
+            return None;
+        }
+        let index = self.tree.find(value * self.tree.total());
+        // rounding errors may point past the last element
+        self.queue
+            .store
+            .map
+            .get_index(index.min(self.queue.len() - 1))
+    }
+
+    /// Insert the item-priority pair into the queue.
+    ///
+    /// If an element equal to `item` was already into the queue,
+    /// it is updated and the old value of its priority returned in `Some`;
+    /// otherwise, returns `None`.
+    ///
+    /// Computes in **O(log(N))** time.
+    ///
+    /// # Panics
+    /// Panics if the weight of `priority` is negative or not finite
+    pub fn push(&mut self, item: I, priority: P) -> Option<P> {
+        let weight = weight(&priority);
+        // an item already in the queue keeps its position in the map
+        let index = self
+            .queue
+            .store
+            .map
+            .get_index_of(&item)
+            .unwrap_or(self.queue.len());
+        let old = self.queue.push(item, priority);
+        self.tree.set(index, weight);
+        old
+    }
+
+    /// Removes the item with the greatest priority from
+    /// the queue and returns the pair (item, priority),
+    /// or None if the queue is empty.
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn pop(&mut self) -> Option<(I, P)> {
+        let index = *self.queue.store.heap.first()?;
+        let popped = self.queue.pop();
+        self.removed(index);
+        popped
+    }
+
+    /// Changes the priority of `item` and returns the old one,
+    /// or `None` if the item is not in the queue.
+    ///
+    /// Computes in **O(log(N))** time.
+    ///
+    /// # Panics
+    /// Panics if the weight of `new_priority` is negative or not finite
+    pub fn change_priority<Q>(&mut self, item: &Q, new_priority: P) -> Option<P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let weight = weight(&new_priority);
+        let index = self.queue.store.map.get_index_of(item)?;
+        let old = self.queue.change_priority(item, new_priority);
+        self.tree.set(index, weight);
+        old
+    }
+
+    /// Removes an arbitrary element from the queue and
+    /// returns it with its priority, if present.
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn remove<Q>(&mut self, item: &Q) -> Option<(I, P)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let index = self.queue.store.map.get_index_of(item)?;
+        let removed = self.queue.remove(item);
+        self.removed(index);
+        removed
+    }
+
+    /// Drops all the elements
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.tree.clear();
+    }
+
+    /// Updates the tree after the element in position `index` of the map
+    /// has been swap removed
+    fn removed(&mut self, index: usize) {
+        let len = self.queue.len();
+        self.tree.set(len, 0.0);
+        if index < len {
+            self.refresh(index);
+        }
+    }
+
+    /// Copies the weight of the element in position `index` of the map
+    /// into the tree
+    fn refresh(&mut self, index: usize) {
+        let w = self
+            .queue
+            .store
+            .map
+            .get_index(index)
+            .map_or(0.0, |(_, p)| weight(p));
+        self.tree.set(index, w);
+    }
+}
+
+fn weight<P>(priority: &P) -> f64
+where
+    P: Clone + Into<f64>,
+{
+    let weight = priority.clone().into();
+    assert!(
+        weight >= 0.0 && weight.is_finite(),
+        "the weight of a priority must be finite and not negative"
+    );
+    weight
+}
+
+/// A complete binary tree stored in a vector, where the leaves hold
+/// the weights and each inner node the sum of its children
+#[derive(Clone, Debug, Default)]
+struct SumTree {
+    // node 0 is unused, the children of node k are 2k and 2k + 1,
+    // and the leaves start at `leaves`
+    nodes: Vec<f64>,
+    leaves: usize,
+}
+
+impl SumTree {
+    fn total(&self) -> f64 {
+        self.nodes.get(1).copied().unwrap_or(0.0)
+    }
+
+    fn clear(&mut self) {
+        self.nodes.iter_mut().for_each(|n| *n = 0.0);
+    }
+
+    /// Sets the weight of the leaf `index`, growing the tree if needed
+    fn set(&mut self, index: usize, weight: f64) {
+        if index >= self.leaves {
+            if weight == 0.0 {
+                return;
+            }
+            self.grow(index + 1);
+        }
+        let mut k = self.leaves + index;
+        self.nodes[k] = weight;
+        while k > 1 {
+            k /= 2;
+            // the sums are recomputed instead of adjusted by the difference,
+            // so that rounding errors do not accumulate
+            self.nodes[k] = self.nodes[2 * k] + self.nodes[2 * k + 1];
+        }
+    }
+
+    /// Doubles the leaves until they are at least `min_leaves`
+    fn grow(&mut self, min_leaves: usize) {
+        let old = self.leaves;
+        let mut leaves = old.max(1);
+        while leaves < min_leaves {
+            leaves *= 2;
+        }
+        let mut nodes = Vec::new();
+        nodes.resize(2 * leaves, 0.0);
+        if old > 0 {
+            nodes[leaves..leaves + old].copy_from_slice(&self.nodes[old..]);
+        }
+        for k in (1..leaves).rev() {
+            nodes[k] = nodes[2 * k] + nodes[2 * k + 1];
+        }
+        self.nodes = nodes;
+        self.leaves = leaves;
+    }
+
+    /// Returns the leaf where the prefix sum of the weights exceeds `target`
+    fn find(&self, mut target: f64) -> usize {
+        let mut k = 1;
+        while k < self.leaves {
+            let left = self.nodes[2 * k];
+            if target < left || self.nodes[2 * k + 1] <= 0.0 {
+                k *= 2;
+            } else {
+                target -= left;
+                k = 2 * k + 1;
+            }
+        }
+        k - self.leaves
+    }
+}
+
+impl<I, P, H> FromIterator<(I, P)> for SampledPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord + Clone + Into<f64>,
+    H: BuildHasher + Default,
+{
+    fn from_iter<IT>(iter: IT) -> Self
+    where
+        IT: IntoIterator<Item = (I, P)>,
+    {
+        let mut pq = Self::with_default_hasher();
+        pq.extend(iter);
+        pq
+    }
+}
+
+impl<I, P, H> Extend<(I, P)> for SampledPriorityQueue<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord + Clone + Into<f64>,
+    H: BuildHasher,
+{
+    fn extend<IT>(&mut self, iter: IT)
+    where
+        IT: IntoIterator<Item = (I, P)>,
+    {
+        for (item, priority) in iter {
+            self.push(item, priority);
+        }
+    }
+}
+
+impl<I, P, H> fmt::Debug for SampledPriorityQueue<I, P, H>
+where
+    I: Hash + Eq + fmt::Debug,
+    P: Ord + Clone + Into<f64> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.queue.store.map.iter()).finish()
+    }
+}
@@ -0,0 +1,359 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+//! This module contains a small discrete-event simulation engine:
+//! the [`Scheduler`] keeps the pending events ordered by time and
+//! dispatches them to a handler, that may schedule new ones.
+//!
+//! # Example
+//! ```rust
+//! use priority_queue::sim::Scheduler;
+//!
+//! // a customer arrives every 3 minutes and is served in 5
+//! #[derive(Debug)]
+//! enum Event {
+//!     Arrival(u32),
+//!     Departure(u32),
+//! }
+//!
+//! let mut sim = Scheduler::new();
+//! sim.schedule(0, Event::Arrival(0));
+//! let mut log = vec![];
+//! sim.run_until(10, |sim, now, event| {
+//!     if let Event::Arrival(n) = event {
+//!         sim.schedule(now + 3, Event::Arrival(n + 1));
+//!         sim.schedule(now + 5, Event::Departure(n));
+//!     }
+//!     log.push(format!("{} {:?}", now, event));
+//! });
+//!
+//! assert_eq!(log, vec![
+//!     "0 Arrival(0)",
+//!     "3 Arrival(1)",
+//!     "5 Departure(0)",
+//!     "6 Arrival(2)",
+//!     "8 Departure(1)",
+//!     "9 Arrival(3)",
+//! ]);
+//! assert_eq!(sim.now(), Some(&10));
+//! ```
+
+#[cfg(not(has_std))]
+use crate::std;
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+use crate::PriorityQueue;
+
+use std::cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::mem;
+
+/// The identifier of an event scheduled in a [`Scheduler`], used to
+/// cancel or reschedule it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EventId(u64);
+
+/// A pending event with its time, ordered so that the earliest,
+/// and the first scheduled among the simultaneous ones, is the greatest
+struct Pending<T, E> {
+    time: T,
+    seq: u64,
+    event: E,
+}
+
+impl<T: Ord, E> PartialEq for Pending<T, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord, E> Eq for Pending<T, E> {}
+
+impl<T: Ord, E> PartialOrd for Pending<T, E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord, E> Ord for Pending<T, E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&other.time, other.seq).cmp(&(&self.time, self.seq))
+    }
+}
+
+/// An event scheduler for discrete-event simulations.
+///
+/// The events of type `E` are scheduled at a simulation time of type `T`,
+/// that may be any type implementing `Ord`, and dispatched in order of time.
+/// Simultaneous events are dispatched in the order in which they were
+/// scheduled (or rescheduled).
+///
+/// The pending events are kept in a [`PriorityQueue`] indexed by their
+/// [`EventId`], so that they can be cancelled or moved to a different time
+/// in **O(log(N))** time, without leaving stale entries in the queue.
+///
+/// The scheduler keeps track of the current simulation time, that is the
+/// time of the last event dispatched, and rejects the events scheduled
+/// before it.
+#[cfg(has_std)]
+pub struct Scheduler<T, E, H = RandomState>
+where
+    T: Ord,
+{
+    queue: PriorityQueue<EventId, Pending<T, E>, H>,
+    now: Option<T>,
+    counter: u64,
+}
+
+#[cfg(not(has_std))]
+pub struct Scheduler<T, E, H>
+where
+    T: Ord,
+{
+    queue: PriorityQueue<EventId, Pending<T, E>, H>,
+    now: Option<T>,
+    counter: u64,
+}
+
+#[cfg(has_std)]
+impl<T, E> Scheduler<T, E>
+where
+    T: Ord + Clone,
+{
+    /// Creates an empty `Scheduler`
+    pub fn new() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+impl<T, E, H> Default for Scheduler<T, E, H>
+where
+    T: Ord + Clone,
+    H: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+impl<T, E, H> Scheduler<T, E, H>
+where
+    T: Ord + Clone,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty `Scheduler` with the default hasher
+    pub fn with_default_hasher() -> Self {
+        Self::with_hasher(H::default())
+    }
+}
+
+impl<T, E, H> Scheduler<T, E, H>
+where
+    T: Ord + Clone,
+    H: BuildHasher,
+{
+    /// Creates an empty `Scheduler` with the specified hasher
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self {
+            queue: PriorityQueue::with_hasher(hash_builder),
+            now: None,
+            counter: 0,
+        }
+    }
+
+    /// Returns the current simulation time, that is `None` until
+    /// the first event is dispatched
+    pub fn now(&self) -> Option<&T> {
+        self.now.as_ref()
+    }
+
+    /// Returns the number of pending events
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns true if there are no pending events
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Drops all the pending events, keeping the current time
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Schedules `event` at the time `at` and returns its identifier
+    ///
+    /// Computes in **O(log(N))** time.
+    ///
+    /// # Panics
+    /// Panics if `at` is before the current time
+    pub fn schedule(&mut self, at: T, event: E) -> EventId {
+        self.check_time(&at);
+        let id = EventId(self.next_seq());
+        let seq = self.next_seq();
+        self.queue.push(
+            id,
+            Pending {
+                time: at,
+                seq,
+                event,
+            },
+        );
+        id
+    }
+
+    /// Cancels the event `id`, returning it with its time,
+    /// if it is still pending
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn cancel(&mut self, id: EventId) -> Option<(T, E)> {
+        self.queue
+            .remove(&id)
+            .map(|(_, pending)| (pending.time, pending.event))
+    }
+
+    /// Moves the event `id` to the time `at`, returning its old time,
+    /// or `None` if it is not pending.
+    ///
+    /// Among simultaneous events, the rescheduled one is dispatched
+    /// after the ones already scheduled at `at`.
+    ///
+    /// Computes in **O(log(N))** time.
+    ///
+    /// # Panics
+    /// Panics if `at` is before the current time
+    pub fn reschedule(&mut self, id: EventId, at: T) -> Option<T> {
+        self.check_time(&at);
+        self.queue.get_priority(&id)?;
+        let seq = self.next_seq();
+        let mut old = None;
+        self.queue.change_priority_by(&id, |pending| {
+            old = Some(mem::replace(&mut pending.time, at));
+            pending.seq = seq;
+        });
+        old
+    }
+
+    /// Returns the time and the event `id`, if it is pending
+    pub fn get(&self, id: EventId) -> Option<(&T, &E)> {
+        self.queue
+            .get_priority(&id)
+            .map(|pending| (&pending.time, &pending.event))
+    }
+
+    /// Returns the next event to be dispatched, with its identifier and
+    /// its time, without dispatching it
+    ///
+    /// Computes in **O(1)** time.
+    pub fn peek(&self) -> Option<(EventId, &T, &E)> {
+        self.queue
+            .peek()
+            .map(|(id, pending)| (*id, &pending.time, &pending.event))
+    }
+
+    /// Removes the next event and returns it with its time, advancing
+    /// the current time to it
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn pop(&mut self) -> Option<(T, E)> {
+        let (_, pending) = self.queue.pop()?;
+        self.now = Some(pending.time.clone());
+        Some((pending.time, pending.event))
+    }
+
+    /// Dispatches to `handler`, in order, all the events scheduled up to
+    /// the time `until`, included, then advances the current time to `until`.
+    ///
+    /// The handler receives the scheduler, so it can schedule, cancel or
+    /// reschedule events, the time of the event and the event itself.
+    /// The events it schedules up to `until` are dispatched in this same call.
+    ///
+    /// Returns the number of events dispatched.
+    pub fn run_until<F>(&mut self, until: T, mut handler: F) -> usize
+    where
+        F: FnMut(&mut Self, T, E),
+    {
+        let mut dispatched = 0;
+        while matches!(self.queue.peek(), Some((_, pending)) if pending.time <= until) {
+            let (time, event) = self.pop().unwrap();
+            handler(self, time, event);
+            dispatched += 1;
+        }
+        if !matches!(&self.now, Some(now) if *now >= until) {
+            self.now = Some(until);
+        }
+        dispatched
+    }
+
+    /// Dispatches to `handler`, in order, all the events until there are
+    /// no more pending, like [`run_until`](Scheduler::run_until) without
+    /// a time limit.
+    ///
+    /// Returns the number of events dispatched.
+    pub fn run<F>(&mut self, mut handler: F) -> usize
+    where
+        F: FnMut(&mut Self, T, E),
+    {
+        let mut dispatched = 0;
+        while let Some((time, event)) = self.pop() {
+            handler(self, time, event);
+            dispatched += 1;
+        }
+        dispatched
+    }
+
+    fn check_time(&self, at: &T) {
+        assert!(
+            !matches!(&self.now, Some(now) if at < now),
+            "cannot schedule an event before the current time"
+        );
+    }
+
+    fn next_seq(&mut self) -> u64 {
+        self.counter += 1;
+        self.counter
+    }
+}
+
+impl<T, E, H> fmt::Debug for Scheduler<T, E, H>
+where
+    T: Ord + fmt::Debug,
+    E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scheduler")
+            .field("now", &self.now)
+            .field(
+                "pending",
+                &self
+                    .queue
+                    .store
+                    .map
+                    .iter()
+                    .map(|(id, p)| (id, &p.time, &p.event))
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
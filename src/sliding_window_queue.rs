@@ -0,0 +1,358 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+//! This module contains the [`SlidingWindowQueue`] type.
+//!
+//! See the type level documentation for more details and examples.
+
+#[cfg(not(has_std))]
+use crate::std;
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+use crate::core_iterators::Iter;
+use crate::{DoublePriorityQueue, PriorityQueue};
+
+use std::borrow::Borrow;
+use std::cmp::{Eq, Ord, Reverse};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::ops::Add;
+
+/// A double priority queue over a sliding window of a stream of events,
+/// to compute rolling minimums and maximums.
+///
+/// Every element is pushed together with its position in the stream,
+/// of type T, that can be an index, a timestamp or any other type that
+/// implements `Ord` and to which the width of the window, of type D,
+/// can be added.
+/// Calling [`advance_to`](SlidingWindowQueue::advance_to) moves the end of the window
+/// and evicts the elements that fell out of it, that is the ones
+/// whose position plus the width is not greater than the new position.
+///
+/// Elements are evicted through [`DoublePriorityQueue::remove`], so that
+/// the priorities can be changed freely while the elements are in the window.
+///
+/// The item is of type I, that must implement `Hash`, `Eq` and `Clone`,
+/// since it is recorded both in the queue and in the index of the positions.
+///
+/// The priority is of type P, that must implement `std::cmp::Ord`.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::SlidingWindowQueue;
+///
+/// let mut wq = SlidingWindowQueue::new(3);
+///
+/// wq.push("a", 7, 0);
+/// wq.push("b", 2, 1);
+/// wq.push("c", 5, 2);
+/// assert_eq!(wq.peek_max(), Some((&"a", &7)));
+///
+/// // The window now covers the positions 1, 2 and 3
+/// assert_eq!(wq.advance_to(3), vec![("a", 7)]);
+/// assert_eq!(wq.peek_max(), Some((&"c", &5)));
+/// assert_eq!(wq.peek_min(), Some((&"b", &2)));
+/// ```
+#[derive(Clone)]
+#[cfg(has_std)]
+pub struct SlidingWindowQueue<I, P, T, D = T, H = RandomState>
+where
+    I: Hash + Eq,
+    P: Ord,
+    T: Ord,
+{
+    queue: DoublePriorityQueue<I, P, H>,
+    positions: PriorityQueue<I, Reverse<T>, H>,
+    width: D,
+}
+
+#[derive(Clone)]
+#[cfg(not(has_std))]
+pub struct SlidingWindowQueue<I, P, T, D, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    T: Ord,
+{
+    queue: DoublePriorityQueue<I, P, H>,
+    positions: PriorityQueue<I, Reverse<T>, H>,
+    width: D,
+}
+
+#[cfg(has_std)]
+impl<I, P, T, D> SlidingWindowQueue<I, P, T, D>
+where
+    I: Hash + Eq,
+    P: Ord,
+    T: Ord,
+{
+    /// Creates an empty `SlidingWindowQueue` over a window of the given width
+    pub fn new(width: D) -> Self {
+        Self::with_default_hasher(width)
+    }
+}
+
+impl<I, P, T, D, H> SlidingWindowQueue<I, P, T, D, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    T: Ord,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty `SlidingWindowQueue` with the default hasher
+    pub fn with_default_hasher(width: D) -> Self {
+        Self {
+            queue: DoublePriorityQueue::with_default_hasher(),
+            positions: PriorityQueue::with_default_hasher(),
+            width,
+        }
+    }
+}
+
+impl<I, P, T, D, H> SlidingWindowQueue<I, P, T, D, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    T: Ord,
+    H: BuildHasher + Clone,
+{
+    /// Creates an empty `SlidingWindowQueue` with the specified hasher
+    pub fn with_hasher(width: D, hash_builder: H) -> Self {
+        Self {
+            queue: DoublePriorityQueue::with_hasher(hash_builder.clone()),
+            positions: PriorityQueue::with_hasher(hash_builder),
+            width,
+        }
+    }
+}
+
+impl<I, P, T, D, H> SlidingWindowQueue<I, P, T, D, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    T: Ord,
+    H: BuildHasher,
+{
+    /// Returns an iterator in arbitrary order over the
+    /// (item, priority) elements in the window
+    pub fn iter(&self) -> Iter<'_, I, P> {
+        self.queue.iter()
+    }
+
+    /// Removes the item with the lowest priority from
+    /// the window and returns the pair (item, priority),
+    /// or None if the window is empty.
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn pop_min(&mut self) -> Option<(I, P)> {
+        let (item, priority) = self.queue.pop_min()?;
+        self.positions.remove(&item);
+        Some((item, priority))
+    }
+
+    /// Removes the item with the greatest priority from
+    /// the window and returns the pair (item, priority),
+    /// or None if the window is empty.
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn pop_max(&mut self) -> Option<(I, P)> {
+        let (item, priority) = self.queue.pop_max()?;
+        self.positions.remove(&item);
+        Some((item, priority))
+    }
+
+    /// Change the priority of an Item returning the old value of priority,
+    /// or `None` if the item wasn't in the window.
+    ///
+    /// The position of the item is not changed.
+    ///
+    /// The operation is performed in **O(log(N))** time.
+    pub fn change_priority<Q>(&mut self, item: &Q, new_priority: P) -> Option<P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.queue.change_priority(item, new_priority)
+    }
+
+    /// Change the priority of an Item using the provided function.
+    ///
+    /// The position of the item is not changed.
+    ///
+    /// The operation is performed in **O(log(N))** time.
+    pub fn change_priority_by<Q, F>(&mut self, item: &Q, priority_setter: F)
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        F: FnOnce(&mut P),
+    {
+        self.queue.change_priority_by(item, priority_setter);
+    }
+
+    /// Get the priority of an item, or `None`, if the item is not in the window
+    pub fn get_priority<Q>(&self, item: &Q) -> Option<&P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.queue.get_priority(item)
+    }
+
+    /// Get the position of an item, or `None`, if the item is not in the window
+    pub fn get_position<Q>(&self, item: &Q) -> Option<&T>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.positions.get_priority(item).map(|p| &p.0)
+    }
+
+    /// Remove an arbitrary element from the window.
+    /// Returns the (item, priority) couple or None if the item
+    /// is not found in the window.
+    ///
+    /// The operation is performed in **O(log(N))** time.
+    pub fn remove<Q>(&mut self, item: &Q) -> Option<(I, P)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.positions.remove(item);
+        self.queue.remove(item)
+    }
+
+    /// Drops all items from the window
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.positions.clear();
+    }
+}
+
+impl<I, P, T, D, H> SlidingWindowQueue<I, P, T, D, H>
+where
+    I: Hash + Eq + Clone,
+    P: Ord,
+    T: Ord,
+    H: BuildHasher,
+{
+    /// Insert the item-priority pair into the window, at the given position.
+    ///
+    /// If an element equal to `item` was already into the window,
+    /// both its priority and its position are updated and the old value
+    /// of its priority returned in `Some`; otherwise, returns `None`.
+    ///
+    /// Positions do not need to be pushed in order. An element pushed
+    /// with a position that is already out of the window is evicted
+    /// by the next call to [`advance_to`](SlidingWindowQueue::advance_to).
+    ///
+    /// Computes in **O(log(N))** time.
+    pub fn push(&mut self, item: I, priority: P, position: T) -> Option<P> {
+        self.positions.push(item.clone(), Reverse(position));
+        self.queue.push(item, priority)
+    }
+}
+
+impl<I, P, T, D, H> SlidingWindowQueue<I, P, T, D, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    T: Ord + Clone + Add<D, Output = T>,
+    D: Clone,
+    H: BuildHasher,
+{
+    /// Moves the end of the window to `position`, evicting all the elements
+    /// whose position plus the width of the window is less than or equal to it.
+    ///
+    /// Returns the evicted (item, priority) couples, from the oldest to the newest.
+    ///
+    /// Computes in **O(k log(N))** time, where k is the number of evicted elements.
+    pub fn advance_to(&mut self, position: T) -> Vec<(I, P)> {
+        let mut evicted = Vec::new();
+        while let Some((_, Reverse(oldest))) = self.positions.peek() {
+            if oldest.clone() + self.width.clone() > position {
+                break;
+            }
+            let (item, _) = self.positions.pop().unwrap();
+            evicted.extend(self.queue.remove(&item));
+        }
+        evicted
+    }
+}
+
+impl<I, P, T, D, H> SlidingWindowQueue<I, P, T, D, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+    T: Ord,
+{
+    /// Returns the couple (item, priority) with the lowest
+    /// priority in the window, or None if it is empty.
+    ///
+    /// Computes in **O(1)** time
+    pub fn peek_min(&self) -> Option<(&I, &P)> {
+        self.queue.peek_min()
+    }
+
+    /// Returns the couple (item, priority) with the greatest
+    /// priority in the window, or None if it is empty.
+    ///
+    /// Computes in **O(1)** time
+    pub fn peek_max(&self) -> Option<(&I, &P)> {
+        self.queue.peek_max()
+    }
+
+    /// Returns the couple (item, position) of the oldest
+    /// element in the window, or None if it is empty.
+    ///
+    /// Computes in **O(1)** time
+    pub fn oldest(&self) -> Option<(&I, &T)> {
+        self.positions.peek().map(|(i, p)| (i, &p.0))
+    }
+
+    /// Returns the width of the window
+    pub fn width(&self) -> &D {
+        &self.width
+    }
+
+    /// Returns the number of elements in the window.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns true if the window contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+use std::fmt;
+
+impl<I, P, T, D, H> fmt::Debug for SlidingWindowQueue<I, P, T, D, H>
+where
+    I: Hash + Eq + fmt::Debug,
+    P: Ord + fmt::Debug,
+    T: Ord,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.queue.store.map.iter()).finish()
+    }
+}
@@ -0,0 +1,379 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+//! This module contains the [`SpaceSaving`] sketch and the [`Estimate`]
+//! it reports for the counts of the items.
+//!
+//! See the type level documentation for more details and examples.
+
+#[cfg(not(has_std))]
+use crate::std;
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+use crate::iterator_ext::select_by;
+use crate::DoublePriorityQueue;
+
+use std::borrow::Borrow;
+use std::cmp::{Eq, Ord, Ordering, PartialOrd};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::{Extend, IntoIterator, Iterator};
+use std::mem;
+
+/// The estimated count of an item tracked by a [`SpaceSaving`] sketch.
+///
+/// The true count of the item is between `count - error` and `count`.
+///
+/// Estimates are ordered by count, and the ones with the same count by
+/// decreasing error, so that the more reliable one is the greater.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Estimate {
+    /// The estimated count, never lower than the true one
+    pub count: usize,
+    /// The maximum overestimation of `count`
+    pub error: usize,
+}
+
+impl Estimate {
+    /// Returns the lowest possible true count
+    pub fn lower_bound(&self) -> usize {
+        self.count - self.error
+    }
+
+    /// Returns the highest possible true count
+    pub fn upper_bound(&self) -> usize {
+        self.count
+    }
+}
+
+impl PartialOrd for Estimate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Estimate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.count
+            .cmp(&other.count)
+            .then_with(|| other.error.cmp(&self.error))
+    }
+}
+
+/// An approximate counter of the most frequent items in a stream,
+/// that uses a fixed amount of memory, implementing the Space-Saving
+/// algorithm by Metwally, Agrawal and El Abbadi.
+///
+/// At most `capacity` items are tracked, with their [`Estimate`]d counts
+/// as priorities of a [`DoublePriorityQueue`].
+/// When an item that is not tracked arrives and the sketch is full,
+/// the item with the lowest count is popped and the new one takes its
+/// place, inheriting its count as error.
+///
+/// After `N` occurrences have been added, the error of every estimate is
+/// at most `N / capacity`, so every item occurring more than
+/// `N / capacity` times is guaranteed to be tracked.
+///
+/// The item is of type I, that must implement `Hash` and `Eq`.
+///
+/// Adding an occurrence of an item is performed in **O(log(capacity))** time.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::SpaceSaving;
+///
+/// let mut sketch = SpaceSaving::new(2);
+/// sketch.extend("abacabad".chars());
+///
+/// assert_eq!(sketch.total(), 8);
+/// let (item, estimate) = sketch.top_k(1)[0];
+/// assert_eq!(*item, 'a');
+/// assert!(estimate.lower_bound() <= 4 && 4 <= estimate.upper_bound());
+/// ```
+#[derive(Clone)]
+#[cfg(has_std)]
+pub struct SpaceSaving<I, H = RandomState>
+where
+    I: Hash + Eq,
+{
+    queue: DoublePriorityQueue<I, Estimate, H>,
+    capacity: usize,
+    total: usize,
+}
+
+#[derive(Clone)]
+#[cfg(not(has_std))]
+pub struct SpaceSaving<I, H>
+where
+    I: Hash + Eq,
+{
+    queue: DoublePriorityQueue<I, Estimate, H>,
+    capacity: usize,
+    total: usize,
+}
+
+#[cfg(has_std)]
+impl<I> SpaceSaving<I>
+where
+    I: Hash + Eq,
+{
+    /// Creates an empty sketch tracking at most `capacity` items
+    ///
+    /// # Panics
+    /// Panics if `capacity` is 0
+    pub fn new(capacity: usize) -> Self {
+        Self::with_hasher(capacity, RandomState::default())
+    }
+}
+
+impl<I, H> SpaceSaving<I, H>
+where
+    I: Hash + Eq,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty sketch tracking at most `capacity` items,
+    /// with the default hasher
+    ///
+    /// # Panics
+    /// Panics if `capacity` is 0
+    pub fn with_default_hasher(capacity: usize) -> Self {
+        Self::with_hasher(capacity, H::default())
+    }
+}
+
+impl<I, H> SpaceSaving<I, H>
+where
+    I: Hash + Eq,
+    H: BuildHasher,
+{
+    /// Creates an empty sketch tracking at most `capacity` items,
+    /// with the specified hasher
+    ///
+    /// # Panics
+    /// Panics if `capacity` is 0
+    pub fn with_hasher(capacity: usize, hash_builder: H) -> Self {
+        assert!(capacity > 0, "the capacity of the sketch must be positive");
+        Self {
+            queue: DoublePriorityQueue::with_capacity_and_hasher(capacity, hash_builder),
+            capacity,
+            total: 0,
+        }
+    }
+
+    /// Returns the maximum number of items tracked
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of items tracked
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns true if no item is tracked
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Returns the number of occurrences added to the sketch
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns the maximum error of the estimates, that is
+    /// `total / capacity`
+    pub fn error_bound(&self) -> usize {
+        self.total / self.capacity
+    }
+
+    /// Returns an iterator in arbitrary order over the tracked items
+    /// and their estimated counts
+    pub fn iter(&self) -> impl Iterator<Item = (&I, &Estimate)> {
+        self.queue.iter()
+    }
+
+    /// Drops all the items and resets the total
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.total = 0;
+    }
+
+    /// Adds an occurrence of `item` to the sketch
+    ///
+    /// Computes in **O(log(capacity))** time.
+    pub fn insert(&mut self, item: I) {
+        self.add(item, 1);
+    }
+
+    /// Adds `by` occurrences of `item` to the sketch.
+    ///
+    /// If the item is not tracked and the sketch is full, the item with
+    /// the lowest estimated count is evicted to make room for it.
+    ///
+    /// Returns the item evicted, if any.
+    ///
+    /// Computes in **O(log(capacity))** time.
+    pub fn add(&mut self, item: I, by: usize) -> Option<(I, Estimate)> {
+        self.total += by;
+        if let Some(estimate) = self.queue.get_priority(&item).copied() {
+            self.queue.change_priority(
+                &item,
+                Estimate {
+                    count: estimate.count + by,
+                    ..estimate
+                },
+            );
+            return None;
+        }
+        let evicted = if self.queue.len() < self.capacity {
+            None
+        } else {
+            self.queue.pop_min()
+        };
+        let floor = evicted.as_ref().map_or(0, |(_, e)| e.count);
+        self.queue.push(
+            item,
+            Estimate {
+                count: floor + by,
+                error: floor,
+            },
+        );
+        evicted
+    }
+
+    /// Returns the estimated count of `item`, if it is tracked
+    pub fn get<Q>(&self, item: &Q) -> Option<&Estimate>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.queue.get_priority(item)
+    }
+
+    /// Returns an upper bound on the count of any item: its estimated count
+    /// if it is tracked, otherwise the lowest estimated count when the
+    /// sketch is full, or 0 if it is not.
+    pub fn upper_bound<Q>(&self, item: &Q) -> usize
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        match self.queue.get_priority(item) {
+            Some(estimate) => estimate.count,
+            None => self.floor(),
+        }
+    }
+
+    /// Returns the `k` tracked items with the highest estimated counts,
+    /// in descending order, without removing them.
+    ///
+    /// An item is certainly among the `k` most frequent if its lower bound
+    /// is not below the upper bound of the `k+1`-th item.
+    ///
+    /// Computes in **O(capacity + k log(k))** time.
+    pub fn top_k(&self, k: usize) -> Vec<(&I, &Estimate)> {
+        select_by(self.queue.iter(), k, |a, b| b.1.cmp(a.1))
+    }
+
+    /// The count that an item not tracked may have at most
+    fn floor(&self) -> usize {
+        if self.queue.len() < self.capacity {
+            0
+        } else {
+            self.queue.peek_min().map_or(0, |(_, e)| e.count)
+        }
+    }
+}
+
+impl<I, H> SpaceSaving<I, H>
+where
+    I: Hash + Eq + Clone,
+    H: BuildHasher + Clone,
+{
+    /// Merges the sketch of another stream into `self`, so that it
+    /// summarizes the concatenation of both streams.
+    ///
+    /// The estimates of the items tracked by only one of the sketches are
+    /// raised by the highest count an item not tracked may have in the other one.
+    /// Then, the items with the lowest counts are dropped until no more
+    /// than `self.capacity()` are left.
+    ///
+    /// Computes in **O((N + M)log(N + M))** time.
+    pub fn merge<H2>(&mut self, other: &SpaceSaving<I, H2>)
+    where
+        H2: BuildHasher,
+    {
+        let (own_floor, other_floor) = (self.floor(), other.floor());
+        let hasher = self.queue.hasher().clone();
+        let mine = mem::replace(&mut self.queue, DoublePriorityQueue::with_hasher(hasher));
+
+        for (item, estimate) in mine {
+            let theirs = other.get(&item).copied().unwrap_or(Estimate {
+                count: other_floor,
+                error: other_floor,
+            });
+            let merged = Estimate {
+                count: estimate.count + theirs.count,
+                error: estimate.error + theirs.error,
+            };
+            self.queue.push(item, merged);
+        }
+        for (item, estimate) in other.iter() {
+            if self.queue.get_priority(item).is_none() {
+                let merged = Estimate {
+                    count: estimate.count + own_floor,
+                    error: estimate.error + own_floor,
+                };
+                self.queue.push(item.clone(), merged);
+            }
+        }
+        while self.queue.len() > self.capacity {
+            self.queue.pop_min();
+        }
+        self.total += other.total;
+    }
+}
+
+impl<I, H> Extend<I> for SpaceSaving<I, H>
+where
+    I: Hash + Eq,
+    H: BuildHasher,
+{
+    /// Adds an occurrence of each item yielded by the iterator
+    fn extend<IT>(&mut self, iter: IT)
+    where
+        IT: IntoIterator<Item = I>,
+    {
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
+impl<I, H> fmt::Debug for SpaceSaving<I, H>
+where
+    I: Hash + Eq + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.queue.store.map.iter()).finish()
+    }
+}
@@ -31,8 +31,10 @@ use std::collections::hash_map::RandomState;
 use std::hash::{BuildHasher, Hash};
 use std::iter::{FromIterator, IntoIterator, Iterator};
 use std::mem::swap;
+#[cfg(feature = "debug-hash-check")]
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use indexmap::map::{IndexMap, MutableKeys};
+use indexmap::map::{IndexMap, MutableKeys, RawEntryApiV1};
 
 /// Internal storage of PriorityQueue and DoublePriorityQueue
 #[derive(Clone)]
@@ -47,6 +49,8 @@ where
     pub qp: Vec<usize>,         // Performs the translation from the index
     // of the map to the index of the heap
     pub size: usize, // The size of the heap
+    #[cfg(feature = "debug-hash-check")]
+    pub hash_check: HashCheck, // Items handed out by mutable reference
 }
 
 #[derive(Clone)]
@@ -61,6 +65,8 @@ where
     pub qp: Vec<usize>,         // Performs the translation from the index
     // of the map to the index of the heap
     pub size: usize, // The size of the heap
+    #[cfg(feature = "debug-hash-check")]
+    pub hash_check: HashCheck, // Items handed out by mutable reference
 }
 
 // do not [derive(Eq)] to loosen up trait requirements for other types and impls
@@ -139,6 +145,8 @@ where
             heap: Vec::with_capacity(capacity),
             qp: Vec::with_capacity(capacity),
             size: 0,
+            #[cfg(feature = "debug-hash-check")]
+            hash_check: HashCheck::default(),
         }
     }
 
@@ -220,6 +228,27 @@ where
         self.size == 0
     }
 
+    /// Records that the item in position `index` of the map
+    /// is handed out by mutable reference.
+    ///
+    /// Does nothing unless the `debug-hash-check` feature is enabled.
+    #[inline]
+    pub fn mark_unchecked(&self, index: usize) {
+        #[cfg(feature = "debug-hash-check")]
+        self.hash_check.mark(index);
+        #[cfg(not(feature = "debug-hash-check"))]
+        let _ = index;
+    }
+
+    /// Records that all the items are handed out by mutable reference.
+    ///
+    /// Does nothing unless the `debug-hash-check` feature is enabled.
+    #[inline]
+    pub fn mark_all_unchecked(&self) {
+        #[cfg(feature = "debug-hash-check")]
+        self.hash_check.mark_all();
+    }
+
     /// Swap two elements keeping a consistent state.
     ///
     /// Computes in **O(1)** time
@@ -237,6 +266,8 @@ where
     pub fn swap_remove(&mut self, index: usize) -> Option<(I, P)> {
         // swap_remove the head
         let head = self.heap.swap_remove(index);
+        #[cfg(feature = "debug-hash-check")]
+        self.hash_check.swap_remove(head, self.size - 1);
         self.size -= 1;
         // swap remove the old heap head from the qp
         if self.size == index {
@@ -292,6 +323,7 @@ where
         I: Borrow<Q>,
         Q: Eq + Hash,
     {
+        self.check_hashes();
         let Store { map, qp, .. } = self;
         map.get_full_mut(item).map(|(index, _, p)| {
             swap(p, &mut new_priority);
@@ -313,6 +345,7 @@ where
         Q: Eq + Hash,
         F: FnOnce(&mut P),
     {
+        self.check_hashes();
         let Store { map, qp, .. } = self;
         map.get_full_mut(item).map(|(index, _, mut p)| {
             priority_setter(&mut p);
@@ -326,6 +359,7 @@ where
         I: Borrow<Q>,
         Q: Eq + Hash,
     {
+        self.check_hashes();
         self.map.get(item)
     }
 
@@ -336,6 +370,7 @@ where
         I: Borrow<Q>,
         Q: Eq + Hash,
     {
+        self.check_hashes();
         self.map.get_full(item).map(|(_, k, v)| (k, v))
     }
 
@@ -353,7 +388,10 @@ where
         I: Borrow<Q>,
         Q: Eq + Hash,
     {
-        self.map.get_full_mut2(item).map(|(_, k, v)| (k, &*v))
+        self.check_hashes();
+        let index = self.map.get_index_of(item)?;
+        self.mark_unchecked(index);
+        self.map.get_index_mut2(index).map(|(k, v)| (k, &*v))
     }
 
     pub fn remove<Q: ?Sized>(&mut self, item: &Q) -> Option<(I, P, usize)>
@@ -361,33 +399,66 @@ where
         I: Borrow<Q>,
         Q: Eq + Hash,
     {
-        self.map.swap_remove_full(item).map(|(i, item, priority)| {
-            self.size -= 1;
+        self.check_hashes();
+        self.map
+            .swap_remove_full(item)
+            .map(|(i, item, priority)| self.removed(i, item, priority))
+    }
 
-            let pos = self.qp.swap_remove(i);
-            self.heap.swap_remove(pos);
-            if i < self.size {
-                unsafe {
-                    let qpi = self.qp.get_unchecked_mut(i);
-                    if *qpi == self.size {
-                        *qpi = pos;
-                    } else {
-                        *self.heap.get_unchecked_mut(*qpi) = i;
-                    }
+    /// Updates the heap after the item in position `i` of the map was
+    /// swap removed from it, returning the item together with its priority
+    /// and the position it had in the heap.
+    fn removed(&mut self, i: usize, item: I, priority: P) -> (I, P, usize) {
+        self.size -= 1;
+
+        let pos = self.qp.swap_remove(i);
+        self.heap.swap_remove(pos);
+        if i < self.size {
+            unsafe {
+                let qpi = self.qp.get_unchecked_mut(i);
+                if *qpi == self.size {
+                    *qpi = pos;
+                } else {
+                    *self.heap.get_unchecked_mut(*qpi) = i;
                 }
             }
-            if pos < self.size {
-                unsafe {
-                    let heap_pos = self.heap.get_unchecked_mut(pos);
-                    if *heap_pos == self.size {
-                        *heap_pos = i;
-                    } else {
-                        *self.qp.get_unchecked_mut(*heap_pos) = pos;
-                    }
+        }
+        if pos < self.size {
+            unsafe {
+                let heap_pos = self.heap.get_unchecked_mut(pos);
+                if *heap_pos == self.size {
+                    *heap_pos = i;
+                } else {
+                    *self.qp.get_unchecked_mut(*heap_pos) = pos;
                 }
             }
-            (item, priority, pos)
-        })
+        }
+        (item, priority, pos)
+    }
+
+    /// Removes the item in position `index` of the map, returning it
+    /// together with its priority and the position it had in the heap.
+    pub fn remove_index(&mut self, index: usize) -> Option<(I, P, usize)> {
+        self.map
+            .swap_remove_index(index)
+            .map(|(item, priority)| self.removed(index, item, priority))
+    }
+
+    /// Returns the position in the map of the item with the given `hash`
+    /// for which `is_match` returns true, or `None` if there is no such item.
+    pub fn get_index_by_hash<F>(&self, hash: u64, is_match: F) -> Option<usize>
+    where
+        F: FnMut(&I) -> bool,
+    {
+        self.check_hashes();
+        self.map.raw_entry_v1().index_from_hash(hash, is_match)
+    }
+
+    /// Change the priority of the item in position `index` of the map,
+    /// returning the old priority and the position of the item in the heap.
+    pub fn change_priority_at(&mut self, index: usize, mut new_priority: P) -> (P, usize) {
+        swap(&mut self.map[index], &mut new_priority);
+        (new_priority, self.qp[index])
     }
 
     /// Returns the items not ordered
@@ -433,6 +504,110 @@ where
     }
 }
 
+impl<I, P, H> Store<I, P, H>
+where
+    P: Ord,
+    I: Hash + Eq,
+    H: BuildHasher,
+{
+    /// Asserts that the items handed out by mutable reference since the
+    /// last check can still be found in the map.
+    ///
+    /// Does nothing unless the `debug-hash-check` feature is enabled.
+    #[inline]
+    pub fn check_hashes(&self) {
+        #[cfg(feature = "debug-hash-check")]
+        self.hash_check.check(&self.map);
+    }
+
+    /// Moves the item in position `index` of the map to the bucket of its
+    /// current hash, after it was modified in a way that may have changed it.
+    ///
+    /// The item takes the last position of the map, swapping it with
+    /// the item that was there, while the heap keeps the same shape.
+    ///
+    /// If the item is now equal to another item in the map, it is removed
+    /// instead, and it is returned together with its priority and the
+    /// position it had in the heap.
+    pub fn reindex(&mut self, index: usize) -> Result<(), (I, P, usize)> {
+        // the map finds the item to remove by the hash it was inserted with
+        let (item, priority) = self.map.swap_remove_index(index).unwrap();
+        if self.map.contains_key(&item) {
+            return Err(self.removed(index, item, priority));
+        }
+        self.reinsert(index, item, priority);
+        Ok(())
+    }
+
+    /// Replaces an item with another one, keeping its priority and its
+    /// position in the heap, and returns the old item.
+    ///
+    /// Returns `Err(new)`, leaving the store unchanged, if `old` is not in
+    /// the store or `new` is equal to another item in the store.
+    pub fn replace_item<Q: ?Sized>(&mut self, old: &Q, new: I) -> Result<I, I>
+    where
+        I: Borrow<Q>,
+        Q: Eq + Hash,
+    {
+        self.check_hashes();
+        let index = match self.map.get_index_of(old) {
+            Some(index) => index,
+            None => return Err(new),
+        };
+        match self.map.get_index_of(&new) {
+            Some(other) if other == index => {
+                // equal items, no need to move it in the hash table
+                let (item, _) = self.map.get_index_mut2(index).unwrap();
+                Ok(std::mem::replace(item, new))
+            }
+            Some(_) => Err(new),
+            None => {
+                let (item, priority) = self.map.swap_remove_index(index).unwrap();
+                self.reinsert(index, new, priority);
+                Ok(item)
+            }
+        }
+    }
+
+    /// Exchanges the priorities of two items, returning their positions
+    /// in the heap, or `None` if any of them is not in the store.
+    pub fn swap_priorities<Q1: ?Sized, Q2: ?Sized>(
+        &mut self,
+        a: &Q1,
+        b: &Q2,
+    ) -> Option<(usize, usize)>
+    where
+        I: Borrow<Q1> + Borrow<Q2>,
+        Q1: Eq + Hash,
+        Q2: Eq + Hash,
+    {
+        self.check_hashes();
+        let ia = self.map.get_index_of(a)?;
+        let ib = self.map.get_index_of(b)?;
+        if ia != ib {
+            let (lo, hi) = if ia < ib { (ia, ib) } else { (ib, ia) };
+            let mut priorities = self.map.values_mut().skip(lo);
+            let first = priorities.next().unwrap();
+            let second = priorities.nth(hi - lo - 1).unwrap();
+            std::mem::swap(first, second);
+        }
+        Some((self.qp[ia], self.qp[ib]))
+    }
+
+    /// Inserts an item in the last position of the map, after the item
+    /// in position `index` was swap-removed, so that the two items swap
+    /// their places in the map while the heap keeps the same shape.
+    fn reinsert(&mut self, index: usize, item: I, priority: P) {
+        let last = self.map.len();
+        self.map.insert(item, priority);
+        self.qp.swap(index, last);
+        unsafe {
+            *self.heap.get_unchecked_mut(*self.qp.get_unchecked(index)) = index;
+            *self.heap.get_unchecked_mut(*self.qp.get_unchecked(last)) = last;
+        }
+    }
+}
+
 impl<I, P, H> IntoIterator for Store<I, P, H>
 where
     I: Hash + Eq,
@@ -502,6 +677,25 @@ where
     }
 }
 
+impl<I, P, H> From<IndexMap<I, P, H>> for Store<I, P, H>
+where
+    I: Hash + Eq,
+    P: Ord,
+{
+    /// Uses the map as it is, with the heap following the order of the map
+    fn from(map: IndexMap<I, P, H>) -> Self {
+        let size = map.len();
+        Store {
+            map,
+            heap: (0..size).collect(),
+            qp: (0..size).collect(),
+            size,
+            #[cfg(feature = "debug-hash-check")]
+            hash_check: HashCheck::default(),
+        }
+    }
+}
+
 impl<I, P, H> FromIterator<(I, P)> for Store<I, P, H>
 where
     I: Hash + Eq,
@@ -665,3 +859,79 @@ mod serde {
         }
     }
 }
+
+/// Records the items of a `Store` handed out by mutable reference,
+/// so that the next lookup can assert that their hash did not change
+#[cfg(feature = "debug-hash-check")]
+pub(crate) struct HashCheck(AtomicUsize);
+
+#[cfg(feature = "debug-hash-check")]
+impl HashCheck {
+    const NONE: usize = usize::MAX;
+    const ALL: usize = usize::MAX - 1;
+
+    /// Records that the item in position `index` of the map may have been modified
+    pub fn mark(&self, index: usize) {
+        let current = self.0.load(Ordering::Relaxed);
+        if current == Self::NONE || current == index {
+            self.0.store(index, Ordering::Relaxed);
+        } else {
+            self.mark_all();
+        }
+    }
+
+    /// Records that any item of the map may have been modified
+    pub fn mark_all(&self) {
+        self.0.store(Self::ALL, Ordering::Relaxed);
+    }
+
+    /// Follows the item moved by `swap_remove_index(head)` on a map whose
+    /// last position is `last`
+    pub fn swap_remove(&self, head: usize, last: usize) {
+        let current = self.0.load(Ordering::Relaxed);
+        if current == head {
+            self.0.store(Self::NONE, Ordering::Relaxed);
+        } else if current == last {
+            self.0.store(head, Ordering::Relaxed);
+        }
+    }
+
+    /// Asserts that the recorded items can still be found in `map`
+    pub fn check<I, P, H>(&self, map: &IndexMap<I, P, H>)
+    where
+        I: Hash + Eq,
+        H: BuildHasher,
+    {
+        let check = |index| {
+            if let Some((item, _)) = map.get_index(index) {
+                assert!(
+                    map.get_index_of(item) == Some(index),
+                    "an item of the queue was modified in a way that changed its hash or equality"
+                );
+            }
+        };
+        // no `swap`, since atomic read-modify-write operations are not
+        // available on every target
+        let current = self.0.load(Ordering::Relaxed);
+        self.0.store(Self::NONE, Ordering::Relaxed);
+        match current {
+            Self::NONE => {}
+            Self::ALL => (0..map.len()).for_each(check),
+            index => check(index),
+        }
+    }
+}
+
+#[cfg(feature = "debug-hash-check")]
+impl Default for HashCheck {
+    fn default() -> Self {
+        HashCheck(AtomicUsize::new(Self::NONE))
+    }
+}
+
+#[cfg(feature = "debug-hash-check")]
+impl Clone for HashCheck {
+    fn clone(&self) -> Self {
+        HashCheck(AtomicUsize::new(self.0.load(Ordering::Relaxed)))
+    }
+}
@@ -0,0 +1,464 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+//! This module contains the [`TaggedPriorityQueue`] type.
+//!
+//! See the type level documentation for more details and examples.
+
+#[cfg(not(has_std))]
+use crate::std;
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+use crate::priority_queue::better_to_rebuild;
+use crate::PriorityMap;
+
+use std::borrow::Borrow;
+use std::cmp::{Eq, Ord};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::iter::{IntoIterator, Iterator};
+use std::mem::take;
+
+use indexmap::map::IndexMap;
+use indexmap::set::IndexSet;
+
+/// A priority queue whose items can be labelled with a set of tags,
+/// to operate on all the items sharing a tag at once.
+///
+/// The item is of type I and the tag of type T. Both must implement
+/// `Hash`, `Eq` and `Clone`, since every item is recorded in the
+/// reverse index of each of its tags.
+///
+/// The priority is of type P, that must implement `std::cmp::Ord`.
+///
+/// Bulk operations on a tag carried by k items are performed in
+/// **O(k log(N))** time, or rebuilding the heap once in **O(N)**
+/// when k is large enough to make it convenient.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::TaggedPriorityQueue;
+///
+/// let mut pq = TaggedPriorityQueue::new();
+///
+/// pq.push("render", 5, vec!["session-1"]);
+/// pq.push("upload", 8, vec!["session-2"]);
+/// pq.push("encode", 3, vec!["session-1", "gpu"]);
+///
+/// pq.change_priority_tag(&"gpu", |p| *p += 10);
+/// assert_eq!(pq.peek(), Some((&"encode", &13)));
+///
+/// let mut removed = pq.remove_tag(&"session-1");
+/// removed.sort();
+/// assert_eq!(removed, vec![("encode", 13), ("render", 5)]);
+/// assert_eq!(pq.pop(), Some(("upload", 8)));
+/// ```
+#[derive(Clone)]
+#[cfg(has_std)]
+pub struct TaggedPriorityQueue<I, T, P, H = RandomState>
+where
+    I: Hash + Eq,
+    T: Hash + Eq,
+    P: Ord,
+{
+    pm: PriorityMap<I, Vec<T>, P, H>,
+    tags: IndexMap<T, IndexSet<I, H>, H>,
+}
+
+#[derive(Clone)]
+#[cfg(not(has_std))]
+pub struct TaggedPriorityQueue<I, T, P, H>
+where
+    I: Hash + Eq,
+    T: Hash + Eq,
+    P: Ord,
+{
+    pm: PriorityMap<I, Vec<T>, P, H>,
+    tags: IndexMap<T, IndexSet<I, H>, H>,
+}
+
+impl<I, T, P, H> Default for TaggedPriorityQueue<I, T, P, H>
+where
+    I: Hash + Eq,
+    T: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+#[cfg(has_std)]
+impl<I, T, P> TaggedPriorityQueue<I, T, P>
+where
+    I: Hash + Eq,
+    T: Hash + Eq,
+    P: Ord,
+{
+    /// Creates an empty `TaggedPriorityQueue`
+    pub fn new() -> Self {
+        Self::with_default_hasher()
+    }
+}
+
+impl<I, T, P, H> TaggedPriorityQueue<I, T, P, H>
+where
+    I: Hash + Eq,
+    T: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty `TaggedPriorityQueue` with the default hasher
+    pub fn with_default_hasher() -> Self {
+        Self {
+            pm: PriorityMap::with_default_hasher(),
+            tags: IndexMap::with_hasher(H::default()),
+        }
+    }
+}
+
+impl<I, T, P, H> TaggedPriorityQueue<I, T, P, H>
+where
+    I: Hash + Eq,
+    T: Hash + Eq,
+    P: Ord,
+    H: BuildHasher + Clone,
+{
+    /// Creates an empty `TaggedPriorityQueue` with the specified hasher
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self {
+            pm: PriorityMap::with_hasher(hash_builder.clone()),
+            tags: IndexMap::with_hasher(hash_builder),
+        }
+    }
+
+    /// Insert the item-priority pair into the queue, labelled with the given tags.
+    ///
+    /// If an element equal to `item` was already into the queue,
+    /// its priority and its tags are replaced and the old value of its
+    /// priority returned in `Some`; otherwise, returns `None`.
+    ///
+    /// Computes in **O(log(N) + K)** time, where K is the number of tags.
+    pub fn push<IT>(&mut self, item: I, priority: P, tags: IT) -> Option<P>
+    where
+        I: Clone,
+        T: Clone,
+        IT: IntoIterator<Item = T>,
+    {
+        if let Some(old_tags) = self.pm.get_value_mut(&item).map(take) {
+            self.unlink(&item, &old_tags);
+        }
+        let mut item_tags = Vec::new();
+        for tag in tags {
+            if !item_tags.contains(&tag) {
+                self.link(tag.clone(), item.clone());
+                item_tags.push(tag);
+            }
+        }
+        self.pm
+            .insert(item, item_tags, priority)
+            .map(|(_, priority)| priority)
+    }
+
+    /// Adds a tag to an item already in the queue.
+    ///
+    /// Returns `false` if the item is not in the queue or if it
+    /// already carried the tag.
+    pub fn add_tag<Q>(&mut self, item: &Q, tag: T) -> bool
+    where
+        I: Borrow<Q> + Clone,
+        T: Clone,
+        Q: ?Sized + Eq + Hash,
+    {
+        let (item, item_tags) = match self.pm.pq.store.map.get_full_mut(item) {
+            Some((_, item, slot)) => (item.clone(), &mut slot.value),
+            None => return false,
+        };
+        if item_tags.contains(&tag) {
+            return false;
+        }
+        item_tags.push(tag.clone());
+        self.link(tag, item);
+        true
+    }
+
+    /**************************************************************************/
+    /*                            internal functions                          */
+
+    /// Adds `item` to the reverse index of `tag`
+    fn link(&mut self, tag: T, item: I) {
+        let hash_builder = self.tags.hasher().clone();
+        self.tags
+            .entry(tag)
+            .or_insert_with(|| IndexSet::with_hasher(hash_builder))
+            .insert(item);
+    }
+}
+
+impl<I, T, P, H> TaggedPriorityQueue<I, T, P, H>
+where
+    I: Hash + Eq,
+    T: Hash + Eq,
+    P: Ord,
+{
+    /// Returns an iterator in arbitrary order over the
+    /// (item, priority) elements in the queue
+    pub fn iter(&self) -> impl Iterator<Item = (&I, &P)> {
+        self.pm.iter().map(|(i, _, p)| (i, p))
+    }
+
+    /// Returns the couple (item, priority) with the greatest
+    /// priority in the queue, or None if it is empty.
+    ///
+    /// Computes in **O(1)** time
+    pub fn peek(&self) -> Option<(&I, &P)> {
+        self.pm.peek().map(|(i, _, p)| (i, p))
+    }
+
+    /// Returns the number of elements in the queue.
+    pub fn len(&self) -> usize {
+        self.pm.len()
+    }
+
+    /// Returns true if the queue contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.pm.is_empty()
+    }
+}
+
+impl<I, T, P, H> TaggedPriorityQueue<I, T, P, H>
+where
+    I: Hash + Eq,
+    T: Hash + Eq,
+    P: Ord,
+    H: BuildHasher,
+{
+    /// Removes the item with the greatest priority from
+    /// the queue and returns the pair (item, priority),
+    /// or None if the queue is empty.
+    pub fn pop(&mut self) -> Option<(I, P)> {
+        let (item, item_tags, priority) = self.pm.pop()?;
+        self.unlink(&item, &item_tags);
+        Some((item, priority))
+    }
+
+    /// Change the priority of an Item returning the old value of priority,
+    /// or `None` if the item wasn't in the queue.
+    ///
+    /// The operation is performed in **O(log(N))** time.
+    pub fn change_priority<Q>(&mut self, item: &Q, new_priority: P) -> Option<P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.pm.change_priority(item, new_priority)
+    }
+
+    /// Get the priority of an item, or `None`, if the item is not in the queue
+    pub fn get_priority<Q>(&self, item: &Q) -> Option<&P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.pm.get_priority(item)
+    }
+
+    /// Returns the tags of an item, or `None`, if the item is not in the queue
+    pub fn tags<Q>(&self, item: &Q) -> Option<&[T]>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.pm.get_value(item).map(Vec::as_slice)
+    }
+
+    /// Removes a tag from an item.
+    ///
+    /// Returns `false` if the item is not in the queue or if it
+    /// did not carry the tag.
+    pub fn remove_item_tag<Q, R>(&mut self, item: &Q, tag: &R) -> bool
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+        T: Borrow<R>,
+        R: ?Sized + Eq + Hash,
+    {
+        let item_tags = match self.pm.get_value_mut(item) {
+            Some(item_tags) => item_tags,
+            None => return false,
+        };
+        match item_tags.iter().position(|t| t.borrow() == tag) {
+            Some(pos) => {
+                item_tags.swap_remove(pos);
+            }
+            None => return false,
+        }
+        if let Some((index, _, items)) = self.tags.get_full_mut(tag) {
+            items.swap_remove(item);
+            if items.is_empty() {
+                self.tags.swap_remove_index(index);
+            }
+        }
+        true
+    }
+
+    /// Remove an arbitrary element from the queue, together with its tags.
+    /// Returns the (item, priority) couple or None if the item
+    /// is not found in the queue.
+    ///
+    /// The operation is performed in **O(log(N) + K)** time, where K
+    /// is the number of tags of the item.
+    pub fn remove<Q>(&mut self, item: &Q) -> Option<(I, P)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let (item, item_tags, priority) = self.pm.remove(item)?;
+        self.unlink(&item, &item_tags);
+        Some((item, priority))
+    }
+
+    /// Returns the number of items carrying the given tag.
+    pub fn count_tag<R>(&self, tag: &R) -> usize
+    where
+        T: Borrow<R>,
+        R: ?Sized + Eq + Hash,
+    {
+        self.tags.get(tag).map_or(0, IndexSet::len)
+    }
+
+    /// Returns an iterator in arbitrary order over the
+    /// (item, priority) elements carrying the given tag.
+    pub fn iter_tag<'a, R>(&'a self, tag: &R) -> impl Iterator<Item = (&'a I, &'a P)> + 'a
+    where
+        T: Borrow<R>,
+        R: ?Sized + Eq + Hash,
+    {
+        self.tags
+            .get(tag)
+            .into_iter()
+            .flatten()
+            .filter_map(move |item| self.pm.get(item).map(|(i, _, p)| (i, p)))
+    }
+
+    /// Removes all the items carrying the given tag, returning them
+    /// with their priorities in arbitrary order.
+    ///
+    /// Computes in **O(k log(N))** time for k tagged items,
+    /// or **O(N)** if the heap is rebuilt once at the end.
+    pub fn remove_tag<R>(&mut self, tag: &R) -> Vec<(I, P)>
+    where
+        T: Borrow<R>,
+        R: ?Sized + Eq + Hash,
+    {
+        let items = match self.tags.swap_remove(tag) {
+            Some(items) => items,
+            None => return Vec::new(),
+        };
+        let rebuild = better_to_rebuild(self.pm.len(), items.len());
+        let mut removed = Vec::with_capacity(items.len());
+        for item in &items {
+            let entry = if rebuild {
+                self.pm
+                    .pq
+                    .store
+                    .remove(item)
+                    .map(|(i, slot, _)| (i, slot.value, slot.priority))
+            } else {
+                self.pm.remove(item)
+            };
+            if let Some((item, item_tags, priority)) = entry {
+                self.unlink(&item, &item_tags);
+                removed.push((item, priority));
+            }
+        }
+        if rebuild {
+            self.pm.pq.heap_build();
+        }
+        removed
+    }
+
+    /// Change the priority of all the items carrying the given tag
+    /// using the provided function.
+    ///
+    /// Computes in **O(k log(N))** time for k tagged items,
+    /// or **O(N)** if the heap is rebuilt once at the end.
+    pub fn change_priority_tag<R, F>(&mut self, tag: &R, mut priority_setter: F)
+    where
+        T: Borrow<R>,
+        R: ?Sized + Eq + Hash,
+        F: FnMut(&mut P),
+    {
+        let items = match self.tags.get(tag) {
+            Some(items) => items,
+            None => return,
+        };
+        if better_to_rebuild(self.pm.len(), items.len()) {
+            for item in items {
+                if let Some(slot) = self.pm.pq.store.map.get_mut(item) {
+                    priority_setter(&mut slot.priority);
+                }
+            }
+            self.pm.pq.heap_build();
+        } else {
+            for item in items {
+                self.pm.change_priority_by(item, &mut priority_setter);
+            }
+        }
+    }
+
+    /// Drops all items and tags from the queue
+    pub fn clear(&mut self) {
+        self.pm.clear();
+        self.tags.clear();
+    }
+
+    /**************************************************************************/
+    /*                            internal functions                          */
+
+    /// Removes `item` from the reverse index of all the given tags,
+    /// dropping the tags that are left without items
+    fn unlink(&mut self, item: &I, item_tags: &[T]) {
+        for tag in item_tags {
+            if let Some((index, _, items)) = self.tags.get_full_mut(tag) {
+                items.swap_remove(item);
+                if items.is_empty() {
+                    self.tags.swap_remove_index(index);
+                }
+            }
+        }
+    }
+}
+
+use std::fmt;
+
+impl<I, T, P, H> fmt::Debug for TaggedPriorityQueue<I, T, P, H>
+where
+    I: Hash + Eq + fmt::Debug,
+    T: Hash + Eq + fmt::Debug,
+    P: Ord + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.pm.fmt(f)
+    }
+}
@@ -0,0 +1,126 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+//! This module contains the traits shared by the priority queues.
+//!
+//! They allow writing algorithms that work with any of the queues
+//! of this crate, as well as with user defined wrappers around them.
+//!
+//! # Example
+//! ```rust
+//! use priority_queue::{DoublePriorityQueue, PriorityQueue, PriorityQueueLike};
+//!
+//! fn drain_top<Q: PriorityQueueLike<&'static str, i32>>(queue: &mut Q, n: usize) -> Vec<&'static str> {
+//!     (0..n).filter_map(|_| queue.pop()).map(|(item, _)| item).collect()
+//! }
+//!
+//! let mut pq = PriorityQueue::new();
+//! let mut dpq = DoublePriorityQueue::new();
+//! for (item, priority) in vec![("a", 1), ("b", 3), ("c", 2)] {
+//!     pq.push(item, priority);
+//!     dpq.push(item, priority);
+//! }
+//!
+//! assert_eq!(drain_top(&mut pq, 2), vec!["b", "c"]);
+//! assert_eq!(drain_top(&mut dpq, 2), vec!["b", "c"]);
+//! ```
+
+#[cfg(not(has_std))]
+use crate::std;
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+/// The operations common to all the priority queues.
+///
+/// `pop` and `peek` refer to the element with the greatest priority.
+pub trait PriorityQueueLike<I, P> {
+    /// The type of the iterator returned by [`iter`](PriorityQueueLike::iter)
+    type Iter<'a>: Iterator<Item = (&'a I, &'a P)>
+    where
+        Self: 'a,
+        I: 'a,
+        P: 'a;
+
+    /// Insert the item-priority pair into the queue, returning
+    /// the old priority of the item, if it was already in the queue.
+    fn push(&mut self, item: I, priority: P) -> Option<P>;
+
+    /// Removes the item with the greatest priority from the queue
+    /// and returns the pair (item, priority), or None if the queue is empty.
+    fn pop(&mut self) -> Option<(I, P)>;
+
+    /// Returns the couple (item, priority) with the greatest
+    /// priority in the queue, or None if it is empty.
+    fn peek(&self) -> Option<(&I, &P)>;
+
+    /// Change the priority of an Item returning the old value of priority,
+    /// or `None` if the item wasn't in the queue.
+    fn change_priority<Q>(&mut self, item: &Q, new_priority: P) -> Option<P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash;
+
+    /// Remove an arbitrary element from the queue.
+    /// Returns the (item, priority) couple or None if the item
+    /// is not found in the queue.
+    fn remove<Q>(&mut self, item: &Q) -> Option<(I, P)>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash;
+
+    /// Get the priority of an item, or `None`, if the item is not in the queue
+    fn get_priority<Q>(&self, item: &Q) -> Option<&P>
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash;
+
+    /// Returns the number of elements in the queue.
+    fn len(&self) -> usize;
+
+    /// Returns true if the queue contains no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator in arbitrary order over the
+    /// (item, priority) elements in the queue
+    fn iter(&self) -> Self::Iter<'_>;
+}
+
+/// The operations of the priority queues that give access
+/// both to the lowest and to the greatest priority.
+pub trait DoubleEndedPriorityQueue<I, P>: PriorityQueueLike<I, P> {
+    /// Returns the couple (item, priority) with the lowest
+    /// priority in the queue, or None if it is empty.
+    fn peek_min(&self) -> Option<(&I, &P)>;
+
+    /// Returns the couple (item, priority) with the greatest
+    /// priority in the queue, or None if it is empty.
+    fn peek_max(&self) -> Option<(&I, &P)>;
+
+    /// Removes the item with the lowest priority from the queue
+    /// and returns the pair (item, priority), or None if the queue is empty.
+    fn pop_min(&mut self) -> Option<(I, P)>;
+
+    /// Removes the item with the greatest priority from the queue
+    /// and returns the pair (item, priority), or None if the queue is empty.
+    fn pop_max(&mut self) -> Option<(I, P)>;
+}
@@ -0,0 +1,318 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+//! This module contains the [`WeightedReservoir`] type.
+//!
+//! See the type level documentation for more details and examples.
+
+#[cfg(not(has_std))]
+use crate::std;
+#[cfg(not(has_std))]
+use std::vec::Vec;
+
+use crate::DoublePriorityQueue;
+
+use std::borrow::Borrow;
+use std::cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd};
+#[cfg(has_std)]
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::Iterator;
+
+/// A weighted random sample without replacement of fixed size over a
+/// stream of unknown length, implementing the A-Res algorithm by
+/// Efraimidis and Spirakis.
+///
+/// Each item offered to the reservoir is given the key `u^(1/w)`, where
+/// `w` is its weight and `u` a random value drawn uniformly from `[0, 1)`,
+/// and the `capacity` items with the largest keys are kept.
+/// They are the priorities of a [`DoublePriorityQueue`], so the item
+/// with the smallest key is found in **O(1)** time and replaced with
+/// [`pop_min`](DoublePriorityQueue::pop_min) in **O(log(capacity))** time.
+///
+/// The random values are supplied by the caller, so no random number
+/// generator is imposed. Samples drawn from separate shards of a stream
+/// can be combined with [`merge`](WeightedReservoir::merge).
+///
+/// The item is of type I, that must implement `Hash` and `Eq`.
+///
+/// # Example
+/// ```rust
+/// use priority_queue::WeightedReservoir;
+///
+/// let mut reservoir = WeightedReservoir::new(2);
+/// assert!(reservoir.insert("light", 1.0, 0.5));
+/// assert!(reservoir.insert("heavy", 100.0, 0.1));
+/// // with a low random value and a low weight the key is too small
+/// assert!(!reservoir.insert("tiny", 0.1, 0.1));
+///
+/// let mut sample: Vec<_> = reservoir.iter().collect();
+/// sample.sort();
+/// assert_eq!(sample, vec![&"heavy", &"light"]);
+/// ```
+#[derive(Clone)]
+#[cfg(has_std)]
+pub struct WeightedReservoir<I, H = RandomState>
+where
+    I: Hash + Eq,
+{
+    queue: DoublePriorityQueue<I, Key, H>,
+    capacity: usize,
+}
+
+#[derive(Clone)]
+#[cfg(not(has_std))]
+pub struct WeightedReservoir<I, H>
+where
+    I: Hash + Eq,
+{
+    queue: DoublePriorityQueue<I, Key, H>,
+    capacity: usize,
+}
+
+/// The key of an item, stored as `ln(u) / w` to keep the precision
+/// when the weights are large, and totally ordered
+#[derive(Clone, Copy, Debug)]
+struct Key(f64);
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Key {}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Returns the natural logarithm of `x`
+#[cfg(has_std)]
+fn ln(x: f64) -> f64 {
+    x.ln()
+}
+
+/// Returns the natural logarithm of `x`, that is in `[0, 1)`.
+///
+/// `f64::ln` needs the standard library, so it is computed from
+/// the series of `atanh`.
+#[cfg(not(has_std))]
+fn ln(x: f64) -> f64 {
+    if x == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    // x = m 2^e, with m in [1/sqrt(2), sqrt(2)]
+    let (mut x, mut e) = (x, 0);
+    if x < f64::MIN_POSITIVE {
+        // subnormal number
+        x *= (1u64 << 54) as f64;
+        e -= 54;
+    }
+    let bits = x.to_bits();
+    e += ((bits >> 52) & 0x7ff) as i32 - 1023;
+    let mut m = f64::from_bits((bits & ((1 << 52) - 1)) | (1023 << 52));
+    if m > std::f64::consts::SQRT_2 {
+        m /= 2.0;
+        e += 1;
+    }
+    // ln(m) = 2 atanh(s) = 2 (s + s^3 / 3 + s^5 / 5 + ...), with |s| < 0.18
+    let s = (m - 1.0) / (m + 1.0);
+    let (mut power, mut sum) = (s, 0.0);
+    for k in (1..30).step_by(2) {
+        sum += power / k as f64;
+        power *= s * s;
+    }
+    f64::from(e) * std::f64::consts::LN_2 + 2.0 * sum
+}
+
+#[cfg(has_std)]
+impl<I> WeightedReservoir<I>
+where
+    I: Hash + Eq,
+{
+    /// Creates an empty reservoir sampling `capacity` items
+    ///
+    /// # Panics
+    /// Panics if `capacity` is 0
+    pub fn new(capacity: usize) -> Self {
+        Self::with_hasher(capacity, RandomState::default())
+    }
+}
+
+impl<I, H> WeightedReservoir<I, H>
+where
+    I: Hash + Eq,
+    H: BuildHasher + Default,
+{
+    /// Creates an empty reservoir sampling `capacity` items,
+    /// with the default hasher
+    ///
+    /// # Panics
+    /// Panics if `capacity` is 0
+    pub fn with_default_hasher(capacity: usize) -> Self {
+        Self::with_hasher(capacity, H::default())
+    }
+}
+
+impl<I, H> WeightedReservoir<I, H>
+where
+    I: Hash + Eq,
+    H: BuildHasher,
+{
+    /// Creates an empty reservoir sampling `capacity` items,
+    /// with the specified hasher
+    ///
+    /// # Panics
+    /// Panics if `capacity` is 0
+    pub fn with_hasher(capacity: usize, hash_builder: H) -> Self {
+        assert!(
+            capacity > 0,
+            "the capacity of the reservoir must be positive"
+        );
+        Self {
+            queue: DoublePriorityQueue::with_capacity_and_hasher(capacity, hash_builder),
+            capacity,
+        }
+    }
+
+    /// Returns the number of items in the sample
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of items currently in the sample,
+    /// that is lower than the capacity only while fewer items have been offered
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns true if no item is in the sample
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Returns true if `item` is in the sample
+    pub fn contains<Q>(&self, item: &Q) -> bool
+    where
+        I: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.queue.get_priority(item).is_some()
+    }
+
+    /// Returns an iterator in arbitrary order over the items in the sample
+    pub fn iter(&self) -> impl Iterator<Item = &I> {
+        self.queue.iter().map(|(i, _)| i)
+    }
+
+    /// Returns the items in the sample in arbitrary order, consuming `self`
+    pub fn into_vec(self) -> Vec<I> {
+        self.queue.into_iter().map(|(i, _)| i).collect()
+    }
+
+    /// Drops all the items
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Offers `item`, with the given `weight`, to the reservoir.
+    ///
+    /// `random` must be drawn uniformly from `[0, 1)`, independently
+    /// for each item.
+    ///
+    /// Returns true if the item entered the sample, possibly evicting
+    /// the item with the smallest key.
+    /// Items with weight 0 are never sampled.
+    /// If the item is already in the sample, it keeps the largest of
+    /// its keys.
+    ///
+    /// Computes in **O(log(capacity))** time.
+    ///
+    /// # Panics
+    /// Panics if `weight` is negative or not finite, or if `random` is
+    /// not in the range `[0, 1)`
+    pub fn insert(&mut self, item: I, weight: f64, random: f64) -> bool {
+        assert!(
+            weight >= 0.0 && weight.is_finite(),
+            "the weight must be finite and not negative"
+        );
+        assert!(
+            (0.0..1.0).contains(&random),
+            "the random value must be in the range [0, 1)"
+        );
+        if weight == 0.0 {
+            return false;
+        }
+        self.offer(item, Key(ln(random) / weight))
+    }
+
+    /// Merges the sample of another shard of the stream into `self`,
+    /// so that it becomes a sample of both shards.
+    ///
+    /// The items of `other` are offered to `self` with the keys they
+    /// were given, and the `self.capacity()` items with the largest keys
+    /// are kept.
+    ///
+    /// Computes in **O(M log(capacity))** time.
+    pub fn merge<H2>(&mut self, other: &WeightedReservoir<I, H2>)
+    where
+        I: Clone,
+        H2: BuildHasher,
+    {
+        for (item, key) in other.queue.iter() {
+            self.offer(item.clone(), *key);
+        }
+    }
+
+    fn offer(&mut self, item: I, key: Key) -> bool {
+        if self.queue.get_priority(&item).is_some() {
+            self.queue.push_increase(item, key);
+            return true;
+        }
+        if self.queue.len() < self.capacity {
+            self.queue.push(item, key);
+            return true;
+        }
+        if matches!(self.queue.peek_min(), Some((_, min)) if key > *min) {
+            self.queue.pop_min();
+            self.queue.push(item, key);
+            return true;
+        }
+        false
+    }
+}
+
+impl<I, H> fmt::Debug for WeightedReservoir<I, H>
+where
+    I: Hash + Eq + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.queue.store.map.keys()).finish()
+    }
+}
@@ -16,3 +16,143 @@ pub fn test_compile() {
 
     let _queue2 = PQ::from_iter(Some((1, 1)));
 }
+
+pub fn test_multi_priority_queue() {
+    let mut queue: priority_queue::MultiPriorityQueue<u8, (u8, u8), BuildHasherDefault<XxHash64>> =
+        Default::default();
+    queue.push(1, (1, 2));
+    queue.pop_by::<0>();
+}
+
+pub fn test_grouped_priority_queue() {
+    let mut queue: priority_queue::GroupedPriorityQueue<u8, u8, u8, BuildHasherDefault<XxHash64>> =
+        Default::default();
+    queue.push(0, 1, 1);
+}
+
+pub fn test_tagged_priority_queue() {
+    let mut queue: priority_queue::TaggedPriorityQueue<u8, u8, u8, BuildHasherDefault<XxHash64>> =
+        Default::default();
+    queue.push(1, 1, [0]);
+}
+
+pub fn test_ranked_priority_queue() {
+    let mut queue: priority_queue::RankedPriorityQueue<u8, u8, BuildHasherDefault<XxHash64>> =
+        Default::default();
+    queue.push(1, 1);
+    queue.rank(&1);
+}
+
+pub fn test_median_queue() {
+    let mut queue: priority_queue::MedianQueue<u8, u8, BuildHasherDefault<XxHash64>> =
+        Default::default();
+    queue.push(1, 1);
+}
+
+pub fn test_sliding_window_queue() {
+    let mut queue: priority_queue::SlidingWindowQueue<
+        u8,
+        u8,
+        u32,
+        u32,
+        BuildHasherDefault<XxHash64>,
+    > = priority_queue::SlidingWindowQueue::with_default_hasher(10);
+    queue.push(1, 1, 0);
+    queue.advance_to(20);
+}
+
+pub fn test_frozen_priority_queue() {
+    let queue = PQ::from_iter(Some((1, 1)));
+    let frozen = priority_queue::FrozenPriorityQueue::from(queue);
+    frozen.rank(&1);
+}
+
+pub fn test_item_mut() {
+    let mut queue = PQ::from_iter(Some((1, 1)));
+    let mut item = queue.get_mut_rehash(&1).unwrap();
+    *item = 2;
+    drop(item);
+}
+
+pub fn test_traits() {
+    fn drain<Q: priority_queue::PriorityQueueLike<u8, u8>>(queue: &mut Q) {
+        while queue.pop().is_some() {}
+    }
+    drain(&mut PQ::from_iter(Some((1, 1))));
+}
+
+pub fn test_iterator_ext() {
+    use priority_queue::IteratorExt;
+    let queue = Some((1u8, 1u8))
+        .into_iter()
+        .collect_pq_with_hasher(BuildHasherDefault::<XxHash64>::default());
+    queue.peek();
+}
+
+pub fn test_merge_sorted() {
+    let sources = [[1u8, 3], [2, 4]];
+    let merged = priority_queue::merge_sorted::MergeSorted::with_hasher(
+        sources.iter().map(|s| s.iter()),
+        BuildHasherDefault::<XxHash64>::default(),
+    );
+    for _ in merged {}
+}
+
+pub fn test_priority_counter() {
+    let mut counter: priority_queue::PriorityCounter<u8, BuildHasherDefault<XxHash64>> =
+        Default::default();
+    counter.increment(1, 2);
+    counter.most_common(1);
+}
+
+pub fn test_space_saving() {
+    let mut sketch: priority_queue::SpaceSaving<u8, BuildHasherDefault<XxHash64>> =
+        priority_queue::SpaceSaving::with_default_hasher(4);
+    sketch.insert(1);
+    sketch.top_k(1);
+}
+
+pub fn test_sampled_priority_queue() {
+    let mut queue: priority_queue::SampledPriorityQueue<u8, u8, BuildHasherDefault<XxHash64>> =
+        Default::default();
+    queue.push(1, 1);
+    queue.sample(0.5);
+}
+
+pub fn test_weighted_reservoir() {
+    let mut reservoir: priority_queue::WeightedReservoir<u8, BuildHasherDefault<XxHash64>> =
+        priority_queue::WeightedReservoir::with_default_hasher(2);
+    reservoir.insert(1, 2.0, 0.5);
+    reservoir.into_vec();
+}
+
+pub fn test_priority_cache() {
+    use priority_queue::priority_cache::Lru;
+
+    let mut cache: priority_queue::PriorityCache<u8, u8, Lru, BuildHasherDefault<XxHash64>> =
+        priority_queue::PriorityCache::with_policy_and_hasher(
+            2,
+            Lru::default(),
+            Default::default(),
+        );
+    cache.insert(1, 1);
+    cache.get(&1);
+}
+
+pub fn test_scheduler() {
+    let mut sim: priority_queue::sim::Scheduler<u32, u8, BuildHasherDefault<XxHash64>> =
+        Default::default();
+    sim.schedule(1, 0);
+    sim.run(|sim, now, event| {
+        if event == 0 {
+            sim.schedule(now + 1, 1);
+        }
+    });
+}
+
+pub fn test_calendar_queue() {
+    let mut queue: priority_queue::CalendarQueue<u8, f32, BuildHasherDefault<XxHash64>> =
+        Default::default();
+    queue.push(1, 2.5);
+    queue.pop();
+}
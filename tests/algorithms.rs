@@ -0,0 +1,262 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+#[cfg(all(feature = "algorithms", test))]
+mod algorithms_tests {
+    pub use priority_queue::algorithms::{astar, dijkstra, prim};
+    use std::collections::{HashMap, VecDeque};
+
+    type Cell = (usize, usize);
+
+    // '#' is a wall, a digit is the cost of entering the cell, anything else costs 1
+    const GRID: &[&str] = &[
+        "S..#......",
+        ".#.#.####.",
+        ".#...#..#.",
+        ".####.#.#.",
+        "...9..#...",
+        ".#.####.#.",
+        ".#......#G",
+    ];
+
+    fn find(grid: &[&str], c: char) -> Cell {
+        for (y, row) in grid.iter().enumerate() {
+            if let Some(x) = row.find(c) {
+                return (x, y);
+            }
+        }
+        panic!("{} not in grid", c)
+    }
+
+    fn cost(grid: &[&str], (x, y): Cell) -> Option<u32> {
+        match grid.get(y)?.as_bytes().get(x)? {
+            b'#' => None,
+            c @ b'0'..=b'9' => Some(u32::from(c - b'0')),
+            _ => Some(1),
+        }
+    }
+
+    fn neighbours<'a>(grid: &'a [&'a str]) -> impl Fn(&Cell) -> Vec<(Cell, u32)> + 'a {
+        move |&(x, y)| {
+            let mut next = vec![(x + 1, y), (x, y + 1)];
+            if x > 0 {
+                next.push((x - 1, y));
+            }
+            if y > 0 {
+                next.push((x, y - 1));
+            }
+            next.into_iter()
+                .filter_map(|c| cost(grid, c).map(|w| (c, w)))
+                .collect()
+        }
+    }
+
+    // Bellman-Ford style relaxation, slow but obviously correct
+    fn naive_distances(grid: &[&str], source: Cell) -> HashMap<Cell, u32> {
+        let next = neighbours(grid);
+        let mut distances = HashMap::new();
+        distances.insert(source, 0);
+        let mut queue = VecDeque::new();
+        queue.push_back(source);
+        while let Some(node) = queue.pop_front() {
+            let d = distances[&node];
+            for (n, w) in next(&node) {
+                if !matches!(distances.get(&n), Some(&old) if old <= d + w) {
+                    distances.insert(n, d + w);
+                    queue.push_back(n);
+                }
+            }
+        }
+        distances
+    }
+
+    fn path_length(grid: &[&str], path: &[Cell]) -> u32 {
+        let next = neighbours(grid);
+        path.windows(2)
+            .map(|w| {
+                next(&w[0])
+                    .into_iter()
+                    .find(|(n, _)| *n == w[1])
+                    .expect("the nodes of the path must be adjacent")
+                    .1
+            })
+            .sum()
+    }
+
+    #[test]
+    fn dijkstra_grid() {
+        let source = find(GRID, 'S');
+        let paths = dijkstra(source, neighbours(GRID));
+        let expected = naive_distances(GRID, source);
+
+        assert_eq!(paths.source(), &source);
+        assert_eq!(paths.distances(), &expected);
+        assert_eq!(paths.distance(&source), Some(&0));
+        assert_eq!(paths.predecessor(&source), None);
+        assert_eq!(paths.distance(&(3, 0)), None);
+        assert_eq!(paths.path_to(&(3, 0)), None);
+
+        for (node, distance) in &expected {
+            let path = paths.path_to(node).unwrap();
+            assert_eq!(path.first(), Some(&source));
+            assert_eq!(path.last(), Some(node));
+            assert_eq!(path_length(GRID, &path), *distance);
+        }
+        assert_eq!(paths.predecessors().len(), expected.len() - 1);
+    }
+
+    #[test]
+    fn dijkstra_avoids_expensive_cell() {
+        let grid = &["S9G", ".1."];
+        let paths = dijkstra(find(grid, 'S'), neighbours(grid));
+        assert_eq!(paths.distance(&(2, 0)), Some(&4));
+        assert_eq!(
+            paths.path_to(&(2, 0)),
+            Some(vec![(0, 0), (0, 1), (1, 1), (2, 1), (2, 0)])
+        );
+    }
+
+    #[test]
+    fn dijkstra_unreachable_and_self_loops() {
+        let paths = dijkstra(0u32, |&n| {
+            // every node links to itself, and 5 is unreachable
+            vec![(n, 0), ((n + 1) % 5, 1)]
+        });
+        assert_eq!(paths.distances().len(), 5);
+        assert_eq!(paths.distance(&4), Some(&4));
+        assert_eq!(paths.distance(&5), None);
+        assert_eq!(paths.path_to(&0), Some(vec![0]));
+        let (distances, predecessors) = paths.into_parts();
+        assert_eq!(distances.len(), predecessors.len() + 1);
+    }
+
+    #[test]
+    fn astar_grid() {
+        let source = find(GRID, 'S');
+        let goal = find(GRID, 'G');
+        let expected = naive_distances(GRID, source)[&goal];
+        let manhattan = |&(x, y): &Cell| {
+            (x as i64 - goal.0 as i64).unsigned_abs() as u32
+                + (y as i64 - goal.1 as i64).unsigned_abs() as u32
+        };
+
+        let (path, length) = astar(source, |n| *n == goal, neighbours(GRID), manhattan).unwrap();
+        assert_eq!(length, expected);
+        assert_eq!(path.first(), Some(&source));
+        assert_eq!(path.last(), Some(&goal));
+        assert_eq!(path_length(GRID, &path), length);
+
+        // without heuristic it behaves like dijkstra
+        let (path, length) = astar(source, |n| *n == goal, neighbours(GRID), |_| 0).unwrap();
+        assert_eq!(length, expected);
+        assert_eq!(path_length(GRID, &path), length);
+    }
+
+    #[test]
+    fn astar_no_path() {
+        let grid = &["S#G"];
+        let goal = find(grid, 'G');
+        assert_eq!(
+            astar(find(grid, 'S'), |n| *n == goal, neighbours(grid), |_| 0),
+            None
+        );
+        // the source may already be a goal
+        assert_eq!(
+            astar(goal, |n| *n == goal, neighbours(grid), |_| 0),
+            Some((vec![goal], 0))
+        );
+    }
+
+    #[test]
+    fn prim_grid() {
+        // weights on the edges of a 4x4 grid, derived from the coordinates
+        let weight = |a: Cell, b: Cell| ((a.0 * 7 + a.1 * 13 + b.0 * 5 + b.1 * 3) % 10) as u32;
+        let next = |&(x, y): &Cell| {
+            let mut next = vec![];
+            if x + 1 < 4 {
+                next.push((x + 1, y));
+            }
+            if y + 1 < 4 {
+                next.push((x, y + 1));
+            }
+            if x > 0 {
+                next.push((x - 1, y));
+            }
+            if y > 0 {
+                next.push((x, y - 1));
+            }
+            next.into_iter()
+                .map(move |n| (n, weight((x, y).min(n), (x, y).max(n))))
+                .collect::<Vec<_>>()
+        };
+        let tree = prim((0, 0), next);
+        assert_eq!(tree.len(), 15);
+
+        // each edge attaches a new node to the tree built so far
+        let mut seen = vec![(0, 0)];
+        for (parent, child, w) in &tree {
+            assert!(seen.contains(parent));
+            assert!(!seen.contains(child));
+            assert_eq!(*w, weight(*parent.min(child), *parent.max(child)));
+            seen.push(*child);
+        }
+
+        // compare the total weight with Kruskal's algorithm
+        let mut all_edges = vec![];
+        for x in 0..4 {
+            for y in 0..4 {
+                for (n, w) in next(&(x, y)) {
+                    if (x, y) < n {
+                        all_edges.push((w, (x, y), n));
+                    }
+                }
+            }
+        }
+        all_edges.sort();
+        let mut component: HashMap<Cell, usize> = HashMap::new();
+        for x in 0..4 {
+            for y in 0..4 {
+                component.insert((x, y), x * 4 + y);
+            }
+        }
+        let mut expected = 0;
+        for (w, a, b) in all_edges {
+            let (ca, cb) = (component[&a], component[&b]);
+            if ca != cb {
+                expected += w;
+                for c in component.values_mut() {
+                    if *c == cb {
+                        *c = ca;
+                    }
+                }
+            }
+        }
+        assert_eq!(tree.iter().map(|e| e.2).sum::<u32>(), expected);
+    }
+
+    #[test]
+    fn prim_disconnected() {
+        let tree = prim(0u32, |&n| match n {
+            0 => vec![(1, 3)],
+            1 => vec![(0, 3)],
+            _ => vec![],
+        });
+        assert_eq!(tree, vec![(0, 1, 3)]);
+    }
+}
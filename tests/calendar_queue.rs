@@ -0,0 +1,208 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+#[cfg(test)]
+mod calendar_queue_tests {
+    pub use priority_queue::CalendarQueue;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn lcg(seed: u64) -> impl FnMut() -> u64 {
+        let mut seed = seed;
+        move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            seed >> 33
+        }
+    }
+
+    #[test]
+    fn push_pop() {
+        let mut cq = CalendarQueue::new();
+        assert_eq!(cq.pop(), None);
+        assert_eq!(cq.peek(), None);
+        for (i, t) in [5u32, 1, 9, 3, 7, 3].iter().enumerate() {
+            assert_eq!(cq.push(i, *t), None);
+        }
+        assert_eq!(cq.len(), 6);
+        assert_eq!(cq.push(0, 4), Some(5));
+        let mut popped = vec![];
+        while let Some((_, t)) = cq.pop() {
+            popped.push(t);
+        }
+        assert_eq!(popped, vec![1, 3, 3, 4, 7, 9]);
+        assert!(cq.is_empty());
+    }
+
+    #[test]
+    fn negative_and_far_timestamps() {
+        let cq: CalendarQueue<_, f64> = vec![
+            ("a", -3.5),
+            ("b", 1e12),
+            ("c", 0.0),
+            ("d", -1e9),
+            ("e", 2.25),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(cq.into_sorted_vec(), vec!["d", "a", "c", "e", "b"]);
+    }
+
+    #[test]
+    fn integer_and_duration_timestamps() {
+        // too close to be told apart as f64
+        let base = u64::MAX - 4096;
+        let cq: CalendarQueue<_, u64> = (0..10).map(|i| (i, base + 7 * (9 - i))).collect();
+        assert_eq!(cq.into_sorted_vec(), (0..10).rev().collect::<Vec<_>>());
+
+        let cq: CalendarQueue<_, i64> = vec![("a", -5), ("b", i64::MIN), ("c", i64::MAX), ("d", 0)]
+            .into_iter()
+            .collect();
+        assert_eq!(cq.into_sorted_vec(), vec!["b", "a", "d", "c"]);
+
+        let mut cq = CalendarQueue::new();
+        cq.push("later", Duration::from_millis(1500));
+        cq.push("sooner", Duration::from_millis(20));
+        assert_eq!(cq.pop(), Some(("sooner", Duration::from_millis(20))));
+    }
+
+    #[test]
+    fn same_timestamp() {
+        let mut cq: CalendarQueue<_, _> = (0..100).map(|i| (i, 7.0f32)).collect();
+        assert_eq!(cq.change_priority(&42, 6.5), Some(7.0));
+        assert_eq!(cq.change_priority(&420, 6.5), None);
+        assert_eq!(cq.pop(), Some((42, 6.5)));
+        for _ in 0..99 {
+            assert_eq!(cq.pop().map(|(_, t)| t), Some(7.0));
+        }
+        assert_eq!(cq.pop(), None);
+    }
+
+    #[test]
+    fn get_remove_clear() {
+        let mut cq = CalendarQueue::new();
+        cq.extend((0..50).map(|i| (i, f64::from(i) * 0.5)));
+        assert_eq!(cq.get(&4), Some((&4, &2.0)));
+        assert_eq!(cq.get_priority(&49), Some(&24.5));
+        assert_eq!(cq.remove(&0), Some((0, 0.0)));
+        assert_eq!(cq.remove(&0), None);
+        assert_eq!(cq.peek(), Some((&1, &0.5)));
+        assert_eq!(cq.iter().count(), 49);
+        cq.clear();
+        assert!(cq.is_empty());
+        cq.push(1, 100.0);
+        assert_eq!(cq.pop(), Some((1, 100.0)));
+    }
+
+    #[test]
+    fn hold_model() {
+        // the classic benchmark of event queues: each extracted timestamp
+        // is pushed again further in the future
+        let mut next = lcg(1);
+        let mut cq = CalendarQueue::new();
+        for i in 0..1000u32 {
+            cq.push(i, (next() % 10000) as f64 / 100.0);
+        }
+        let mut last = f64::NEG_INFINITY;
+        for _ in 0..20000 {
+            let (i, t) = cq.pop().unwrap();
+            assert!(t >= last);
+            last = t;
+            cq.push(i, t + (next() % 10000) as f64 / 100.0);
+        }
+        assert_eq!(cq.len(), 1000);
+    }
+
+    #[test]
+    fn matches_naive_model() {
+        let mut next = lcg(21);
+        let mut cq = CalendarQueue::new();
+        let mut model: HashMap<u64, f64> = HashMap::new();
+        for round in 0..6000 {
+            // grow for a while, then shrink, so that the buckets are resized both ways
+            let grow = (round / 1500) % 2 == 0;
+            let item = next() % 500;
+            let time = (next() % 2000) as f64 / 8.0 - 50.0;
+            match next() % 5 {
+                0 | 1 if grow => {
+                    assert_eq!(cq.push(item, time), model.insert(item, time));
+                }
+                2 => {
+                    let expected = model.get_mut(&item).map(|t| std::mem::replace(t, time));
+                    assert_eq!(cq.change_priority(&item, time), expected);
+                }
+                3 => {
+                    assert_eq!(cq.remove(&item).map(|(_, t)| t), model.remove(&item));
+                }
+                _ => {
+                    let min = model
+                        .values()
+                        .copied()
+                        .fold(None, |m: Option<f64>, t| Some(m.map_or(t, |m| m.min(t))));
+                    assert_eq!(cq.peek().map(|(_, t)| *t), min);
+                    let popped = cq.pop();
+                    assert_eq!(popped.map(|(_, t)| t), min);
+                    if let Some((i, _)) = popped {
+                        model.remove(&i);
+                    }
+                }
+            }
+            assert_eq!(cq.len(), model.len());
+            for (item, time) in model.iter().take(3) {
+                assert_eq!(cq.get_priority(item), Some(time));
+            }
+        }
+    }
+
+    #[test]
+    fn push_increase_decrease() {
+        let mut cq = CalendarQueue::new();
+        assert_eq!(cq.push_increase("a", 5), None);
+        assert_eq!(cq.push_increase("a", 3), Some(3));
+        assert_eq!(cq.push_increase("a", 8), Some(5));
+        assert_eq!(cq.get_priority("a"), Some(&8));
+        assert_eq!(cq.push_decrease("b", 6), None);
+        assert_eq!(cq.push_decrease("b", 7), Some(7));
+        assert_eq!(cq.push_decrease("a", 2), Some(8));
+        assert_eq!(cq.pop(), Some(("a", 2)));
+        assert_eq!(cq.pop(), Some(("b", 6)));
+    }
+
+    #[test]
+    fn change_priority_by() {
+        let mut cq: CalendarQueue<_, _> = (0..20).map(|i| (i, f64::from(i))).collect();
+        cq.change_priority_by(&15, |t| *t -= 20.0);
+        cq.change_priority_by(&0, |t| *t += 100.0);
+        cq.change_priority_by(&42, |t| *t = 0.0);
+        assert_eq!(cq.len(), 20);
+        assert_eq!(cq.peek(), Some((&15, &-5.0)));
+        let mut expected = vec![15];
+        expected.extend((1..20).filter(|i| *i != 15));
+        expected.push(0);
+        let sorted: Vec<_> = cq.into_sorted_iter().map(|(i, _)| i).collect();
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan() {
+        CalendarQueue::new().push(1, f64::NAN);
+    }
+}
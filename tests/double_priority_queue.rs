@@ -501,6 +501,321 @@ mod doublepq_tests {
         assert_eq!(dpq.pop_max(), Some(('b', 5)));
         assert_eq!(dpq.pop_min(), Some(('c', 1)));
     }
+
+    #[test]
+    fn ties() {
+        let mut pq = DoublePriorityQueue::new();
+        assert!(pq.peek_ties_min().is_empty());
+        assert!(pq.pop_ties_max().is_empty());
+        pq.push(0, 4);
+        assert_eq!(pq.peek_ties_min(), vec![(&0, &4)]);
+        assert_eq!(pq.peek_ties_max(), vec![(&0, &4)]);
+
+        // pseudo random priorities in a small range, to have many ties
+        let mut seed: u64 = 11;
+        for i in 1..300 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            pq.push(i, (seed >> 33) % 20);
+        }
+        while !pq.is_empty() {
+            let min = *pq.peek_min().unwrap().1;
+            let max = *pq.peek_max().unwrap().1;
+            let mut expected_min: Vec<_> = pq.iter().filter(|(_, p)| **p == min).collect();
+            let mut expected_max: Vec<_> = pq.iter().filter(|(_, p)| **p == max).collect();
+            expected_min.sort_unstable();
+            expected_max.sort_unstable();
+            let mut ties_min = pq.peek_ties_min();
+            let mut ties_max = pq.peek_ties_max();
+            ties_min.sort_unstable();
+            ties_max.sort_unstable();
+            assert_eq!(ties_min, expected_min);
+            assert_eq!(ties_max, expected_max);
+
+            let n = ties_max.len();
+            let popped = pq.pop_ties_max();
+            assert_eq!(popped.len(), n);
+            assert!(popped.iter().all(|(_, p)| *p == max));
+            if let Some((_, p)) = pq.peek_min() {
+                let min = *p;
+                assert!(pq.pop_ties_min().iter().all(|(_, p)| *p == min));
+                assert!(pq.iter().all(|(_, p)| *p > min));
+            }
+        }
+    }
+
+    #[test]
+    fn std_conversions() {
+        use std::collections::{BTreeMap, BinaryHeap};
+
+        let heap: BinaryHeap<_> = vec![(3, 'a'), (7, 'b'), (5, 'c')].into();
+        let mut pq: DoublePriorityQueue<_, _> = heap.into();
+        assert_eq!(pq.pop_max(), Some(('b', 7)));
+        let heap: BinaryHeap<_> = pq.into();
+        assert_eq!(heap.into_sorted_vec(), vec![(3, 'a'), (5, 'c')]);
+
+        let mut map = BTreeMap::new();
+        map.insert(1, vec!['x', 'y']);
+        map.insert(4, vec!['z', 'x']);
+        let mut pq: DoublePriorityQueue<_, _> = map.into();
+        assert_eq!(pq.len(), 3);
+        assert_eq!(pq.get_priority(&'x'), Some(&4));
+        pq.push('w', 1);
+        let mut map: BTreeMap<_, _> = pq.into();
+        map.values_mut().for_each(|v| v.sort_unstable());
+        assert_eq!(map.get(&1), Some(&vec!['w', 'y']));
+        assert_eq!(map.get(&4), Some(&vec!['x', 'z']));
+    }
+
+    #[test]
+    fn index_map_conversions() {
+        use indexmap::IndexMap;
+        use std::collections::hash_map::RandomState;
+
+        let mut map = IndexMap::with_hasher(RandomState::new());
+        map.insert("a", 2);
+        map.insert("b", 9);
+        map.insert("c", 4);
+        let mut pq = DoublePriorityQueue::from(map);
+        assert_eq!(pq.as_index_map().get("c"), Some(&4));
+        assert_eq!(pq.pop_max(), Some(("b", 9)));
+        assert_eq!(pq.peek_min(), Some((&"a", &2)));
+        let map = pq.into_index_map();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("a"), Some(&2));
+    }
+
+    #[test]
+    // `BuildHasher::hash_one` would require Rust 1.71
+    #[allow(clippy::manual_hash_one)]
+    fn by_hash() {
+        use std::collections::hash_map::RandomState;
+        use std::hash::{BuildHasher, Hash, Hasher};
+
+        let mut a: DoublePriorityQueue<&str, i32> =
+            DoublePriorityQueue::with_hasher(RandomState::new());
+        let mut b = DoublePriorityQueue::<&str, i32>::with_hasher(a.hasher().clone());
+        a.extend(vec![("x", 1), ("y", 2), ("z", 3)]);
+        b.extend(vec![("x", 10), ("y", 20)]);
+        let hash = |h: &RandomState| {
+            let mut hasher = h.build_hasher();
+            "x".hash(&mut hasher);
+            hasher.finish()
+        };
+        let hash = hash(a.hasher());
+
+        assert_eq!(a.get_by_hash(hash, |i| *i == "x"), Some((&"x", &1)));
+        assert_eq!(b.get_by_hash(hash, |i| *i == "x"), Some((&"x", &10)));
+        assert_eq!(a.get_by_hash(hash, |i| *i == "y"), None);
+
+        assert_eq!(a.change_priority_by_hash(hash, |i| *i == "x", 5), Some(1));
+        assert_eq!(a.change_priority_by_hash(hash, |i| *i == "w", 7), None);
+        assert_eq!(a.pop_max(), Some(("x", 5)));
+        assert_eq!(a.change_priority_by_hash(hash, |i| *i == "x", 7), None);
+
+        assert_eq!(b.remove_by_hash(hash, |i| *i == "x"), Some(("x", 10)));
+        assert_eq!(b.remove_by_hash(hash, |i| *i == "x"), None);
+        assert_eq!(b.len(), 1);
+        assert_eq!(b.pop_max(), Some(("y", 20)));
+    }
+
+    #[test]
+    fn replace_item() {
+        let mut pq = DoublePriorityQueue::new();
+        pq.push(String::from("a"), 1);
+        pq.push(String::from("b"), 5);
+        pq.push(String::from("c"), 3);
+        assert_eq!(
+            pq.replace_item("a", String::from("z")),
+            Ok(String::from("a"))
+        );
+        assert_eq!(pq.get_priority("z"), Some(&1));
+        assert_eq!(pq.get_priority("a"), None);
+        assert_eq!(
+            pq.replace_item("a", String::from("y")),
+            Err(String::from("y"))
+        );
+        assert_eq!(pq.get_priority("y"), None);
+        assert_eq!(
+            pq.replace_item("z", String::from("b")),
+            Err(String::from("b"))
+        );
+        assert_eq!(pq.get_priority("z"), Some(&1));
+        assert_eq!(pq.get_priority("b"), Some(&5));
+        assert_eq!(
+            pq.replace_item("b", String::from("b")),
+            Ok(String::from("b"))
+        );
+        assert_eq!(pq.len(), 3);
+    }
+
+    #[test]
+    fn swap_priorities() {
+        let mut pq = DoublePriorityQueue::new();
+        let mut seed: u64 = 5;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize
+        };
+        for i in 0..100 {
+            pq.push(i, next() % 1000);
+        }
+        assert!(!pq.swap_priorities(&0, &100));
+        for _ in 0..200 {
+            let (a, b) = (next() % 100, next() % 100);
+            let (pa, pb) = (*pq.get_priority(&a).unwrap(), *pq.get_priority(&b).unwrap());
+            assert!(pq.swap_priorities(&a, &b));
+            assert_eq!(pq.get_priority(&a), Some(&pb));
+            assert_eq!(pq.get_priority(&b), Some(&pa));
+        }
+        let mut last = usize::MAX;
+        while let Some((_, p)) = pq.pop_max() {
+            assert!(p <= last);
+            last = p;
+        }
+    }
+
+    #[test]
+    fn change_priority_sinks_through_child() {
+        let mut pq = DoublePriorityQueue::new();
+        pq.push(6, 0);
+        pq.push(5, 6);
+        pq.push(0, 34);
+        pq.push(19, 6);
+        pq.change_priority(&6, 44);
+        assert_eq!(pq.peek_max(), Some((&6, &44)));
+        let sorted: Vec<_> = pq.into_sorted_iter().map(|(_, p)| p).collect();
+        assert_eq!(sorted, vec![6, 6, 34, 44]);
+    }
+
+    #[test]
+    fn random_operations_keep_heap_invariant() {
+        use std::collections::HashMap;
+
+        let mut seed: u64 = 7;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize
+        };
+        for _ in 0..300 {
+            let mut pq = DoublePriorityQueue::new();
+            let mut model = HashMap::new();
+            for _ in 0..60 {
+                let item = next() % 20;
+                let priority = next() % 50;
+                match next() % 7 {
+                    0 | 1 => {
+                        assert_eq!(pq.push(item, priority), model.insert(item, priority));
+                    }
+                    2 => {
+                        let old = pq.change_priority(&item, priority);
+                        if old.is_some() {
+                            model.insert(item, priority);
+                        }
+                    }
+                    3 => {
+                        assert_eq!(pq.remove(&item), model.remove_entry(&item));
+                    }
+                    4 => {
+                        if let Some((item, _)) = pq.pop_min() {
+                            model.remove(&item);
+                        }
+                    }
+                    5 => {
+                        if let Some((item, _)) = pq.pop_max() {
+                            model.remove(&item);
+                        }
+                    }
+                    _ => {
+                        let other = next() % 20;
+                        if pq.swap_priorities(&item, &other) {
+                            let (a, b) = (model[&item], model[&other]);
+                            model.insert(item, b);
+                            model.insert(other, a);
+                        }
+                    }
+                }
+                assert_eq!(
+                    pq.peek_min().map(|(_, p)| *p),
+                    model.values().min().copied()
+                );
+                assert_eq!(
+                    pq.peek_max().map(|(_, p)| *p),
+                    model.values().max().copied()
+                );
+            }
+            let mut expected: Vec<_> = model.values().copied().collect();
+            expected.sort_unstable();
+            let sorted: Vec<_> = pq.into_sorted_iter().map(|(_, p)| p).collect();
+            assert_eq!(sorted, expected);
+        }
+    }
+
+    #[test]
+    fn transfer() {
+        let mut a = DoublePriorityQueue::new();
+        let mut b = DoublePriorityQueue::new();
+        a.push("x", 1);
+        a.push("y", 2);
+        b.push("y", 7);
+        assert!(a.transfer(&"x", &mut b));
+        assert!(a.transfer(&"y", &mut b));
+        assert!(!a.transfer(&"z", &mut b));
+        assert!(a.is_empty());
+        assert_eq!(b.get_priority(&"x"), Some(&1));
+        assert_eq!(b.get_priority(&"y"), Some(&2));
+    }
+
+    #[test]
+    fn get_mut_rehash() {
+        let mut pq = DoublePriorityQueue::new();
+        for (i, p) in ["a", "b", "c", "d"].iter().zip(1..) {
+            pq.push(i.to_string(), p);
+        }
+        pq.get_mut_rehash("a").unwrap().push('a');
+        pq.get_mut_rehash("d").unwrap().push('d');
+        assert_eq!(pq.get_priority("aa"), Some(&1));
+        assert_eq!(pq.get_priority("dd"), Some(&4));
+        assert_eq!(pq.pop_min(), Some((String::from("aa"), 1)));
+        assert_eq!(pq.pop_max(), Some((String::from("dd"), 4)));
+        assert_eq!(pq.len(), 2);
+    }
+
+    #[test]
+    fn get_mut_rehash_duplicate() {
+        let mut pq = DoublePriorityQueue::new();
+        for (i, p) in ["a", "b", "c", "d", "e"].iter().zip(1..) {
+            pq.push(i.to_string(), p);
+        }
+        let mut item = pq.get_mut_rehash("a").unwrap();
+        item.push('a');
+        assert_eq!(item.commit(), Ok(()));
+        let mut item = pq.get_mut_rehash("e").unwrap();
+        *item = String::from("aa");
+        assert_eq!(item.commit(), Err(String::from("aa")));
+        *pq.get_mut_rehash("c").unwrap() = String::from("b");
+        assert_eq!(pq.len(), 3);
+        assert_eq!(pq.pop_max(), Some((String::from("d"), 4)));
+        assert_eq!(pq.pop_min(), Some((String::from("aa"), 1)));
+        assert_eq!(pq.pop_min(), Some((String::from("b"), 2)));
+        assert!(pq.is_empty());
+    }
+
+    #[cfg(feature = "debug-hash-check")]
+    #[test]
+    #[should_panic(expected = "changed its hash")]
+    fn debug_hash_check_peek_mut() {
+        let mut pq = DoublePriorityQueue::new();
+        pq.push(String::from("a"), 1);
+        pq.push(String::from("b"), 2);
+        pq.peek_max_mut().unwrap().0.push('x');
+        pq.remove("a");
+    }
 }
 
 #[cfg(all(feature = "serde", test))]
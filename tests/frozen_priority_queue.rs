@@ -0,0 +1,93 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+#[cfg(test)]
+mod frozen_pqueue_tests {
+    pub use priority_queue::{DoublePriorityQueue, FrozenPriorityQueue, PriorityQueue};
+
+    fn sample() -> FrozenPriorityQueue<char, i32> {
+        let pq: PriorityQueue<_, _> = vec![('a', 3), ('b', 9), ('c', 1), ('d', 5), ('e', 5)]
+            .into_iter()
+            .collect();
+        pq.into()
+    }
+
+    #[test]
+    fn sorted_lookups() {
+        let frozen = sample();
+        assert_eq!(frozen.len(), 5);
+        assert_eq!(frozen.peek(), Some((&'b', &9)));
+        assert_eq!(frozen.peek_min(), Some((&'c', &1)));
+        assert_eq!(
+            frozen.iter().map(|(_, p)| *p).collect::<Vec<_>>(),
+            vec![9, 5, 5, 3, 1]
+        );
+        assert_eq!(frozen.rank(&'b'), Some(0));
+        assert_eq!(frozen.rank(&'a'), Some(3));
+        assert_eq!(frozen.rank(&'z'), None);
+        assert_eq!(frozen.nth(4), Some((&'c', &1)));
+        assert_eq!(frozen.nth(5), None);
+        assert_eq!(frozen.get_priority(&'d'), Some(&5));
+        assert_eq!(frozen.get(&'e'), Some((&'e', &5)));
+        assert!(frozen.contains(&'a'));
+        for (i, (item, _)) in frozen.iter().enumerate() {
+            assert_eq!(frozen.rank(item), Some(i));
+        }
+    }
+
+    #[test]
+    fn ranges() {
+        let frozen = sample();
+        let mut mid: Vec<_> = frozen.range(3..=5).map(|(i, _)| *i).collect();
+        mid.sort_unstable();
+        assert_eq!(mid, vec!['a', 'd', 'e']);
+        assert_eq!(frozen.count_in_range(3..5), 1);
+        assert_eq!(frozen.count_in_range(..), 5);
+        assert_eq!(frozen.count_in_range(6..), 1);
+        assert_eq!(frozen.count_in_range(..=1), 1);
+        assert_eq!(frozen.count_in_range(10..), 0);
+        assert_eq!(frozen.range(6..9).count(), 0);
+    }
+
+    #[test]
+    fn back_to_queues() {
+        let mut pq: PriorityQueue<_, _> = sample().into();
+        pq.push('f', 7);
+        assert_eq!(pq.pop(), Some(('b', 9)));
+        assert_eq!(pq.pop(), Some(('f', 7)));
+
+        let mut dpq: DoublePriorityQueue<_, _> = sample().into();
+        assert_eq!(dpq.pop_min(), Some(('c', 1)));
+        assert_eq!(dpq.pop_max(), Some(('b', 9)));
+        assert_eq!(dpq.pop_min(), Some(('a', 3)));
+
+        let frozen = FrozenPriorityQueue::from(dpq);
+        assert_eq!(frozen.into_sorted_vec().len(), 2);
+    }
+
+    #[test]
+    fn empty() {
+        let frozen: FrozenPriorityQueue<i32, i32> = PriorityQueue::new().into();
+        assert!(frozen.is_empty());
+        assert_eq!(frozen.peek(), None);
+        assert_eq!(frozen.count_in_range(..), 0);
+        assert_eq!(frozen.into_iter().count(), 0);
+    }
+}
@@ -0,0 +1,136 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+#[cfg(test)]
+mod grouped_pqueue_tests {
+    pub use priority_queue::GroupedPriorityQueue;
+
+    #[test]
+    fn push_pop_across_groups() {
+        let mut pq = GroupedPriorityQueue::new();
+        assert_eq!(pq.peek(), None);
+        pq.push("a", 1, 5);
+        pq.push("a", 2, 9);
+        pq.push("b", 3, 7);
+        assert_eq!(pq.len(), 3);
+        assert_eq!(pq.groups_len(), 2);
+        // both groups have the default priority: the item priority decides
+        assert_eq!(pq.pop(), Some(("a", 2, 9)));
+        assert_eq!(pq.pop(), Some(("b", 3, 7)));
+        assert_eq!(pq.pop(), Some(("a", 1, 5)));
+        assert_eq!(pq.pop(), None);
+        assert!(pq.is_empty());
+        assert_eq!(pq.groups_len(), 2);
+    }
+
+    #[test]
+    fn group_priority_takes_precedence() {
+        let mut pq = GroupedPriorityQueue::new();
+        pq.insert_group("low", 1);
+        pq.insert_group("high", 2);
+        pq.push("low", "x", 100);
+        pq.push("high", "y", 1);
+        assert_eq!(pq.peek(), Some((&"high", &"y", &1)));
+        assert_eq!(pq.change_group_priority(&"low", 3), Some(1));
+        assert_eq!(pq.change_group_priority(&"none", 3), None);
+        assert_eq!(pq.peek(), Some((&"low", &"x", &100)));
+        assert_eq!(pq.insert_group("high", 4), Some(2));
+        assert_eq!(pq.get_group_priority(&"high"), Some(&4));
+        assert_eq!(pq.peek(), Some((&"high", &"y", &1)));
+    }
+
+    #[test]
+    fn empty_groups_sink() {
+        let mut pq = GroupedPriorityQueue::new();
+        pq.insert_group("empty", 100);
+        pq.push("full", 'a', 1);
+        assert_eq!(pq.peek(), Some((&"full", &'a', &1)));
+        assert_eq!(pq.pop_group(&"empty"), None);
+        assert_eq!(pq.pop_group(&"full"), Some(('a', 1)));
+        assert_eq!(pq.peek(), None);
+    }
+
+    #[test]
+    fn change_priority_updates_head() {
+        let mut pq = GroupedPriorityQueue::new();
+        pq.push(1, "a", 10);
+        pq.push(1, "b", 5);
+        pq.push(2, "c", 8);
+        assert_eq!(pq.change_priority(&1, &"a", 0), Some(10));
+        assert_eq!(pq.change_priority(&2, &"a", 0), None);
+        assert_eq!(pq.peek(), Some((&2, &"c", &8)));
+        pq.change_priority_by(&1, &"b", |p| *p *= 3);
+        assert_eq!(pq.peek(), Some((&1, &"b", &15)));
+        assert_eq!(pq.peek_group(&2), Some((&"c", &8)));
+        assert_eq!(pq.get_priority(&1, &"a"), Some(&0));
+    }
+
+    #[test]
+    fn remove() {
+        let mut pq = GroupedPriorityQueue::new();
+        for i in 0..30 {
+            pq.push(i % 3, i, i);
+        }
+        assert_eq!(pq.remove(&2, &29), Some((29, 29)));
+        assert_eq!(pq.remove(&2, &29), None);
+        assert_eq!(pq.remove(&1, &29), None);
+        assert_eq!(pq.len(), 29);
+        assert_eq!(pq.peek(), Some((&1, &28, &28)));
+
+        let (g, p, queue) = pq.remove_group(&1).unwrap();
+        assert_eq!((g, p, queue.len()), (1, 0, 10));
+        assert!(pq.remove_group(&1).is_none());
+        assert_eq!(pq.len(), 19);
+        assert_eq!(pq.groups_len(), 2);
+        assert_eq!(pq.peek(), Some((&0, &27, &27)));
+        assert!(pq.group(&2).is_some());
+
+        let mut last = i32::MAX;
+        while let Some((g, i, p)) = pq.pop() {
+            assert_eq!(i % 3, g);
+            assert!(p <= last);
+            last = p;
+        }
+    }
+
+    #[test]
+    fn many_groups() {
+        let mut pq = GroupedPriorityQueue::new();
+        for g in 0..20 {
+            pq.insert_group(g, (g * 7) % 20);
+            for i in 0..5 {
+                pq.push(g, i, i * g);
+            }
+        }
+        assert_eq!(pq.iter().count(), 100);
+        for g in 0..20 {
+            pq.change_group_priority(&g, 19 - ((g * 7) % 20));
+        }
+        let mut order = Vec::new();
+        while let Some((g, _, _)) = pq.pop() {
+            if order.last() != Some(&g) {
+                order.push(g);
+            }
+        }
+        let mut expected: Vec<_> = (0..20).collect();
+        expected.sort_by_key(|g| (g * 7) % 20);
+        assert_eq!(order, expected);
+    }
+}
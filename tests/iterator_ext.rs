@@ -0,0 +1,95 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+#[cfg(test)]
+mod iterator_ext_tests {
+    pub use priority_queue::IteratorExt;
+
+    #[test]
+    fn top_and_bottom() {
+        let v = vec![(1, 5), (2, 9), (3, 1), (4, 7), (2, 3), (5, 8), (3, 10)];
+        assert_eq!(
+            v.clone().into_iter().top_k(3),
+            vec![(3, 10), (2, 9), (5, 8)]
+        );
+        assert_eq!(v.clone().into_iter().bottom_k(2), vec![(3, 1), (2, 3)]);
+        assert_eq!(v.clone().into_iter().top_k(0), vec![]);
+        assert_eq!(v.clone().into_iter().top_k(100).len(), 5);
+        assert_eq!(v.into_iter().bottom_k(100).len(), 5);
+    }
+
+    #[test]
+    fn evicted_items_can_come_back() {
+        let v = vec![("a", 1), ("b", 2), ("c", 3), ("a", 4), ("b", 0)];
+        assert_eq!(v.into_iter().top_k(2), vec![("a", 4), ("c", 3)]);
+    }
+
+    #[test]
+    fn by_key() {
+        let words = vec![("apple", 3), ("fig", 10), ("banana", 1), ("kiwi", 5)];
+        let longest = words.into_iter().top_k_by_key(2, |word, _| word.len());
+        assert_eq!(longest, vec![("banana", 1), ("apple", 3)]);
+
+        let v = vec![('x', -3), ('y', 2), ('x', 1)];
+        assert_eq!(
+            v.into_iter().top_k_by_key(1, |_, p: &i32| p.abs()),
+            vec![('x', -3)]
+        );
+    }
+
+    #[test]
+    fn matches_sorting() {
+        let mut seed: u64 = 17;
+        let v: Vec<_> = (0..500)
+            .map(|_| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((seed >> 40) % 100, (seed >> 20) % 1000)
+            })
+            .collect();
+        let mut best = std::collections::HashMap::new();
+        for (i, p) in &v {
+            let e = best.entry(*i).or_insert(*p);
+            *e = (*e).max(*p);
+        }
+        let mut expected: Vec<_> = best.values().copied().collect();
+        expected.sort_unstable_by(|a, b| b.cmp(a));
+        expected.truncate(10);
+        let top: Vec<_> = v
+            .into_iter()
+            .top_k(10)
+            .into_iter()
+            .map(|(_, p)| p)
+            .collect();
+        assert_eq!(top, expected);
+    }
+
+    #[test]
+    fn collect_with_hasher() {
+        use hashbrown::hash_map::DefaultHashBuilder;
+
+        let mut pq = vec![("a", 1), ("b", 2), ("a", 3)]
+            .into_iter()
+            .collect_pq_with_hasher(DefaultHashBuilder::default());
+        assert_eq!(pq.len(), 2);
+        assert_eq!(pq.pop(), Some(("a", 3)));
+    }
+}
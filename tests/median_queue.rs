@@ -0,0 +1,123 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+#[cfg(test)]
+mod median_queue_tests {
+    pub use priority_queue::MedianQueue;
+
+    #[test]
+    fn median() {
+        let mut mq = MedianQueue::new();
+        assert_eq!(mq.median(), None);
+        mq.push(1, 10);
+        assert_eq!(mq.median(), Some((&1, &10)));
+        mq.push(2, 20);
+        assert_eq!(mq.median(), Some((&1, &10)));
+        mq.push(3, 30);
+        assert_eq!(mq.median(), Some((&2, &20)));
+        assert_eq!(mq.push(1, 40), Some(10));
+        assert_eq!(mq.median(), Some((&3, &30)));
+        assert_eq!(mq.remove(&3), Some((3, 30)));
+        assert_eq!(mq.median(), Some((&2, &20)));
+        assert_eq!(mq.change_priority(&2, 50), Some(20));
+        assert_eq!(mq.median(), Some((&1, &40)));
+        assert_eq!(mq.change_priority(&7, 50), None);
+        assert_eq!(mq.len(), 2);
+        mq.clear();
+        assert!(mq.is_empty());
+        assert_eq!(mq.median(), None);
+    }
+
+    #[test]
+    fn quantiles() {
+        let mut mq: MedianQueue<_, _> = (0..101).map(|i| (i, i)).collect();
+        assert_eq!(mq.quantile(), Some((&50, &50)));
+        mq.set_quantile(0.9);
+        assert_eq!(mq.quantile(), Some((&90, &90)));
+        mq.set_quantile(0.0);
+        assert_eq!(mq.quantile(), Some((&0, &0)));
+        mq.set_quantile(1.0);
+        assert_eq!(mq.quantile(), Some((&100, &100)));
+        assert_eq!(mq.fraction(), 1.0);
+
+        let mut mq = MedianQueue::with_quantile(0.25);
+        mq.extend((0..5).map(|i| (i, i * 2)));
+        assert_eq!(mq.quantile(), Some((&1, &2)));
+        assert_eq!(mq.get_priority(&4), Some(&8));
+        assert!(mq.contains(&0));
+        assert_eq!(mq.iter().count(), 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_quantile() {
+        MedianQueue::<i32, i32>::with_quantile(1.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not track the median")]
+    fn median_of_quantile_queue() {
+        let mut mq = MedianQueue::with_quantile(0.9);
+        mq.push(1, 10);
+        mq.median();
+    }
+
+    #[test]
+    fn stays_consistent() {
+        // pseudo random sequence of operations checked against a naive model
+        let mut seed: u64 = 3;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as i64
+        };
+        let mut mq = MedianQueue::with_quantile(0.3);
+        let mut model = std::collections::HashMap::new();
+        for _ in 0..2000 {
+            let item = next() % 40;
+            match next() % 4 {
+                0 | 1 => {
+                    let p = next() % 100;
+                    assert_eq!(mq.push(item, p), model.insert(item, p));
+                }
+                2 => {
+                    assert_eq!(mq.remove(&item), model.remove_entry(&item));
+                }
+                _ => {
+                    let p = next() % 100;
+                    assert_eq!(
+                        mq.change_priority(&item, p),
+                        model.get_mut(&item).map(|old| std::mem::replace(old, p))
+                    );
+                }
+            }
+            let mut sorted: Vec<_> = model.values().copied().collect();
+            sorted.sort_unstable();
+            let expected = if sorted.is_empty() {
+                None
+            } else {
+                Some(sorted[(0.3 * (sorted.len() - 1) as f64) as usize])
+            };
+            assert_eq!(mq.quantile().map(|(_, p)| *p), expected);
+            assert_eq!(mq.len(), model.len());
+        }
+    }
+}
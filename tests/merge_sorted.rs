@@ -0,0 +1,88 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+#[cfg(test)]
+mod merge_sorted_tests {
+    pub use priority_queue::merge_sorted::{merge_sorted, merge_sorted_dedup, MergeSorted};
+
+    #[test]
+    fn merge() {
+        let sources = vec![vec![1, 5, 9], vec![], vec![2, 3, 10, 11], vec![5]];
+        let merged = merge_sorted(sources.into_iter().map(|s| s.into_iter()));
+        assert_eq!(merged.size_hint(), (8, Some(8)));
+        assert_eq!(merged.collect::<Vec<_>>(), vec![1, 2, 3, 5, 5, 9, 10, 11]);
+        assert_eq!(
+            merge_sorted(Vec::<std::vec::IntoIter<i32>>::new()).next(),
+            None
+        );
+    }
+
+    #[test]
+    fn dedup() {
+        let sources = vec![vec![1, 1, 4, 6], vec![1, 4, 4], vec![6, 7]];
+        let merged: Vec<_> =
+            merge_sorted_dedup(sources.into_iter().map(|s| s.into_iter())).collect();
+        assert_eq!(merged, vec![1, 4, 6, 7]);
+    }
+
+    #[test]
+    fn ties_follow_sources() {
+        use std::cmp::Ordering;
+
+        // ordered by the key only, to tell apart the sources of equal elements
+        #[derive(Debug, PartialEq, Eq)]
+        struct Tagged(u32, char);
+
+        impl Ord for Tagged {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+
+        impl PartialOrd for Tagged {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        let a = vec![Tagged(1, 'a'), Tagged(2, 'a')];
+        let b = vec![Tagged(1, 'b'), Tagged(2, 'b')];
+        let merged: Vec<_> = merge_sorted(vec![b.into_iter(), a.into_iter()])
+            .map(|t| t.1)
+            .collect();
+        assert_eq!(merged, vec!['b', 'a', 'b', 'a']);
+    }
+
+    #[test]
+    fn with_hasher() {
+        use hashbrown::hash_map::DefaultHashBuilder;
+
+        let sources = vec![(0..10).step_by(3), (1..10).step_by(3), (2..10).step_by(3)];
+        let merged: Vec<_> =
+            MergeSorted::with_hasher(sources.clone(), DefaultHashBuilder::default()).collect();
+        assert_eq!(merged, (0..10).collect::<Vec<_>>());
+        let merged: Vec<_> = MergeSorted::dedup_with_hasher(
+            sources.into_iter().chain(Some((0..10).step_by(1))),
+            DefaultHashBuilder::default(),
+        )
+        .collect();
+        assert_eq!(merged, (0..10).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,186 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+#[cfg(test)]
+mod multi_pqueue_tests {
+    pub use priority_queue::MultiPriorityQueue;
+    use std::cmp::Reverse;
+
+    #[test]
+    fn push_peek_by() {
+        let mut pq = MultiPriorityQueue::new();
+        assert_eq!(pq.peek_by::<0>(), None);
+        pq.push("a", (1, 'z'));
+        pq.push("b", (5, 'a'));
+        pq.push("c", (3, 'm'));
+        assert_eq!(pq.len(), 3);
+        assert_eq!(pq.peek_by::<0>(), Some((&"b", &(5, 'a'))));
+        assert_eq!(pq.peek_by::<1>(), Some((&"a", &(1, 'z'))));
+    }
+
+    #[test]
+    fn pop_removes_from_all_heaps() {
+        let mut pq = MultiPriorityQueue::new();
+        pq.push("a", (1, 30));
+        pq.push("b", (5, 10));
+        pq.push("c", (3, 20));
+        assert_eq!(pq.pop_by::<1>(), Some(("a", (1, 30))));
+        assert_eq!(pq.pop_by::<0>(), Some(("b", (5, 10))));
+        assert_eq!(pq.peek_by::<1>(), Some((&"c", &(3, 20))));
+        assert_eq!(pq.pop_by::<1>(), Some(("c", (3, 20))));
+        assert_eq!(pq.pop_by::<0>(), None);
+        assert!(pq.is_empty());
+    }
+
+    #[test]
+    fn push_update() {
+        let mut pq = MultiPriorityQueue::new();
+        pq.push("a", (1, 1));
+        pq.push("b", (2, 2));
+        assert_eq!(pq.push("a", (9, 0)), Some((1, 1)));
+        assert_eq!(pq.peek_by::<0>(), Some((&"a", &(9, 0))));
+        assert_eq!(pq.peek_by::<1>(), Some((&"b", &(2, 2))));
+    }
+
+    #[test]
+    fn change_priority() {
+        let mut pq = MultiPriorityQueue::new();
+        pq.push("deploy", (Reverse(3), 1));
+        pq.push("review", (Reverse(1), 5));
+        pq.push("lunch", (Reverse(2), 9));
+
+        assert_eq!(
+            pq.change_priority::<0, _>(&"deploy", Reverse(0)),
+            Some(Reverse(3))
+        );
+        assert_eq!(pq.change_priority::<1, _>(&"nap", 0), None);
+        assert_eq!(pq.peek_by::<0>().map(|(i, _)| *i), Some("deploy"));
+        assert_eq!(pq.peek_by::<1>().map(|(i, _)| *i), Some("lunch"));
+
+        pq.change_priority_by::<1, _, _>(&"review", |p| *p += 10);
+        assert_eq!(pq.get_priority::<1, _>(&"review"), Some(&15));
+        assert_eq!(pq.peek_by::<1>().map(|(i, _)| *i), Some("review"));
+
+        assert_eq!(
+            pq.change_priorities(&"lunch", (Reverse(-1), 0)),
+            Some((Reverse(2), 9))
+        );
+        assert_eq!(pq.pop_by::<0>(), Some(("lunch", (Reverse(-1), 0))));
+    }
+
+    #[test]
+    fn remove() {
+        let mut pq: MultiPriorityQueue<_, _> = (0..20).map(|i| (i, (i, 20 - i, i % 7))).collect();
+        assert_eq!(pq.remove(&19), Some((19, (19, 1, 5))));
+        assert_eq!(pq.remove(&0), Some((0, (0, 20, 0))));
+        assert_eq!(pq.remove(&0), None);
+        assert_eq!(pq.peek_by::<0>().map(|(i, _)| *i), Some(18));
+        assert_eq!(pq.peek_by::<1>().map(|(i, _)| *i), Some(1));
+        assert_eq!(pq.peek_by::<2>().map(|(_, p)| p.2), Some(6));
+    }
+
+    #[test]
+    fn heaps_stay_consistent() {
+        // pseudo random sequence of operations checked against a naive model
+        let mut seed: u64 = 42;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as i64
+        };
+        let mut pq = MultiPriorityQueue::new();
+        let mut model = std::collections::HashMap::new();
+        for _ in 0..2000 {
+            let item = next() % 50;
+            match next() % 5 {
+                0 | 1 => {
+                    let ps = (next() % 100, next() % 100);
+                    pq.push(item, ps);
+                    model.insert(item, ps);
+                }
+                2 => {
+                    assert_eq!(pq.remove(&item).map(|(_, p)| p), model.remove(&item));
+                }
+                3 => {
+                    let p = next() % 100;
+                    let old = pq.change_priority::<1, _>(&item, p);
+                    assert_eq!(
+                        old,
+                        model
+                            .get_mut(&item)
+                            .map(|ps| std::mem::replace(&mut ps.1, p))
+                    );
+                }
+                _ => {
+                    if let Some((i, ps)) = pq.pop_by::<0>() {
+                        assert_eq!(model.values().map(|p| p.0).max(), Some(ps.0));
+                        model.remove(&i);
+                    }
+                }
+            }
+            assert_eq!(pq.len(), model.len());
+            assert_eq!(
+                pq.peek_by::<1>().map(|(_, ps)| ps.1),
+                model.values().map(|p| p.1).max()
+            );
+        }
+    }
+
+    #[test]
+    fn eq() {
+        let mut a = MultiPriorityQueue::new();
+        let mut b = MultiPriorityQueue::new();
+        a.push(1, (1, 2, 3));
+        a.push(2, (4, 5, 6));
+        b.push(2, (4, 5, 6));
+        b.push(1, (1, 2, 3));
+        assert_eq!(a, b);
+        b.change_priority::<2, _>(&1, 0);
+        assert_ne!(a, b);
+    }
+}
+
+#[cfg(all(feature = "serde", test))]
+mod serde_tests_basics {
+    use priority_queue::MultiPriorityQueue;
+    use serde_test::{assert_tokens, Token};
+
+    #[test]
+    fn serde() {
+        let mut pq = MultiPriorityQueue::new();
+        pq.push("a", (1, 2));
+
+        assert_tokens(
+            &pq,
+            &[
+                Token::Seq { len: Some(1) },
+                Token::Tuple { len: 2 },
+                Token::BorrowedStr("a"),
+                Token::Tuple { len: 2 },
+                Token::I32(1),
+                Token::I32(2),
+                Token::TupleEnd,
+                Token::TupleEnd,
+                Token::SeqEnd,
+            ],
+        );
+    }
+}
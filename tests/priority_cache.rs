@@ -0,0 +1,259 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+#[cfg(test)]
+mod priority_cache_tests {
+    pub use priority_queue::priority_cache::{CachePolicy, Lfu, Lrfu, Lru};
+    pub use priority_queue::PriorityCache;
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    fn keys<C: CachePolicy<u32>>(cache: &PriorityCache<u32, u32, C>) -> Vec<u32> {
+        let mut keys: Vec<_> = cache.iter().map(|(k, _)| *k).collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    #[test]
+    fn lru() {
+        let mut cache = PriorityCache::new(3);
+        for i in 0..3 {
+            assert_eq!(cache.insert(i, i * 10), None);
+        }
+        assert_eq!(cache.get(&0), Some(&0));
+        cache.insert(3, 30);
+        assert_eq!(keys(&cache), vec![0, 2, 3]);
+        assert_eq!(cache.insert(2, 21), Some(20));
+        cache.insert(4, 40);
+        assert_eq!(keys(&cache), vec![2, 3, 4]);
+        assert_eq!(cache.get(&0), None);
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.capacity(), 3);
+    }
+
+    #[test]
+    fn lru_matches_naive_model() {
+        let mut seed: u64 = 13;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as u32
+        };
+        let mut cache = PriorityCache::new(8);
+        // most recently used at the back
+        let mut model: VecDeque<(u32, u32)> = VecDeque::new();
+        for _ in 0..3000 {
+            let key = next() % 20;
+            match next() % 4 {
+                0 | 1 => {
+                    let value = next();
+                    let old = model
+                        .iter()
+                        .position(|(k, _)| *k == key)
+                        .map(|i| model.remove(i).unwrap().1);
+                    if old.is_none() && model.len() == 8 {
+                        model.pop_front();
+                    }
+                    model.push_back((key, value));
+                    assert_eq!(cache.insert(key, value), old);
+                }
+                2 => {
+                    let hit = model
+                        .iter()
+                        .position(|(k, _)| *k == key)
+                        .map(|i| model.remove(i).unwrap());
+                    if let Some(entry) = hit {
+                        model.push_back(entry);
+                    }
+                    assert_eq!(cache.get(&key).copied(), hit.map(|(_, v)| v));
+                }
+                _ => {
+                    let expected = model.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+                    assert_eq!(cache.peek(&key).copied(), expected);
+                }
+            }
+            let mut expected: Vec<_> = model.iter().map(|(k, _)| *k).collect();
+            expected.sort_unstable();
+            assert_eq!(keys(&cache), expected);
+        }
+    }
+
+    #[test]
+    fn lfu_with_aging() {
+        let mut cache = PriorityCache::with_policy(2, Lfu::default());
+        cache.insert(1, 1);
+        cache.insert(2, 2);
+        for _ in 0..5 {
+            cache.get(&1);
+        }
+        cache.get(&2);
+        // 2 has fewer accesses than 1
+        cache.insert(3, 3);
+        assert_eq!(keys(&cache), vec![1, 3]);
+        // the age of the cache is now the score of 2, so the newer entries
+        // catch up with 1 after a few accesses
+        for k in 4..8 {
+            cache.insert(k, k);
+            for _ in 0..4 {
+                cache.get(&k);
+            }
+        }
+        assert!(!cache.contains(&1));
+    }
+
+    #[test]
+    fn lrfu_between_lru_and_lfu() {
+        for (decay, expected) in [(1.0, vec![1, 3]), (0.5, vec![2, 3])] {
+            let mut cache = PriorityCache::with_policy(2, Lrfu::new(decay));
+            cache.insert(1, 1);
+            for _ in 0..5 {
+                cache.get(&1);
+            }
+            cache.insert(2, 2);
+            cache.insert(3, 3);
+            assert_eq!(keys(&cache), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn lrfu_zero_decay() {
+        Lrfu::new(0.0);
+    }
+
+    #[test]
+    fn lrfu_matches_naive_model() {
+        let mut seed: u64 = 29;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as u32
+        };
+        let decay = 0.75;
+        let mut cache = PriorityCache::with_policy(8, Lrfu::new(decay));
+        // the CRF of every entry, decayed at every access
+        let mut model: Vec<(u32, f64)> = Vec::new();
+        // long enough to scale the weights down several times
+        for _ in 0..20000 {
+            let key = next() % 20;
+            let hit = model.iter().position(|(k, _)| *k == key);
+            let insert = next() % 2 == 0;
+            if hit.is_some() || insert {
+                if hit.is_none() && model.len() == 8 {
+                    let min = (0..8)
+                        .min_by(|&i, &j| model[i].1.partial_cmp(&model[j].1).unwrap())
+                        .unwrap();
+                    model.swap_remove(min);
+                }
+                for (_, crf) in model.iter_mut() {
+                    *crf *= decay;
+                }
+                match model.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, crf)) => *crf += 1.0,
+                    None => model.push((key, 1.0)),
+                }
+            }
+            if insert {
+                cache.insert(key, key);
+            } else {
+                assert_eq!(cache.get(&key).is_some(), hit.is_some());
+            }
+            let mut expected: Vec<_> = model.iter().map(|(k, _)| *k).collect();
+            expected.sort_unstable();
+            assert_eq!(keys(&cache), expected);
+        }
+    }
+
+    #[test]
+    fn eviction_callback() {
+        let evicted = Arc::new(Mutex::new(vec![]));
+        let log = evicted.clone();
+        let mut cache = PriorityCache::new(1);
+        cache.set_on_evict(move |k, v| log.lock().unwrap().push((k, v)));
+        cache.insert("a", 1);
+        cache.insert("a", 2);
+        assert!(evicted.lock().unwrap().is_empty());
+        cache.insert("b", 3);
+        assert_eq!(*evicted.lock().unwrap(), vec![("a", 2)]);
+        // manual eviction and removal do not call the callback
+        assert_eq!(cache.evict(), Some(("b", 3)));
+        cache.insert("c", 4);
+        assert_eq!(cache.remove("c"), Some(4));
+        assert_eq!(cache.remove("c"), None);
+        assert_eq!(evicted.lock().unwrap().len(), 1);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn send_with_callback() {
+        let evicted = Arc::new(Mutex::new(vec![]));
+        let log = evicted.clone();
+        let mut cache = PriorityCache::new(1);
+        cache.set_on_evict(move |k, v| log.lock().unwrap().push((k, v)));
+        let cache = Arc::new(Mutex::new(cache));
+        let shared = cache.clone();
+        std::thread::spawn(move || {
+            let mut cache = shared.lock().unwrap();
+            cache.insert(1, 1);
+            cache.insert(2, 2);
+        })
+        .join()
+        .unwrap();
+        assert!(cache.lock().unwrap().contains(&2));
+        assert_eq!(*evicted.lock().unwrap(), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn custom_policy() {
+        // evicts the entry with the largest key, ignoring accesses
+        struct LargestFirst;
+        impl CachePolicy<u32> for LargestFirst {
+            type Score = std::cmp::Reverse<u32>;
+            fn on_insert(&mut self, key: &u32) -> Self::Score {
+                std::cmp::Reverse(*key)
+            }
+            fn on_access(&mut self, _score: &mut Self::Score) {}
+        }
+
+        let mut cache = PriorityCache::with_policy(3, LargestFirst);
+        for k in [5, 1, 9, 3, 7].iter() {
+            cache.insert(*k, 0);
+            cache.get(k);
+        }
+        // the new entry is inserted after evicting the largest one
+        assert_eq!(keys(&cache), vec![1, 3, 7]);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn debug() {
+        let mut cache: PriorityCache<u8, u8, Lru> = PriorityCache::new(2);
+        cache.insert(1, 1);
+        assert_eq!(format!("{:?}", cache), "{1: 1}");
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_capacity() {
+        PriorityCache::<u8, u8>::new(0);
+    }
+}
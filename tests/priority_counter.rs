@@ -0,0 +1,163 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+#[cfg(test)]
+mod priority_counter_tests {
+    pub use priority_queue::PriorityCounter;
+    use std::collections::HashMap;
+
+    #[test]
+    fn increment_decrement() {
+        let mut counter = PriorityCounter::new();
+        assert_eq!(counter.increment("a", 2), 2);
+        assert_eq!(counter.increment("a", 3), 5);
+        assert_eq!(counter.increment("b", 0), 0);
+        assert!(!counter.contains("b"));
+        assert_eq!(counter.increment("b", 1), 1);
+        assert_eq!(counter.total(), 6);
+        assert_eq!(counter.len(), 2);
+
+        assert_eq!(counter.decrement("a", 4), 1);
+        assert_eq!(counter.total(), 2);
+        assert_eq!(counter.decrement("a", 4), 0);
+        assert!(!counter.contains("a"));
+        assert_eq!(counter.total(), 1);
+        assert_eq!(counter.decrement("c", 1), 0);
+        assert_eq!(counter.get("c"), 0);
+        assert_eq!(counter.remove("b"), Some(("b", 1)));
+        assert!(counter.is_empty());
+        assert_eq!(counter.total(), 0);
+    }
+
+    #[test]
+    fn most_and_least_common() {
+        let counter: PriorityCounter<_> = (1..=10).flat_map(|i| vec![i; i]).collect();
+        assert_eq!(counter.total(), 55);
+        assert_eq!(counter.most_common(0), vec![]);
+        assert_eq!(counter.most_common(1), vec![(&10, 10)]);
+        assert_eq!(counter.most_common(3), vec![(&10, 10), (&9, 9), (&8, 8)]);
+        assert_eq!(counter.least_common(1), vec![(&1, 1)]);
+        assert_eq!(counter.least_common(2), vec![(&1, 1), (&2, 2)]);
+        assert_eq!(counter.most_common(20).len(), 10);
+        let all: Vec<usize> = (1..=10).collect();
+        assert_eq!(
+            counter.least_common(20),
+            all.iter().map(|i| (i, *i)).collect::<Vec<_>>()
+        );
+        // the counter is not consumed
+        assert_eq!(counter.len(), 10);
+
+        let empty = PriorityCounter::<u8>::new();
+        assert_eq!(empty.most_common(1), vec![]);
+        assert_eq!(empty.least_common(3), vec![]);
+    }
+
+    #[test]
+    fn pop() {
+        let mut counter: PriorityCounter<_> = "abbccc".chars().collect();
+        assert_eq!(counter.pop_most_common(), Some(('c', 3)));
+        assert_eq!(counter.pop_least_common(), Some(('a', 1)));
+        assert_eq!(counter.total(), 2);
+        assert_eq!(counter.pop_least_common(), Some(('b', 2)));
+        assert_eq!(counter.pop_most_common(), None);
+    }
+
+    #[test]
+    fn set_arithmetic() {
+        let a: PriorityCounter<_> = "aaabbc".chars().collect();
+        let b: PriorityCounter<_> = "abbbbd".chars().collect();
+        let counts = |c: &PriorityCounter<char>| {
+            let mut v: Vec<_> = c.iter().map(|(i, n)| (*i, n)).collect();
+            v.sort();
+            v
+        };
+
+        let sum = a.sum(&b);
+        assert_eq!(counts(&sum), vec![('a', 4), ('b', 6), ('c', 1), ('d', 1)]);
+        assert_eq!(sum.total(), 12);
+
+        let difference = a.difference(&b);
+        assert_eq!(counts(&difference), vec![('a', 2), ('c', 1)]);
+        assert_eq!(difference.total(), 3);
+
+        let intersection = a.intersection(&b);
+        assert_eq!(counts(&intersection), vec![('a', 1), ('b', 2)]);
+        assert_eq!(intersection.total(), 3);
+
+        let union = a.union(&b);
+        assert_eq!(counts(&union), vec![('a', 3), ('b', 4), ('c', 1), ('d', 1)]);
+        assert_eq!(union.total(), 9);
+
+        assert_eq!(union.most_common(1), vec![(&'b', 4)]);
+        assert_eq!(a.union(&a), a);
+        assert_eq!(a.intersection(&a), a);
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn matches_naive_model() {
+        let mut seed: u64 = 7;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize
+        };
+        let mut counter = PriorityCounter::new();
+        let mut model: HashMap<usize, usize> = HashMap::new();
+        for _ in 0..2000 {
+            let item = next() % 30;
+            let by = next() % 4;
+            if next() % 3 == 0 {
+                let count = model.entry(item).or_default();
+                *count = count.saturating_sub(by);
+                let count = *count;
+                if count == 0 {
+                    model.remove(&item);
+                }
+                assert_eq!(counter.decrement(&item, by), count);
+            } else {
+                let count = model.entry(item).or_default();
+                *count += by;
+                let count = *count;
+                if count == 0 {
+                    model.remove(&item);
+                }
+                assert_eq!(counter.increment(item, by), count);
+            }
+            assert_eq!(counter.len(), model.len());
+            assert_eq!(counter.total(), model.values().sum::<usize>());
+            assert_eq!(
+                counter.most_common(1).first().map(|(_, c)| *c),
+                model.values().max().copied()
+            );
+            let mut top: Vec<_> = model.values().copied().collect();
+            top.sort_unstable_by(|a, b| b.cmp(a));
+            top.truncate(5);
+            assert_eq!(
+                counter
+                    .most_common(5)
+                    .into_iter()
+                    .map(|(_, c)| c)
+                    .collect::<Vec<_>>(),
+                top
+            );
+        }
+    }
+}
@@ -0,0 +1,197 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+#[cfg(test)]
+mod pmap_tests {
+    pub use priority_queue::PriorityMap;
+
+    #[derive(Debug, PartialEq)]
+    struct JobSpec {
+        command: &'static str,
+        retries: u32,
+    }
+
+    #[test]
+    fn insert_pop() {
+        let mut pm = PriorityMap::new();
+        assert_eq!(pm.peek(), None);
+        pm.insert("a", 'x', 1);
+        pm.insert("b", 'y', 2);
+        pm.insert("f", 'z', 7);
+        pm.insert("g", 'w', 4);
+        assert_eq!(pm.len(), 4);
+        assert_eq!(pm.pop(), Some(("f", 'z', 7)));
+        assert_eq!(pm.peek(), Some((&"g", &'w', &4)));
+        assert_eq!(pm.pop(), Some(("g", 'w', 4)));
+        assert_eq!(pm.len(), 2);
+    }
+
+    #[test]
+    fn insert_update() {
+        let mut pm = PriorityMap::new();
+        pm.insert("a", 1, 9);
+        pm.insert("b", 2, 8);
+        assert_eq!(pm.insert("a", 3, 1), Some((1, 9)));
+        assert_eq!(pm.peek(), Some((&"b", &2, &8)));
+        assert_eq!(pm.get(&"a"), Some((&"a", &3, &1)));
+    }
+
+    #[test]
+    fn value_does_not_need_hash() {
+        let mut pm = PriorityMap::new();
+        pm.insert(
+            1u64,
+            JobSpec {
+                command: "build",
+                retries: 0,
+            },
+            3,
+        );
+        pm.insert(
+            2u64,
+            JobSpec {
+                command: "test",
+                retries: 0,
+            },
+            5,
+        );
+
+        pm.get_value_mut(&1).unwrap().retries += 1;
+        assert_eq!(pm.get_value(&1).map(|s| s.retries), Some(1));
+        assert!(pm.contains_key(&1));
+
+        let (id, spec, priority) = pm.pop().unwrap();
+        assert_eq!((id, spec.command, priority), (2, "test", 5));
+    }
+
+    #[test]
+    fn change_priority() {
+        let mut pm = PriorityMap::new();
+        pm.insert("Processor", "cpu", 1);
+        pm.insert("Mainboard", "mb", 2);
+        pm.insert("RAM", "mem", 5);
+        assert_eq!(pm.change_priority(&"Processor", 10), Some(1));
+        assert_eq!(pm.change_priority(&"GPU", 10), None);
+        assert_eq!(pm.peek(), Some((&"Processor", &"cpu", &10)));
+
+        pm.change_priority_by(&"Mainboard", |p| *p *= 10);
+        assert_eq!(pm.pop(), Some(("Mainboard", "mb", 20)));
+        assert_eq!(pm.get_priority(&"RAM"), Some(&5));
+    }
+
+    #[test]
+    fn peek_mut() {
+        let mut pm = PriorityMap::new();
+        pm.insert('a', String::from("first"), 1);
+        pm.insert('b', String::from("second"), 2);
+        pm.peek_mut().unwrap().1.push_str(" place");
+        assert_eq!(pm.pop(), Some(('b', String::from("second place"), 2)));
+    }
+
+    #[test]
+    fn remove() {
+        let mut pm = PriorityMap::new();
+        for (i, p) in [3, 7, 1, 9, 4, 6].iter().enumerate() {
+            pm.insert(i, i * 10, *p);
+        }
+        assert_eq!(pm.remove(&3), Some((3, 30, 9)));
+        assert_eq!(pm.remove(&3), None);
+        let priorities: Vec<_> = pm.into_sorted_iter().map(|(_, _, p)| p).collect();
+        assert_eq!(priorities, vec![7, 6, 4, 3, 1]);
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut pm: PriorityMap<_, _, _> = vec![("a", 1, 1), ("b", 2, 2), ("c", 3, 3)]
+            .into_iter()
+            .collect();
+        for (_, v, p) in pm.iter_mut() {
+            *v += *p * 100;
+        }
+        assert_eq!(pm.get_value(&"c"), Some(&303));
+        assert_eq!(pm.iter().map(|(_, v, _)| *v).sum::<i32>(), 606);
+    }
+
+    #[test]
+    fn from_iter_extend() {
+        let mut pm: PriorityMap<_, _, _> = vec![("a", 1, 1), ("a", 2, 5), ("b", 3, 3)]
+            .into_iter()
+            .collect();
+        assert_eq!(pm.len(), 2);
+        assert_eq!(pm.peek(), Some((&"a", &2, &5)));
+
+        pm.extend(vec![("c", 4, 7), ("b", 5, 0)]);
+        assert_eq!(pm.pop(), Some(("c", 4, 7)));
+        assert_eq!(pm.pop(), Some(("a", 2, 5)));
+        assert_eq!(pm.pop(), Some(("b", 5, 0)));
+    }
+
+    #[test]
+    fn eq() {
+        let mut a = PriorityMap::new();
+        let mut b = PriorityMap::new();
+        a.insert(1, 'a', 1);
+        a.insert(2, 'b', 2);
+        b.insert(2, 'b', 2);
+        b.insert(1, 'a', 1);
+        assert_eq!(a, b);
+        *b.get_value_mut(&1).unwrap() = 'z';
+        assert_ne!(a, b);
+    }
+}
+
+#[cfg(all(feature = "serde", test))]
+mod serde_tests_basics {
+    use priority_queue::PriorityMap;
+    use serde_test::{assert_tokens, Token};
+
+    #[test]
+    fn serde_empty() {
+        let pm: PriorityMap<String, String, i32> = PriorityMap::new();
+
+        assert_tokens(&pm, &[Token::Seq { len: Some(0) }, Token::SeqEnd]);
+    }
+
+    #[test]
+    fn serde() {
+        let mut pm = PriorityMap::new();
+
+        pm.insert("a", 'x', 1);
+        pm.insert("b", 'y', 2);
+
+        assert_tokens(
+            &pm,
+            &[
+                Token::Seq { len: Some(2) },
+                Token::Tuple { len: 3 },
+                Token::BorrowedStr("a"),
+                Token::Char('x'),
+                Token::I32(1),
+                Token::TupleEnd,
+                Token::Tuple { len: 3 },
+                Token::BorrowedStr("b"),
+                Token::Char('y'),
+                Token::I32(2),
+                Token::TupleEnd,
+                Token::SeqEnd,
+            ],
+        );
+    }
+}
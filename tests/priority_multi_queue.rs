@@ -0,0 +1,146 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+#[cfg(test)]
+mod multi_queue_tests {
+    pub use priority_queue::PriorityMultiQueue;
+
+    #[test]
+    fn push_pop_repeated() {
+        let mut pq = PriorityMultiQueue::new();
+        assert_eq!(pq.peek(), None);
+        pq.push("a", 1);
+        pq.push("b", 5);
+        pq.push("a", 7);
+        pq.push("a", 3);
+        assert_eq!(pq.len(), 4);
+        assert_eq!(pq.distinct_len(), 2);
+        assert_eq!(pq.count(&"a"), 3);
+        assert_eq!(pq.pop(), Some(("a", 7)));
+        assert_eq!(pq.pop(), Some(("b", 5)));
+        assert_eq!(pq.count(&"b"), 0);
+        assert!(!pq.contains(&"b"));
+        assert_eq!(pq.pop(), Some(("a", 3)));
+        assert_eq!(pq.pop(), Some(("a", 1)));
+        assert_eq!(pq.pop(), None);
+        assert_eq!(pq.distinct_len(), 0);
+    }
+
+    #[test]
+    fn same_priority_twice() {
+        let mut pq = PriorityMultiQueue::new();
+        pq.push('x', 2);
+        pq.push('x', 2);
+        assert_eq!(pq.len(), 2);
+        assert_eq!(pq.pop(), Some(('x', 2)));
+        assert_eq!(pq.pop(), Some(('x', 2)));
+        assert!(pq.is_empty());
+    }
+
+    #[test]
+    fn remove_one() {
+        let mut pq = PriorityMultiQueue::new();
+        pq.push("a", 1);
+        pq.push("a", 9);
+        pq.push("b", 5);
+        assert_eq!(pq.remove_one(&"a"), Some(("a", 9)));
+        assert_eq!(pq.remove_one(&"c"), None);
+        assert_eq!(pq.count(&"a"), 1);
+        assert_eq!(pq.peek(), Some((&"b", &5)));
+    }
+
+    #[test]
+    fn remove_all() {
+        let mut pq = PriorityMultiQueue::new();
+        for p in 0..10 {
+            pq.push(p % 3, p);
+        }
+        let mut removed = pq.remove_all(&1);
+        removed.sort();
+        assert_eq!(removed, vec![(1, 1), (1, 4), (1, 7)]);
+        assert!(pq.remove_all(&1).is_empty());
+        assert_eq!(pq.len(), 7);
+        let sorted: Vec<_> = pq.into_sorted_iter().map(|(_, p)| p).collect();
+        assert_eq!(sorted, vec![9, 8, 6, 5, 3, 2, 0]);
+    }
+
+    #[test]
+    fn change_priority_of_instances() {
+        let mut pq = PriorityMultiQueue::new();
+        pq.push("reminder", 10);
+        pq.push("reminder", 20);
+        pq.push("reminder", 30);
+        pq.push("meeting", 25);
+
+        assert_eq!(
+            pq.change_priority_of_min_instance(&"reminder", 40),
+            Some(10)
+        );
+        assert_eq!(pq.peek(), Some((&"reminder", &40)));
+        assert_eq!(pq.change_priority_of_max_instance(&"reminder", 5), Some(40));
+        assert_eq!(pq.change_priority_of_max_instance(&"lunch", 5), None);
+
+        let mut priorities: Vec<_> = pq.priorities(&"reminder").copied().collect();
+        priorities.sort();
+        assert_eq!(priorities, vec![5, 20, 30]);
+        assert_eq!(pq.pop(), Some(("reminder", 30)));
+        assert_eq!(pq.pop(), Some(("meeting", 25)));
+    }
+
+    #[test]
+    fn from_iter_extend() {
+        let mut pq: PriorityMultiQueue<_, _> = vec![("a", 1), ("a", 2)].into_iter().collect();
+        pq.extend(vec![("b", 3), ("a", 4)]);
+        assert_eq!(pq.len(), 4);
+        assert_eq!(pq.count(&"a"), 3);
+        assert_eq!(pq.iter().filter(|(i, _)| **i == "b").count(), 1);
+        assert_eq!(pq.pop(), Some(("a", 4)));
+        assert_eq!(pq.pop(), Some(("b", 3)));
+    }
+}
+
+#[cfg(all(feature = "serde", test))]
+mod serde_tests_basics {
+    use priority_queue::PriorityMultiQueue;
+    use serde_test::{assert_tokens, Token};
+
+    #[test]
+    fn serde() {
+        let mut pq = PriorityMultiQueue::new();
+        pq.push("a", 1);
+        pq.push("a", 2);
+
+        assert_tokens(
+            &pq,
+            &[
+                Token::Seq { len: Some(2) },
+                Token::Tuple { len: 2 },
+                Token::BorrowedStr("a"),
+                Token::I32(1),
+                Token::TupleEnd,
+                Token::Tuple { len: 2 },
+                Token::BorrowedStr("a"),
+                Token::I32(2),
+                Token::TupleEnd,
+                Token::SeqEnd,
+            ],
+        );
+    }
+}
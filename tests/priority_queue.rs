@@ -470,6 +470,286 @@ mod pqueue_tests {
 
         assert_eq!(pq.pop(), Some(('b', 5)));
     }
+
+    #[test]
+    fn all_max() {
+        let mut pq = PriorityQueue::new();
+        assert!(pq.peek_all_max().is_empty());
+        assert!(pq.pop_all_max().is_empty());
+        for (i, p) in [3, 7, 1, 7, 5, 7, 2, 7].iter().enumerate() {
+            pq.push(i, *p);
+        }
+        let mut ties: Vec<_> = pq.peek_all_max().into_iter().map(|(i, _)| *i).collect();
+        ties.sort_unstable();
+        assert_eq!(ties, vec![1, 3, 5, 7]);
+
+        let mut ties = pq.pop_all_max();
+        ties.sort_unstable();
+        assert_eq!(ties, vec![(1, 7), (3, 7), (5, 7), (7, 7)]);
+        assert_eq!(pq.len(), 4);
+        assert_eq!(pq.peek_all_max(), vec![(&4, &5)]);
+        assert_eq!(pq.pop_all_max(), vec![(4, 5)]);
+    }
+
+    #[test]
+    fn std_conversions() {
+        use std::collections::{BTreeMap, BinaryHeap};
+
+        let heap: BinaryHeap<_> = vec![(3, 'a'), (7, 'b'), (5, 'c')].into();
+        let mut pq: PriorityQueue<_, _> = heap.into();
+        assert_eq!(pq.pop(), Some(('b', 7)));
+        let heap: BinaryHeap<_> = pq.into();
+        assert_eq!(heap.into_sorted_vec(), vec![(3, 'a'), (5, 'c')]);
+
+        let mut map = BTreeMap::new();
+        map.insert(1, vec!['x', 'y']);
+        map.insert(4, vec!['z', 'x']);
+        let mut pq: PriorityQueue<_, _> = map.into();
+        assert_eq!(pq.len(), 3);
+        assert_eq!(pq.get_priority(&'x'), Some(&4));
+        pq.push('w', 1);
+        let mut map: BTreeMap<_, _> = pq.into();
+        map.values_mut().for_each(|v| v.sort_unstable());
+        assert_eq!(map.get(&1), Some(&vec!['w', 'y']));
+        assert_eq!(map.get(&4), Some(&vec!['x', 'z']));
+    }
+
+    #[test]
+    fn index_map_conversions() {
+        use indexmap::IndexMap;
+        use std::collections::hash_map::RandomState;
+
+        let mut map = IndexMap::with_hasher(RandomState::new());
+        map.insert("a", 2);
+        map.insert("b", 9);
+        map.insert("c", 4);
+        let mut pq = PriorityQueue::from(map);
+        assert_eq!(pq.as_index_map().get("c"), Some(&4));
+        assert_eq!(pq.pop(), Some(("b", 9)));
+        assert_eq!(pq.peek(), Some((&"c", &4)));
+        let map = pq.into_index_map();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("a"), Some(&2));
+    }
+
+    #[test]
+    // `BuildHasher::hash_one` would require Rust 1.71
+    #[allow(clippy::manual_hash_one)]
+    fn by_hash() {
+        use std::collections::hash_map::RandomState;
+        use std::hash::{BuildHasher, Hash, Hasher};
+
+        let mut a: PriorityQueue<&str, i32> = PriorityQueue::with_hasher(RandomState::new());
+        let mut b = PriorityQueue::<&str, i32>::with_hasher(a.hasher().clone());
+        a.extend(vec![("x", 1), ("y", 2), ("z", 3)]);
+        b.extend(vec![("x", 10), ("y", 20)]);
+        let hash = |h: &RandomState| {
+            let mut hasher = h.build_hasher();
+            "x".hash(&mut hasher);
+            hasher.finish()
+        };
+        let hash = hash(a.hasher());
+
+        assert_eq!(a.get_by_hash(hash, |i| *i == "x"), Some((&"x", &1)));
+        assert_eq!(b.get_by_hash(hash, |i| *i == "x"), Some((&"x", &10)));
+        assert_eq!(a.get_by_hash(hash, |i| *i == "y"), None);
+
+        assert_eq!(a.change_priority_by_hash(hash, |i| *i == "x", 5), Some(1));
+        assert_eq!(a.change_priority_by_hash(hash, |i| *i == "w", 7), None);
+        assert_eq!(a.pop(), Some(("x", 5)));
+        assert_eq!(a.change_priority_by_hash(hash, |i| *i == "x", 7), None);
+
+        assert_eq!(b.remove_by_hash(hash, |i| *i == "x"), Some(("x", 10)));
+        assert_eq!(b.remove_by_hash(hash, |i| *i == "x"), None);
+        assert_eq!(b.len(), 1);
+        assert_eq!(b.pop(), Some(("y", 20)));
+    }
+
+    #[test]
+    fn replace_item() {
+        let mut pq = PriorityQueue::new();
+        pq.push(String::from("a"), 1);
+        pq.push(String::from("b"), 5);
+        pq.push(String::from("c"), 3);
+        assert_eq!(
+            pq.replace_item("a", String::from("z")),
+            Ok(String::from("a"))
+        );
+        assert_eq!(pq.get_priority("z"), Some(&1));
+        assert_eq!(pq.get_priority("a"), None);
+        assert_eq!(
+            pq.replace_item("a", String::from("y")),
+            Err(String::from("y"))
+        );
+        assert_eq!(pq.get_priority("y"), None);
+        assert_eq!(
+            pq.replace_item("z", String::from("b")),
+            Err(String::from("b"))
+        );
+        assert_eq!(pq.get_priority("z"), Some(&1));
+        assert_eq!(pq.get_priority("b"), Some(&5));
+        assert_eq!(
+            pq.replace_item("b", String::from("b")),
+            Ok(String::from("b"))
+        );
+        assert_eq!(pq.len(), 3);
+    }
+
+    #[test]
+    fn swap_priorities() {
+        let mut pq = PriorityQueue::new();
+        let mut seed: u64 = 5;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize
+        };
+        for i in 0..100 {
+            pq.push(i, next() % 1000);
+        }
+        assert!(!pq.swap_priorities(&0, &100));
+        for _ in 0..200 {
+            let (a, b) = (next() % 100, next() % 100);
+            let (pa, pb) = (*pq.get_priority(&a).unwrap(), *pq.get_priority(&b).unwrap());
+            assert!(pq.swap_priorities(&a, &b));
+            assert_eq!(pq.get_priority(&a), Some(&pb));
+            assert_eq!(pq.get_priority(&b), Some(&pa));
+        }
+        let mut last = usize::MAX;
+        while let Some((_, p)) = pq.pop() {
+            assert!(p <= last);
+            last = p;
+        }
+    }
+
+    #[test]
+    fn transfer() {
+        let mut a = PriorityQueue::new();
+        let mut b = PriorityQueue::new();
+        a.push("x", 1);
+        a.push("y", 2);
+        b.push("y", 7);
+        assert!(a.transfer(&"x", &mut b));
+        assert!(a.transfer(&"y", &mut b));
+        assert!(!a.transfer(&"z", &mut b));
+        assert!(a.is_empty());
+        assert_eq!(b.get_priority(&"x"), Some(&1));
+        assert_eq!(b.get_priority(&"y"), Some(&2));
+    }
+
+    #[test]
+    // `BuildHasher::hash_one` would require Rust 1.71
+    #[allow(clippy::manual_hash_one)]
+    fn shared_hasher() {
+        use std::collections::hash_map::RandomState;
+        use std::hash::{BuildHasher, Hash, Hasher};
+
+        let a: PriorityQueue<&str, i32> = PriorityQueue::with_hasher(RandomState::new());
+        let b = PriorityQueue::<&str, i32>::with_hasher(a.hasher().clone());
+        let hash = |h: &RandomState| {
+            let mut hasher = h.build_hasher();
+            "item".hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(a.hasher()), hash(b.hasher()));
+    }
+
+    #[test]
+    fn get_mut_rehash() {
+        let mut pq = PriorityQueue::new();
+        pq.push(String::from("a"), 1);
+        pq.push(String::from("b"), 5);
+        pq.push(String::from("c"), 3);
+        {
+            let mut item = pq.get_mut_rehash("a").unwrap();
+            assert_eq!(item.priority(), &1);
+            item.push('x');
+        }
+        assert_eq!(pq.get_priority("ax"), Some(&1));
+        assert_eq!(pq.get_priority("a"), None);
+        // changing the hash of an item does not move it in the heap
+        *pq.get_mut_rehash("b").unwrap() = String::from("z");
+        assert!(pq.get_mut_rehash("b").is_none());
+        assert_eq!(pq.pop(), Some((String::from("z"), 5)));
+        assert_eq!(pq.pop(), Some((String::from("c"), 3)));
+        assert_eq!(pq.pop(), Some((String::from("ax"), 1)));
+    }
+
+    #[test]
+    fn get_mut_rehash_duplicate() {
+        let mut pq = PriorityQueue::new();
+        pq.push(String::from("a"), 1);
+        pq.push(String::from("b"), 2);
+        pq.push(String::from("c"), 3);
+        pq.push(String::from("d"), 4);
+        let mut item = pq.get_mut_rehash("a").unwrap();
+        *item = String::from("b");
+        assert_eq!(item.commit(), Err(String::from("b")));
+        assert_eq!(pq.get_priority("b"), Some(&2));
+        assert_eq!(pq.len(), 3);
+
+        // dropping the guard removes the duplicate as well
+        *pq.get_mut_rehash("d").unwrap() = String::from("c");
+        assert_eq!(pq.get_priority("c"), Some(&3));
+        assert_eq!(pq.get_priority("d"), None);
+        assert_eq!(pq.len(), 2);
+        assert_eq!(pq.pop(), Some((String::from("c"), 3)));
+        assert_eq!(pq.pop(), Some((String::from("b"), 2)));
+        assert_eq!(pq.pop(), None);
+    }
+
+    #[test]
+    fn get_mut_rehash_commit() {
+        let mut pq = PriorityQueue::new();
+        pq.push(String::from("a"), 1);
+        pq.push(String::from("b"), 2);
+        let mut item = pq.get_mut_rehash("a").unwrap();
+        item.push('a');
+        assert_eq!(item.commit(), Ok(()));
+        assert_eq!(pq.get_priority("aa"), Some(&1));
+        assert_eq!(pq.get_mut_rehash("b").unwrap().commit(), Ok(()));
+        assert_eq!(pq.get_priority("b"), Some(&2));
+    }
+
+    #[cfg(feature = "debug-hash-check")]
+    #[test]
+    #[should_panic(expected = "changed its hash")]
+    fn debug_hash_check_get_mut() {
+        let mut pq = PriorityQueue::new();
+        pq.push(String::from("a"), 1);
+        pq.push(String::from("b"), 2);
+        pq.get_mut("a").unwrap().0.push('x');
+        pq.get_priority("b");
+    }
+
+    #[cfg(feature = "debug-hash-check")]
+    #[test]
+    #[should_panic(expected = "changed its hash")]
+    fn debug_hash_check_iter_mut() {
+        let mut pq = PriorityQueue::new();
+        pq.push(String::from("a"), 1);
+        pq.push(String::from("b"), 2);
+        for (item, _) in pq.iter_mut() {
+            item.push('x');
+        }
+        pq.push(String::from("c"), 3);
+    }
+
+    #[cfg(feature = "debug-hash-check")]
+    #[test]
+    fn debug_hash_check_harmless_changes() {
+        let mut pq = PriorityQueue::new();
+        pq.push(String::from("a"), 1);
+        pq.push(String::from("b"), 2);
+        for (_, priority) in pq.iter_mut() {
+            *priority += 1;
+        }
+        pq.peek_mut();
+        assert_eq!(pq.pop(), Some((String::from("b"), 3)));
+        pq.get_mut("a");
+        assert_eq!(pq.get_priority("a"), Some(&2));
+    }
 }
 
 #[cfg(all(feature = "serde", test))]
@@ -0,0 +1,144 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+#[cfg(test)]
+mod ranked_pqueue_tests {
+    pub use priority_queue::{PriorityQueue, RankedPriorityQueue};
+
+    #[test]
+    fn push_pop() {
+        let mut pq = RankedPriorityQueue::new();
+        assert_eq!(pq.pop(), None);
+        pq.push("a", 1);
+        pq.push("b", 3);
+        pq.push("c", 2);
+        assert_eq!(pq.push("a", 4), Some(1));
+        assert_eq!(pq.len(), 3);
+        assert_eq!(pq.pop(), Some(("a", 4)));
+        assert_eq!(pq.pop(), Some(("b", 3)));
+        assert_eq!(pq.pop(), Some(("c", 2)));
+        assert!(pq.is_empty());
+    }
+
+    #[test]
+    fn rank_and_nth() {
+        let mut pq: RankedPriorityQueue<_, _> = vec![('a', 10), ('b', 20), ('c', 20), ('d', 5)]
+            .into_iter()
+            .collect();
+        assert_eq!(pq.rank(&'b'), Some(0));
+        assert_eq!(pq.rank(&'c'), Some(0));
+        assert_eq!(pq.rank(&'a'), Some(2));
+        assert_eq!(pq.rank(&'d'), Some(3));
+        assert_eq!(pq.rank(&'e'), None);
+        assert_eq!(pq.nth(2), Some((&'a', &10)));
+        assert_eq!(pq.nth(3), Some((&'d', &5)));
+        assert_eq!(pq.nth(4), None);
+
+        pq.change_priority(&'d', 30);
+        assert_eq!(pq.rank(&'d'), Some(0));
+        assert_eq!(pq.nth(0), Some((&'d', &30)));
+        pq.change_priority_by(&'a', |p| *p = 0);
+        assert_eq!(pq.rank(&'a'), Some(3));
+        pq.remove(&'b');
+        assert_eq!(pq.nth(1), Some((&'c', &20)));
+        assert_eq!(pq.rank(&'a'), Some(2));
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn counts() {
+        let pq: RankedPriorityQueue<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+        assert_eq!(pq.count_above(&45), 5);
+        assert_eq!(pq.count_above(&90), 0);
+        assert_eq!(pq.count_below(&0), 0);
+        assert_eq!(pq.count_below(&45), 5);
+        assert_eq!(pq.count_in_range(20..50), 3);
+        assert_eq!(pq.count_in_range(20..=50), 4);
+        assert_eq!(pq.count_in_range(..), 10);
+        assert_eq!(pq.count_in_range(70..), 3);
+        assert_eq!(pq.count_in_range(50..20), 0);
+    }
+
+    #[test]
+    fn conversions() {
+        let mut pq = PriorityQueue::new();
+        pq.push(1, 5);
+        pq.push(2, 7);
+        let mut rpq = RankedPriorityQueue::from(pq);
+        assert_eq!(rpq.rank(&1), Some(1));
+        rpq.extend(vec![(3, 6)]);
+        assert_eq!(rpq.rank(&1), Some(2));
+        let pq: PriorityQueue<_, _> = rpq.into();
+        assert_eq!(pq.into_sorted_vec(), vec![2, 3, 1]);
+    }
+
+    #[test]
+    fn stays_consistent() {
+        // pseudo random sequence of operations checked against a naive model
+        let mut seed: u64 = 7;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as i64
+        };
+        let mut pq = RankedPriorityQueue::new();
+        let mut model = std::collections::HashMap::new();
+        for _ in 0..2000 {
+            let item = next() % 50;
+            match next() % 5 {
+                0 | 1 => {
+                    let p = next() % 100;
+                    assert_eq!(pq.push(item, p), model.insert(item, p));
+                }
+                2 => {
+                    assert_eq!(pq.remove(&item), model.remove_entry(&item));
+                }
+                3 => {
+                    let p = next() % 100;
+                    assert_eq!(
+                        pq.change_priority(&item, p),
+                        model.get_mut(&item).map(|old| std::mem::replace(old, p))
+                    );
+                }
+                _ => {
+                    if let Some((i, p)) = pq.pop() {
+                        assert_eq!(model.values().max(), Some(&p));
+                        model.remove(&i);
+                    }
+                }
+            }
+            assert_eq!(pq.len(), model.len());
+            let mut sorted: Vec<_> = model.values().copied().collect();
+            sorted.sort_unstable_by(|a, b| b.cmp(a));
+            for (k, p) in sorted.iter().enumerate() {
+                assert_eq!(pq.nth(k).map(|(_, p)| *p), Some(*p));
+            }
+            for (i, p) in &model {
+                assert_eq!(pq.rank(i), Some(sorted.iter().filter(|q| *q > p).count()));
+            }
+            let p = next() % 100;
+            assert_eq!(
+                pq.count_in_range(p..p + 20),
+                sorted.iter().filter(|q| (p..p + 20).contains(q)).count()
+            );
+        }
+    }
+}
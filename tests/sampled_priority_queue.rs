@@ -0,0 +1,168 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+#[cfg(test)]
+mod sampled_pqueue_tests {
+    pub use priority_queue::SampledPriorityQueue;
+    use std::collections::HashMap;
+
+    // the item whose range of [0, 1) contains `value`, computed naively
+    fn naive_sample(model: &[(u32, u32)], value: f64) -> Option<u32> {
+        let total: f64 = model.iter().map(|(_, p)| f64::from(*p)).sum();
+        let mut target = value * total;
+        for (i, p) in model {
+            if target < f64::from(*p) {
+                return Some(*i);
+            }
+            target -= f64::from(*p);
+        }
+        None
+    }
+
+    #[test]
+    fn sample_ranges() {
+        let pq: SampledPriorityQueue<_, _> = vec![("a", 1u8), ("b", 0), ("c", 2), ("d", 1)]
+            .into_iter()
+            .collect();
+        assert_eq!(pq.total(), 4.0);
+        assert_eq!(pq.max(), Some(&2));
+        assert_eq!(pq.peek(), Some((&"c", &2)));
+        let sampled: Vec<_> = [0.0, 0.24, 0.25, 0.5, 0.74, 0.75, 0.99]
+            .iter()
+            .map(|v| *pq.sample(*v).unwrap().0)
+            .collect();
+        assert_eq!(sampled, vec!["a", "a", "c", "c", "c", "d", "d"]);
+    }
+
+    #[test]
+    fn empty_and_zero_weights() {
+        let mut pq = SampledPriorityQueue::new();
+        assert_eq!(pq.sample(0.5), None);
+        assert_eq!(pq.max(), None);
+        pq.push(1, 0u16);
+        pq.push(2, 0u16);
+        assert_eq!(pq.sample(0.5), None);
+        pq.change_priority(&2, 7);
+        assert_eq!(pq.sample(0.0), Some((&2, &7)));
+        assert_eq!(pq.sample(0.999), Some((&2, &7)));
+        pq.clear();
+        assert!(pq.is_empty());
+        assert_eq!(pq.total(), 0.0);
+        assert_eq!(pq.sample(0.1), None);
+    }
+
+    #[test]
+    fn push_pop_remove() {
+        let mut pq = SampledPriorityQueue::new();
+        for i in 0..10u32 {
+            pq.push(i, i);
+        }
+        assert_eq!(pq.total(), 45.0);
+        assert_eq!(pq.push(3, 10), Some(3));
+        assert_eq!(pq.total(), 52.0);
+        assert_eq!(pq.pop(), Some((3, 10)));
+        assert_eq!(pq.total(), 42.0);
+        assert_eq!(pq.remove(&0), Some((0, 0)));
+        assert_eq!(pq.remove(&0), None);
+        assert_eq!(pq.remove(&5), Some((5, 5)));
+        assert_eq!(pq.total(), 37.0);
+        assert_eq!(pq.change_priority(&42, 1), None);
+        assert_eq!(pq.get_priority(&9), Some(&9));
+        assert!(pq.contains(&9));
+        assert_eq!(pq.len(), 7);
+        assert_eq!(pq.iter().map(|(_, p)| *p).sum::<u32>(), 37);
+    }
+
+    #[test]
+    fn matches_naive_model() {
+        let mut seed: u64 = 11;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as u32
+        };
+        let mut pq = SampledPriorityQueue::new();
+        let mut model: HashMap<u32, u32> = HashMap::new();
+        for _ in 0..3000 {
+            let item = next() % 40;
+            match next() % 6 {
+                0 | 1 => {
+                    let p = next() % 100;
+                    assert_eq!(pq.push(item, p), model.insert(item, p));
+                }
+                2 => {
+                    let p = next() % 100;
+                    let expected = model.get_mut(&item).map(|old| std::mem::replace(old, p));
+                    assert_eq!(pq.change_priority(&item, p), expected);
+                }
+                3 => {
+                    assert_eq!(pq.remove(&item).map(|(_, p)| p), model.remove(&item));
+                }
+                4 => {
+                    if let Some((i, p)) = pq.pop() {
+                        assert_eq!(Some(p), model.values().max().copied());
+                        assert_eq!(model.remove(&i), Some(p));
+                    } else {
+                        assert!(model.is_empty());
+                    }
+                }
+                _ => {
+                    let value = f64::from(next() % 1000) / 1000.0;
+                    // the order of the ranges is the order of the iteration
+                    let order: Vec<_> = pq.iter().map(|(i, p)| (*i, *p)).collect();
+                    assert_eq!(
+                        pq.sample(value).map(|(i, _)| *i),
+                        naive_sample(&order, value)
+                    );
+                }
+            }
+            assert_eq!(
+                pq.total(),
+                model.values().map(|p| f64::from(*p)).sum::<f64>()
+            );
+            assert_eq!(pq.max(), model.values().max());
+        }
+    }
+
+    #[test]
+    fn frequencies() {
+        let mut pq = SampledPriorityQueue::new();
+        pq.push('x', 1u8);
+        pq.push('y', 2u8);
+        pq.push('z', 5u8);
+        let mut counts = HashMap::new();
+        for k in 0..8000 {
+            *counts
+                .entry(*pq.sample(f64::from(k) / 8000.0).unwrap().0)
+                .or_insert(0) += 1;
+        }
+        assert_eq!(counts[&'x'], 1000);
+        assert_eq!(counts[&'y'], 2000);
+        assert_eq!(counts[&'z'], 5000);
+    }
+
+    #[test]
+    #[should_panic]
+    fn sample_out_of_range() {
+        let mut pq = SampledPriorityQueue::new();
+        pq.push(1, 1u8);
+        pq.sample(1.0);
+    }
+}
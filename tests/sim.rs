@@ -0,0 +1,165 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+#[cfg(test)]
+mod sim_tests {
+    pub use priority_queue::sim::Scheduler;
+
+    #[test]
+    fn fifo_ties() {
+        let mut sim = Scheduler::new();
+        for (i, t) in [3, 1, 3, 2, 1, 3].iter().enumerate() {
+            sim.schedule(*t, i);
+        }
+        let mut order = vec![];
+        assert_eq!(sim.run(|_, t, e| order.push((t, e))), 6);
+        assert_eq!(order, vec![(1, 1), (1, 4), (2, 3), (3, 0), (3, 2), (3, 5)]);
+        assert_eq!(sim.now(), Some(&3));
+        assert!(sim.is_empty());
+    }
+
+    #[test]
+    fn cancel_and_reschedule() {
+        let mut sim = Scheduler::new();
+        let a = sim.schedule(10, "a");
+        let b = sim.schedule(20, "b");
+        let c = sim.schedule(20, "c");
+        assert_eq!(sim.len(), 3);
+        assert_eq!(sim.get(a), Some((&10, &"a")));
+
+        assert_eq!(sim.cancel(a), Some((10, "a")));
+        assert_eq!(sim.cancel(a), None);
+        assert_eq!(sim.reschedule(a, 5), None);
+
+        // b moves after c, that was already scheduled at 20
+        assert_eq!(sim.reschedule(b, 20), Some(20));
+        assert_eq!(sim.peek().map(|(id, _, _)| id), Some(c));
+        assert_eq!(sim.reschedule(b, 15), Some(20));
+        assert_eq!(sim.peek(), Some((b, &15, &"b")));
+
+        assert_eq!(sim.pop(), Some((15, "b")));
+        assert_eq!(sim.pop(), Some((20, "c")));
+        assert_eq!(sim.pop(), None);
+    }
+
+    #[test]
+    fn run_until_advances_time() {
+        let mut sim = Scheduler::new();
+        sim.schedule(1, ());
+        sim.schedule(5, ());
+        assert_eq!(sim.now(), None);
+        assert_eq!(sim.run_until(3, |_, _, _| {}), 1);
+        assert_eq!(sim.now(), Some(&3));
+        assert_eq!(sim.len(), 1);
+        // events at exactly `until` are dispatched
+        assert_eq!(sim.run_until(5, |_, _, _| {}), 1);
+        assert_eq!(sim.run_until(4, |_, _, _| {}), 0);
+        assert_eq!(sim.now(), Some(&5));
+    }
+
+    #[test]
+    fn handler_schedules_and_cancels() {
+        // a timeout is cancelled whenever a heartbeat arrives in time
+        #[derive(Debug, PartialEq)]
+        enum Event {
+            Heartbeat,
+            Timeout,
+        }
+        let mut sim = Scheduler::new();
+        let mut timeout = sim.schedule(10, Event::Timeout);
+        for t in &[4, 8, 12, 30] {
+            sim.schedule(*t, Event::Heartbeat);
+        }
+        let mut timeouts = vec![];
+        sim.run_until(100, |sim, now, event| match event {
+            Event::Heartbeat => {
+                sim.cancel(timeout);
+                timeout = sim.schedule(now + 10, Event::Timeout);
+            }
+            Event::Timeout => timeouts.push(now),
+        });
+        assert_eq!(timeouts, vec![22, 40]);
+    }
+
+    #[test]
+    fn matches_sorted_model() {
+        let mut seed: u64 = 17;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            seed >> 33
+        };
+        let mut sim = Scheduler::new();
+        // (time, sequence, id, event), the sequence gives the FIFO order
+        let mut model = vec![];
+        let mut ids = vec![];
+        let mut seq = 0;
+        for step in 0..2000u64 {
+            seq += 1;
+            let now = *sim.now().unwrap_or(&0);
+            match next() % 4 {
+                0 | 1 => {
+                    let at = now + next() % 20;
+                    let id = sim.schedule(at, step);
+                    ids.push(id);
+                    model.push((at, seq, id, step));
+                }
+                2 if !ids.is_empty() => {
+                    let id = ids[(next() % ids.len() as u64) as usize];
+                    let at = now + next() % 20;
+                    let pos = model.iter().position(|m| m.2 == id);
+                    let expected = pos.map(|p| std::mem::replace(&mut model[p].0, at));
+                    if let Some(p) = pos {
+                        model[p].1 = seq;
+                    }
+                    assert_eq!(sim.reschedule(id, at), expected);
+                }
+                3 if !ids.is_empty() => {
+                    let id = ids[(next() % ids.len() as u64) as usize];
+                    let pos = model.iter().position(|m| m.2 == id);
+                    let expected = pos.map(|p| {
+                        let m = model.remove(p);
+                        (m.0, m.3)
+                    });
+                    assert_eq!(sim.cancel(id), expected);
+                }
+                _ => {
+                    model.sort();
+                    let expected = if model.is_empty() {
+                        None
+                    } else {
+                        let m = model.remove(0);
+                        Some((m.0, m.3))
+                    };
+                    assert_eq!(sim.pop(), expected);
+                }
+            }
+            assert_eq!(sim.len(), model.len());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn schedule_in_the_past() {
+        let mut sim = Scheduler::new();
+        sim.run_until(10, |_, _, _: ()| {});
+        sim.schedule(9, ());
+    }
+}
@@ -0,0 +1,103 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+#[cfg(test)]
+mod sliding_window_queue_tests {
+    pub use priority_queue::SlidingWindowQueue;
+
+    #[test]
+    fn rolling_extremes() {
+        let values = [4, 8, 1, 3, 9, 2, 2, 7];
+        let mut wq = SlidingWindowQueue::new(3usize);
+        let mut maxs = Vec::new();
+        let mut mins = Vec::new();
+        for (i, v) in values.iter().enumerate() {
+            wq.advance_to(i);
+            wq.push(i, *v, i);
+            maxs.push(*wq.peek_max().unwrap().1);
+            mins.push(*wq.peek_min().unwrap().1);
+            assert!(wq.len() <= 3);
+        }
+        assert_eq!(maxs, vec![4, 8, 8, 8, 9, 9, 9, 7]);
+        assert_eq!(mins, vec![4, 4, 1, 1, 1, 2, 2, 2]);
+    }
+
+    #[test]
+    fn advance_returns_evicted_in_order() {
+        let mut wq = SlidingWindowQueue::new(10u64);
+        wq.push("c", 1, 5);
+        wq.push("a", 3, 0);
+        wq.push("b", 2, 2);
+        assert_eq!(wq.oldest(), Some((&"a", &0)));
+        assert_eq!(wq.advance_to(9), vec![]);
+        assert_eq!(wq.advance_to(12), vec![("a", 3), ("b", 2)]);
+        assert_eq!(wq.oldest(), Some((&"c", &5)));
+        assert_eq!(wq.advance_to(100), vec![("c", 1)]);
+        assert!(wq.is_empty());
+    }
+
+    #[test]
+    fn changes_inside_the_window() {
+        let mut wq = SlidingWindowQueue::new(5u32);
+        wq.push('a', 1, 0);
+        wq.push('b', 2, 1);
+        assert_eq!(wq.change_priority(&'a', 10), Some(1));
+        assert_eq!(wq.peek_max(), Some((&'a', &10)));
+        wq.change_priority_by(&'b', |p| *p += 20);
+        assert_eq!(wq.peek_max(), Some((&'b', &22)));
+        // pushing again refreshes the position
+        assert_eq!(wq.push('a', 0, 4), Some(10));
+        assert_eq!(wq.get_position(&'a'), Some(&4));
+        assert_eq!(wq.advance_to(6), vec![('b', 22)]);
+        assert_eq!(wq.get_priority(&'a'), Some(&0));
+        assert_eq!(wq.remove(&'a'), Some(('a', 0)));
+        assert_eq!(wq.oldest(), None);
+    }
+
+    #[test]
+    fn pops_forget_positions() {
+        let mut wq = SlidingWindowQueue::new(2i32);
+        wq.push(1, 1, 0);
+        wq.push(2, 2, 0);
+        wq.push(3, 3, 1);
+        assert_eq!(wq.pop_max(), Some((3, 3)));
+        assert_eq!(wq.pop_min(), Some((1, 1)));
+        assert_eq!(wq.oldest(), Some((&2, &0)));
+        assert_eq!(wq.iter().count(), 1);
+        wq.clear();
+        assert_eq!(wq.advance_to(10), vec![]);
+    }
+
+    #[test]
+    fn timestamps() {
+        use std::time::{Duration, Instant};
+
+        let start = Instant::now();
+        let mut wq = SlidingWindowQueue::new(Duration::from_secs(60));
+        wq.push("old", 1, start);
+        wq.push("new", 2, start + Duration::from_secs(30));
+        assert_eq!(wq.width(), &Duration::from_secs(60));
+        assert_eq!(
+            wq.advance_to(start + Duration::from_secs(60)),
+            vec![("old", 1)]
+        );
+        assert_eq!(wq.peek_min(), Some((&"new", &2)));
+    }
+}
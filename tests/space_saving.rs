@@ -0,0 +1,165 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+#[cfg(test)]
+mod space_saving_tests {
+    pub use priority_queue::space_saving::Estimate;
+    pub use priority_queue::SpaceSaving;
+    use std::collections::HashMap;
+
+    // a skewed pseudo random stream: small values are much more frequent
+    fn stream(seed: u64, len: usize) -> Vec<u64> {
+        let mut seed = seed;
+        (0..len)
+            .map(|_| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let r = (seed >> 33) % 1000;
+                r * r % 997 / (r % 13 + 1)
+            })
+            .collect()
+    }
+
+    fn exact(items: &[u64]) -> HashMap<u64, usize> {
+        let mut counts = HashMap::new();
+        for i in items {
+            *counts.entry(*i).or_default() += 1;
+        }
+        counts
+    }
+
+    fn check_bounds(sketch: &SpaceSaving<u64>, counts: &HashMap<u64, usize>) {
+        let total: usize = counts.values().sum();
+        assert_eq!(sketch.total(), total);
+        assert!(sketch.len() <= sketch.capacity());
+        for (item, estimate) in sketch.iter() {
+            let count = counts[item];
+            assert!(estimate.lower_bound() <= count, "{:?} {}", estimate, count);
+            assert!(count <= estimate.upper_bound(), "{:?} {}", estimate, count);
+            assert!(estimate.error <= sketch.error_bound());
+        }
+        for (item, count) in counts {
+            assert!(sketch.upper_bound(item) >= *count);
+            if *count > sketch.error_bound() {
+                assert!(sketch.get(item).is_some(), "{} is frequent", item);
+            }
+        }
+    }
+
+    #[test]
+    fn exact_below_capacity() {
+        let mut sketch = SpaceSaving::new(10);
+        sketch.extend("mississippi".chars());
+        assert_eq!(sketch.len(), 4);
+        assert_eq!(sketch.get(&'s'), Some(&Estimate { count: 4, error: 0 }));
+        assert_eq!(sketch.get(&'x'), None);
+        assert_eq!(sketch.upper_bound(&'x'), 0);
+        let top: Vec<_> = sketch.top_k(2).into_iter().map(|(_, e)| e.count).collect();
+        assert_eq!(top, vec![4, 4]);
+        assert_eq!(sketch.top_k(0), vec![]);
+        assert_eq!(sketch.top_k(9).len(), 4);
+        sketch.clear();
+        assert!(sketch.is_empty());
+        assert_eq!(sketch.total(), 0);
+    }
+
+    #[test]
+    fn eviction() {
+        let mut sketch = SpaceSaving::new(2);
+        assert_eq!(sketch.add("a", 5), None);
+        assert_eq!(sketch.add("b", 2), None);
+        assert_eq!(
+            sketch.add("c", 1),
+            Some(("b", Estimate { count: 2, error: 0 }))
+        );
+        assert_eq!(sketch.get("c"), Some(&Estimate { count: 3, error: 2 }));
+        assert_eq!(sketch.upper_bound("b"), 3);
+        sketch.insert("a");
+        assert_eq!(sketch.get("a"), Some(&Estimate { count: 6, error: 0 }));
+        assert_eq!(sketch.total(), 9);
+    }
+
+    #[test]
+    fn estimate_order() {
+        let reliable = Estimate { count: 4, error: 0 };
+        let unreliable = Estimate { count: 4, error: 3 };
+        assert!(reliable > unreliable);
+        assert!(Estimate { count: 5, error: 5 } > reliable);
+        assert_eq!(unreliable.lower_bound(), 1);
+        assert_eq!(unreliable.upper_bound(), 4);
+    }
+
+    #[test]
+    fn bounds_hold_on_skewed_stream() {
+        let items = stream(1, 5000);
+        let counts = exact(&items);
+        for &capacity in &[1, 5, 20, 100] {
+            let mut sketch = SpaceSaving::new(capacity);
+            sketch.extend(items.iter().copied());
+            check_bounds(&sketch, &counts);
+        }
+    }
+
+    #[test]
+    fn top_k_finds_heavy_hitters() {
+        let mut items = stream(3, 3000);
+        items.extend(vec![12345; 600]);
+        items.extend(vec![54321; 400]);
+        let mut sketch = SpaceSaving::new(30);
+        for i in items {
+            sketch.insert(i);
+        }
+        let top: Vec<_> = sketch.top_k(2).into_iter().map(|(i, _)| *i).collect();
+        assert!(top.contains(&12345));
+        assert!(top.contains(&54321));
+    }
+
+    #[test]
+    fn merge() {
+        let first = stream(5, 3000);
+        let second = stream(6, 2000);
+        let mut a = SpaceSaving::new(25);
+        a.extend(first.iter().copied());
+        let mut b = SpaceSaving::new(25);
+        b.extend(second.iter().copied());
+        a.merge(&b);
+
+        let all: Vec<_> = first.into_iter().chain(second).collect();
+        check_bounds(&a, &exact(&all));
+        assert_eq!(a.len(), 25);
+
+        // merging into a sketch that is not full is exact for its items
+        let mut c = SpaceSaving::new(25);
+        c.add(7, 3);
+        let mut d = SpaceSaving::new(25);
+        d.add(7, 2);
+        d.add(8, 1);
+        c.merge(&d);
+        assert_eq!(c.get(&7), Some(&Estimate { count: 5, error: 0 }));
+        assert_eq!(c.get(&8), Some(&Estimate { count: 1, error: 0 }));
+        assert_eq!(c.total(), 6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_capacity() {
+        SpaceSaving::<u8>::new(0);
+    }
+}
@@ -0,0 +1,150 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+#[cfg(test)]
+mod tagged_pqueue_tests {
+    pub use priority_queue::TaggedPriorityQueue;
+
+    #[test]
+    fn push_pop() {
+        let mut pq = TaggedPriorityQueue::new();
+        pq.push("a", 1, vec!["x"]);
+        pq.push("b", 3, vec!["x", "y"]);
+        pq.push("c", 2, vec![]);
+        assert_eq!(pq.len(), 3);
+        assert_eq!(pq.count_tag(&"x"), 2);
+        assert_eq!(pq.pop(), Some(("b", 3)));
+        assert_eq!(pq.count_tag(&"x"), 1);
+        assert_eq!(pq.count_tag(&"y"), 0);
+        assert_eq!(pq.pop(), Some(("c", 2)));
+        assert_eq!(pq.pop(), Some(("a", 1)));
+        assert_eq!(pq.count_tag(&"x"), 0);
+        assert!(pq.is_empty());
+    }
+
+    #[test]
+    fn push_replaces_tags() {
+        let mut pq = TaggedPriorityQueue::new();
+        pq.push("a", 1, vec!["x", "x", "y"]);
+        assert_eq!(pq.tags(&"a"), Some(&["x", "y"][..]));
+        assert_eq!(pq.push("a", 5, vec!["z"]), Some(1));
+        assert_eq!(pq.tags(&"a"), Some(&["z"][..]));
+        assert_eq!(pq.count_tag(&"x"), 0);
+        assert_eq!(pq.count_tag(&"z"), 1);
+        assert_eq!(pq.get_priority(&"a"), Some(&5));
+    }
+
+    #[test]
+    fn add_and_remove_item_tags() {
+        let mut pq = TaggedPriorityQueue::new();
+        pq.push(1, 1, vec!['a']);
+        assert!(pq.add_tag(&1, 'b'));
+        assert!(!pq.add_tag(&1, 'b'));
+        assert!(!pq.add_tag(&2, 'b'));
+        assert_eq!(pq.count_tag(&'b'), 1);
+        assert!(pq.remove_item_tag(&1, &'a'));
+        assert!(!pq.remove_item_tag(&1, &'a'));
+        assert_eq!(pq.count_tag(&'a'), 0);
+        assert_eq!(pq.tags(&1), Some(&['b'][..]));
+    }
+
+    #[test]
+    fn iter_tag() {
+        let mut pq = TaggedPriorityQueue::new();
+        for i in 0..10 {
+            pq.push(i, i * 10, vec![i % 2 == 0]);
+        }
+        let mut even: Vec<_> = pq.iter_tag(&true).map(|(i, p)| (*i, *p)).collect();
+        even.sort();
+        assert_eq!(even, vec![(0, 0), (2, 20), (4, 40), (6, 60), (8, 80)]);
+        assert_eq!(pq.iter_tag(&false).count(), 5);
+        assert_eq!(pq.iter().count(), 10);
+    }
+
+    fn check_order<T>(pq: &mut TaggedPriorityQueue<usize, T, usize>)
+    where
+        T: std::hash::Hash + Eq,
+    {
+        let mut last = usize::MAX;
+        while let Some((_, p)) = pq.pop() {
+            assert!(p <= last);
+            last = p;
+        }
+    }
+
+    #[test]
+    fn remove_tag_few_and_many() {
+        for &modulo in &[2usize, 50] {
+            let mut pq = TaggedPriorityQueue::new();
+            for i in 0..200 {
+                let mut tags = vec![];
+                if i % modulo == 0 {
+                    tags.push("multiple");
+                }
+                if i % 3 == 0 {
+                    tags.push("three");
+                }
+                pq.push(i, (i * 37) % 101, tags);
+            }
+            let removed = pq.remove_tag(&"multiple");
+            assert_eq!(removed.len(), 200 / modulo);
+            assert!(removed.iter().all(|(i, _)| i % modulo == 0));
+            assert_eq!(pq.len(), 200 - 200 / modulo);
+            assert!(pq.remove_tag(&"multiple").is_empty());
+            // the items removed do not linger in the other tags
+            assert_eq!(
+                pq.count_tag(&"three"),
+                (0..200).filter(|i| i % 3 == 0 && i % modulo != 0).count()
+            );
+            check_order(&mut pq);
+        }
+    }
+
+    #[test]
+    fn change_priority_tag_few_and_many() {
+        for &modulo in &[2usize, 50] {
+            let mut pq = TaggedPriorityQueue::new();
+            for i in 0..200 {
+                pq.push(i, i, vec![i % modulo == 0]);
+            }
+            pq.change_priority_tag(&true, |p| *p += 1000);
+            assert_eq!(
+                pq.peek(),
+                Some((&(200 / modulo * modulo - modulo), &(1200 - modulo)))
+            );
+            assert_eq!(pq.change_priority(&1, 5000), Some(1));
+            assert_eq!(pq.peek(), Some((&1, &5000)));
+            check_order(&mut pq);
+        }
+    }
+
+    #[test]
+    fn remove() {
+        let mut pq = TaggedPriorityQueue::new();
+        pq.push("a", 1, vec![1, 2]);
+        pq.push("b", 2, vec![2]);
+        assert_eq!(pq.remove(&"a"), Some(("a", 1)));
+        assert_eq!(pq.remove(&"a"), None);
+        assert_eq!(pq.count_tag(&1), 0);
+        assert_eq!(pq.count_tag(&2), 1);
+        pq.clear();
+        assert_eq!(pq.count_tag(&2), 0);
+    }
+}
@@ -0,0 +1,132 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+#[cfg(test)]
+mod traits_tests {
+    pub use priority_queue::{
+        DoubleEndedPriorityQueue, DoublePriorityQueue, PriorityQueue, PriorityQueueLike,
+    };
+
+    fn exercise<Q: PriorityQueueLike<char, i32>>(mut queue: Q) {
+        assert!(queue.is_empty());
+        assert_eq!(queue.push('a', 3), None);
+        assert_eq!(queue.push('b', 7), None);
+        assert_eq!(queue.push('c', 5), None);
+        assert_eq!(queue.push('a', 4), Some(3));
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.peek(), Some((&'b', &7)));
+        assert_eq!(queue.change_priority(&'c', 9), Some(5));
+        assert_eq!(queue.get_priority(&'c'), Some(&9));
+        assert_eq!(queue.iter().map(|(_, p)| *p).sum::<i32>(), 20);
+        assert_eq!(queue.remove(&'b'), Some(('b', 7)));
+        assert_eq!(queue.pop(), Some(('c', 9)));
+        assert_eq!(queue.pop(), Some(('a', 4)));
+        assert_eq!(queue.pop(), None);
+    }
+
+    fn spread<Q: DoubleEndedPriorityQueue<char, i32>>(queue: &Q) -> Option<i32> {
+        Some(queue.peek_max()?.1 - queue.peek_min()?.1)
+    }
+
+    #[test]
+    fn common_operations() {
+        exercise(PriorityQueue::new());
+        exercise(DoublePriorityQueue::new());
+    }
+
+    #[test]
+    fn double_ended() {
+        let mut queue: DoublePriorityQueue<_, _> =
+            vec![('a', 3), ('b', 10), ('c', 1)].into_iter().collect();
+        assert_eq!(spread(&queue), Some(9));
+        assert_eq!(
+            DoubleEndedPriorityQueue::pop_min(&mut queue),
+            Some(('c', 1))
+        );
+        assert_eq!(
+            DoubleEndedPriorityQueue::pop_max(&mut queue),
+            Some(('b', 10))
+        );
+        assert_eq!(spread(&queue), Some(0));
+    }
+
+    /// A wrapper counting the pushes, as an example of a user defined queue
+    struct Counting {
+        queue: PriorityQueue<char, i32>,
+        pushes: usize,
+    }
+
+    impl PriorityQueueLike<char, i32> for Counting {
+        type Iter<'a> = priority_queue::core_iterators::Iter<'a, char, i32>;
+
+        fn push(&mut self, item: char, priority: i32) -> Option<i32> {
+            self.pushes += 1;
+            self.queue.push(item, priority)
+        }
+        fn pop(&mut self) -> Option<(char, i32)> {
+            self.queue.pop()
+        }
+        fn peek(&self) -> Option<(&char, &i32)> {
+            self.queue.peek()
+        }
+        fn change_priority<Q>(&mut self, item: &Q, new_priority: i32) -> Option<i32>
+        where
+            char: std::borrow::Borrow<Q>,
+            Q: ?Sized + Eq + std::hash::Hash,
+        {
+            self.queue.change_priority(item, new_priority)
+        }
+        fn remove<Q>(&mut self, item: &Q) -> Option<(char, i32)>
+        where
+            char: std::borrow::Borrow<Q>,
+            Q: ?Sized + Eq + std::hash::Hash,
+        {
+            self.queue.remove(item)
+        }
+        fn get_priority<Q>(&self, item: &Q) -> Option<&i32>
+        where
+            char: std::borrow::Borrow<Q>,
+            Q: ?Sized + Eq + std::hash::Hash,
+        {
+            self.queue.get_priority(item)
+        }
+        fn len(&self) -> usize {
+            self.queue.len()
+        }
+        fn iter(&self) -> Self::Iter<'_> {
+            self.queue.iter()
+        }
+    }
+
+    #[test]
+    fn user_defined_queue() {
+        exercise(Counting {
+            queue: PriorityQueue::new(),
+            pushes: 0,
+        });
+        let mut counting = Counting {
+            queue: PriorityQueue::new(),
+            pushes: 0,
+        };
+        counting.push('x', 1);
+        counting.push('x', 2);
+        assert_eq!(counting.pushes, 2);
+    }
+}
@@ -0,0 +1,136 @@
+/*
+ *  Copyright 2017 Gianmarco Garrisi and contributors
+ *
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Lesser General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version, or (at your opinion) under the terms
+ *  of the Mozilla Public License version 2.0.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Lesser General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Lesser General Public License
+ *  along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+#[cfg(test)]
+mod weighted_reservoir_tests {
+    pub use priority_queue::WeightedReservoir;
+
+    fn lcg(seed: u64) -> impl FnMut() -> f64 {
+        let mut seed = seed;
+        move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        }
+    }
+
+    fn sorted(reservoir: WeightedReservoir<u32>) -> Vec<u32> {
+        let mut v = reservoir.into_vec();
+        v.sort_unstable();
+        v
+    }
+
+    #[test]
+    fn fills_up_to_capacity() {
+        let mut reservoir = WeightedReservoir::new(3);
+        assert!(reservoir.is_empty());
+        assert!(reservoir.insert(1, 1.0, 0.5));
+        assert!(reservoir.insert(2, 1.0, 0.5));
+        assert!(!reservoir.insert(3, 0.0, 0.5));
+        assert_eq!(reservoir.len(), 2);
+        assert!(reservoir.insert(4, 1.0, 0.2));
+        assert_eq!(reservoir.len(), 3);
+        // larger key than 4, that is evicted
+        assert!(reservoir.insert(5, 1.0, 0.3));
+        assert!(!reservoir.contains(&4));
+        assert!(!reservoir.insert(6, 1.0, 0.1));
+        assert_eq!(reservoir.capacity(), 3);
+        assert_eq!(sorted(reservoir), vec![1, 2, 5]);
+    }
+
+    #[test]
+    fn duplicate_keeps_largest_key() {
+        let mut reservoir = WeightedReservoir::new(1);
+        assert!(reservoir.insert("a", 1.0, 0.9));
+        assert!(reservoir.insert("a", 1.0, 0.1));
+        assert!(!reservoir.insert("b", 1.0, 0.8));
+        assert_eq!(reservoir.iter().collect::<Vec<_>>(), vec![&"a"]);
+        reservoir.clear();
+        assert!(reservoir.is_empty());
+    }
+
+    #[test]
+    fn inclusion_probability() {
+        let weights = [1.0, 2.0, 7.0];
+        let mut random = lcg(3);
+        let mut hits = [0usize; 3];
+        let trials = 20000;
+        for _ in 0..trials {
+            let mut reservoir = WeightedReservoir::new(1);
+            for (i, w) in weights.iter().enumerate() {
+                reservoir.insert(i, *w, random());
+            }
+            hits[*reservoir.iter().next().unwrap()] += 1;
+        }
+        for (h, w) in hits.iter().zip(&weights) {
+            let frequency = *h as f64 / trials as f64;
+            assert!((frequency - w / 10.0).abs() < 0.02, "{:?}", hits);
+        }
+    }
+
+    #[test]
+    fn heavy_items_dominate() {
+        let mut random = lcg(9);
+        let mut reservoir = WeightedReservoir::new(10);
+        for i in 0..10000u32 {
+            let weight = if i % 1000 == 0 { 1e9 } else { 1.0 };
+            reservoir.insert(i, weight, random());
+        }
+        assert_eq!(
+            sorted(reservoir),
+            (0..10).map(|i| i * 1000).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn merge_equals_single_pass() {
+        let mut random = lcg(5);
+        let stream: Vec<_> = (0..3000u32)
+            .map(|i| (i, f64::from(i % 17 + 1), random()))
+            .collect();
+
+        let mut whole = WeightedReservoir::new(25);
+        for (i, w, r) in &stream {
+            whole.insert(*i, *w, *r);
+        }
+
+        let mut shards: Vec<_> = (0..4).map(|_| WeightedReservoir::new(25)).collect();
+        for (k, (i, w, r)) in stream.iter().enumerate() {
+            shards[k % 4].insert(*i, *w, *r);
+        }
+        let mut merged = WeightedReservoir::new(25);
+        for shard in &shards {
+            merged.merge(shard);
+        }
+        assert_eq!(sorted(merged), sorted(whole));
+    }
+
+    #[test]
+    #[should_panic]
+    fn negative_weight() {
+        WeightedReservoir::new(1).insert(0, -1.0, 0.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn random_out_of_range() {
+        WeightedReservoir::new(1).insert(0, 1.0, 1.0);
+    }
+}